@@ -0,0 +1,298 @@
+/*
+env_map.h
+=========
+An environment map background from an equirectangular image.
+
+The direction of a ray that leaves the scene is converted to texture
+coordinates the same way as points on a sphere: u runs around the y-axis
+with u = 0.5 towards -z, v runs from the bottom (v = 0) to the top (v = 1).
+The top row of the image is the sky above.
+
+Bright parts of the map can be sampled as lights. A 2D CDF over the
+luminance of the pixels, weighted with the solid angle of their row, picks
+bright directions more often. pdf_value returns the matching density with
+respect to solid angle, so EnvMapPDF can be mixed with the other light pdfs.
+
+Images are read from Radiance HDR files (.hdr, RGBE pixels, flat or with
+run length encoded scanlines).
+*/
+
+#ifndef ENV_MAP_H
+#define ENV_MAP_H
+
+#include <algorithm>
+#include <cmath>
+#include <fstream>
+#include <iostream>
+#include <memory>
+#include <string>
+#include <vector>
+
+#include "background.h"
+#include "pdf.h"
+
+/**
+ * @brief      Class for environment map background.
+ */
+class EnvMap : public Background
+{
+public:
+    EnvMap() {}
+    EnvMap(const std::string &filename)
+    {
+        if (!load_hdr(filename))
+        {
+            std::cerr << "Could not load environment map: " << filename << std::endl;
+            width = height = 0;
+            pixels.clear();
+        }
+        build_distribution();
+    }
+    /**
+     * @brief      Constructs the map from pixels, row by row from the top.
+     */
+    EnvMap(int width, int height, const std::vector<Color> &pixels) : width(width), height(height), pixels(pixels)
+    {
+        build_distribution();
+    }
+
+    Color get_color(const Ray &ray) const override;
+    double pdf_value(const Vec3 &direction) const;
+    Vec3 sample_direction(double &pdf) const;
+    int get_width() const { return width; }
+    int get_height() const { return height; }
+
+private:
+    int width = 0;
+    int height = 0;
+    std::vector<Color> pixels;      // row by row from the top
+    std::vector<double> row_cdf;    // marginal distribution of the rows
+    std::vector<double> column_cdf; // distribution of the columns in each row
+    std::vector<double> weights;    // luminance times solid angle of each pixel
+    double total_weight = 0;
+
+    void pixel_of_direction(const Vec3 &direction, int &i, int &j) const;
+    void build_distribution();
+    bool load_hdr(const std::string &filename);
+};
+
+/**
+ * @brief      Finds the pixel seen in a direction.
+ * @param[in]  direction  The direction
+ * @param[out] i          The column
+ * @param[out] j          The row, counted from the top
+ */
+void EnvMap::pixel_of_direction(const Vec3 &direction, int &i, int &j) const
+{
+    Vec3 d = unit_vector(direction);
+    double phi = atan2(-d.x(), -d.z());
+    double theta = acos(clamp(-d.y(), -1.0, 1.0));
+    double u = (phi + M_PI) / (2 * M_PI);
+    double v = theta / M_PI;
+    i = std::min(static_cast<int>(u * width), width - 1);
+    j = std::min(static_cast<int>((1 - v) * height), height - 1);
+}
+
+/**
+ * @brief      Gets the color of the map in the direction of the ray.
+ * @param[in]  ray   The ray
+ * @return     The color, black if the map is empty.
+ */
+Color EnvMap::get_color(const Ray &ray) const
+{
+    if (pixels.empty())
+        return Color(0, 0, 0);
+    int i, j;
+    pixel_of_direction(ray.direction, i, j);
+    return pixels[j * width + i];
+}
+
+/**
+ * @brief      Builds the CDFs of rows and columns from the pixel weights.
+ */
+void EnvMap::build_distribution()
+{
+    weights.assign(width * height, 0.0);
+    row_cdf.assign(height, 0.0);
+    column_cdf.assign(width * height, 0.0);
+    total_weight = 0;
+    for (int j = 0; j < height; j++)
+    {
+        // rows near the poles cover less solid angle
+        double sin_theta = sin(M_PI * (j + 0.5) / height);
+        double row_weight = 0;
+        for (int i = 0; i < width; i++)
+        {
+            const Color &c = pixels[j * width + i];
+            double luminance = 0.2126 * c.r() + 0.7152 * c.g() + 0.0722 * c.b();
+            weights[j * width + i] = luminance * sin_theta;
+            row_weight += weights[j * width + i];
+            column_cdf[j * width + i] = row_weight;
+        }
+        total_weight += row_weight;
+        row_cdf[j] = total_weight;
+    }
+}
+
+/**
+ * @brief      The density of sampling a direction, with respect to solid angle.
+ * @param[in]  direction  The direction
+ * @return     The density, zero if the map is black.
+ */
+double EnvMap::pdf_value(const Vec3 &direction) const
+{
+    if (total_weight <= 0)
+        return 0;
+    int i, j;
+    pixel_of_direction(direction, i, j);
+    double sin_theta = sin(M_PI * (j + 0.5) / height);
+    double pixel_probability = weights[j * width + i] / total_weight;
+    // each pixel covers (2 pi / width) * (pi / height) * sin(theta)
+    return pixel_probability * width * height / (2 * M_PI * M_PI * sin_theta);
+}
+
+/**
+ * @brief      Samples a direction with a probability proportional to the weight
+ *             of its pixel, uniform within the pixel.
+ * @param[out] pdf   The density of the direction, see pdf_value
+ * @return     The direction.
+ */
+Vec3 EnvMap::sample_direction(double &pdf) const
+{
+    if (total_weight <= 0)
+    {
+        pdf = 0;
+        return Vec3(0, 1, 0);
+    }
+    double r = random_double() * total_weight;
+    int j = std::upper_bound(row_cdf.begin(), row_cdf.end(), r) - row_cdf.begin();
+    j = std::min(j, height - 1);
+    double row_start = j > 0 ? row_cdf[j - 1] : 0.0;
+    double row_weight = row_cdf[j] - row_start;
+    auto row = column_cdf.begin() + j * width;
+    int i = std::upper_bound(row, row + width, random_double() * row_weight) - row;
+    i = std::min(i, width - 1);
+
+    double u = (i + random_double()) / width;
+    double v = 1 - (j + random_double()) / height;
+    double phi = 2 * M_PI * u - M_PI;
+    double theta = M_PI * v;
+    Vec3 direction(-sin(theta) * sin(phi), -cos(theta), -sin(theta) * cos(phi));
+    pdf = pdf_value(direction);
+    return direction;
+}
+
+/**
+ * @brief      Loads a Radiance HDR file.
+ * @param[in]  filename  The filename
+ * @return     True if the image was loaded.
+ */
+bool EnvMap::load_hdr(const std::string &filename)
+{
+    std::ifstream in(filename, std::ios::binary);
+    if (!in.good())
+        return false;
+
+    // header lines up to an empty line, then the resolution
+    std::string line;
+    std::getline(in, line);
+    if (line.rfind("#?", 0) != 0)
+        return false;
+    while (std::getline(in, line) && !line.empty())
+    {
+        if (line.rfind("FORMAT=", 0) == 0 && line != "FORMAT=32-bit_rle_rgbe")
+            return false;
+    }
+    std::string y_axis, x_axis;
+    if (!(in >> y_axis >> height >> x_axis >> width) || y_axis != "-Y" || x_axis != "+X")
+        return false;
+    in.get();
+    if (width <= 0 || height <= 0)
+        return false;
+
+    pixels.resize(width * height);
+    std::vector<unsigned char> scanline(4 * width);
+    for (int j = 0; j < height; j++)
+    {
+        unsigned char start[4];
+        if (!in.read(reinterpret_cast<char *>(start), 4))
+            return false;
+        if (width >= 8 && width < 32768 && start[0] == 2 && start[1] == 2 && ((start[2] << 8) | start[3]) == width)
+        {
+            // run length encoded, one channel after the other
+            for (int c = 0; c < 4; c++)
+            {
+                int i = 0;
+                while (i < width)
+                {
+                    int count = in.get();
+                    if (count == EOF)
+                        return false;
+                    if (count > 128)
+                    {
+                        count -= 128;
+                        int value = in.get();
+                        if (value == EOF || i + count > width)
+                            return false;
+                        for (int k = 0; k < count; k++)
+                            scanline[4 * (i++) + c] = value;
+                    }
+                    else
+                    {
+                        if (count == 0 || i + count > width)
+                            return false;
+                        for (int k = 0; k < count; k++)
+                        {
+                            int value = in.get();
+                            if (value == EOF)
+                                return false;
+                            scanline[4 * (i++) + c] = value;
+                        }
+                    }
+                }
+            }
+        }
+        else
+        {
+            // flat pixels
+            std::copy(start, start + 4, scanline.begin());
+            if (!in.read(reinterpret_cast<char *>(scanline.data()) + 4, 4 * width - 4))
+                return false;
+        }
+
+        for (int i = 0; i < width; i++)
+        {
+            const unsigned char *rgbe = &scanline[4 * i];
+            if (rgbe[3] == 0)
+            {
+                pixels[j * width + i] = Color(0, 0, 0);
+                continue;
+            }
+            double f = ldexp(1.0, rgbe[3] - (128 + 8));
+            pixels[j * width + i] = Color((rgbe[0] + 0.5) * f, (rgbe[1] + 0.5) * f, (rgbe[2] + 0.5) * f);
+        }
+    }
+    return true;
+}
+
+/**
+ * @brief      Class for the pdf of an environment map used as light.
+ */
+class EnvMapPDF : public PDF
+{
+private:
+    std::shared_ptr<EnvMap> environment;
+
+public:
+    EnvMapPDF(std::shared_ptr<EnvMap> environment) : environment(environment) {}
+    virtual double value(const Vec3 &direction) const override
+    {
+        return environment->pdf_value(direction);
+    }
+    virtual void generate(Vec3 &direction, double &value) const override
+    {
+        direction = environment->sample_direction(value);
+    }
+};
+
+#endif // ENV_MAP_H
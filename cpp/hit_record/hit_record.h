@@ -22,6 +22,13 @@ class Material;
  *             - material: the material of the object that was hit
  *             - hit: a boolean value indicating whether the ray hit an object
  *             - u, v: the texture coordinates of the intersection point
+ *             - shading_normal: the normal used for shading, equal to the normal
+ *               unless a material perturbs it, e.g. with a normal map, or the
+ *               object interpolates it, e.g. a smooth triangle
+ *             - tangent: the direction of increasing u on the surface, zero if
+ *               the object does not provide one
+ *             - footprint: the width of the pixel at the hit in texture
+ *               coordinates, zero without ray differentials
  */
 class HitRecord
 {
@@ -36,19 +43,16 @@ public:
     }
     HitRecord(double t, const Vec3 &normal, const Vec3 &hit_point, Material *material, double u, double v)
     {
-        this->hit = true;
-        this->t = t;
-        this->normal = normal;
-        this->hit_point = hit_point;
-        this->material = material;
-        this->u = u;
-        this->v = v;
+        set(t, normal, hit_point, material, u, v);
     }
     void set(double t, const Vec3 &normal, const Vec3 &hit_point, Material *material, double u, double v)
     {
         this->hit = true;
         this->t = t;
         this->normal = normal;
+        this->shading_normal = normal;
+        this->tangent = Vec3(0, 0, 0);
+        this->footprint = 0;
         this->hit_point = hit_point;
         this->material = material;
         this->u = u;
@@ -63,6 +67,15 @@ public:
     void set_normal(const Vec3 &normal) { this->normal = normal; }
     Vec3 get_normal() const { return normal; }
 
+    void set_shading_normal(const Vec3 &shading_normal) { this->shading_normal = shading_normal; }
+    Vec3 get_shading_normal() const { return shading_normal; }
+
+    void set_tangent(const Vec3 &tangent) { this->tangent = tangent; }
+    Vec3 get_tangent() const { return tangent; }
+
+    void set_footprint(double footprint) { this->footprint = footprint; }
+    double get_footprint() const { return footprint; }
+
     void set_hit_point(const Vec3 &hit_point) { this->hit_point = hit_point; }
     Vec3 get_hit_point() const { return hit_point; }
 
@@ -111,6 +124,9 @@ private:
     bool hit;
     double t;
     Vec3 normal;
+    Vec3 shading_normal;
+    Vec3 tangent;
+    double footprint = 0;
     Vec3 hit_point;
     Ray ray;
     Material *material;
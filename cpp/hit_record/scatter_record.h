@@ -14,6 +14,8 @@ public:
     Ray specular_ray;
     Color attenuation;
     std::shared_ptr<PDF> pdf;
+    std::shared_ptr<PDF> light_pdf;    // part of pdf that samples the lights, null without lights
+    std::shared_ptr<PDF> material_pdf; // part of pdf that samples the material
 
     ScatterRecord()
     {
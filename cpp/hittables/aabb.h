@@ -6,6 +6,8 @@ Axis-aligned bounding box
 #ifndef AABB_H
 #define AABB_H
 
+#include "hit_record.h"
+
 /**
  * @brief      Class for axis-aligned bounding box.
  * @details    An AABB is a box that is aligned with the x, y, and z axes that contains an object.
@@ -25,6 +27,11 @@ public:
     Vec3 get_max() const { return maximum; }
 
     bool hit(const Ray &r, double t_min, double t_max) const;
+    bool hit(const Ray &r, double t_min, double t_max, double &t_enter) const;
+    bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec, Material *material) const;
+    double surface_area() const;
+    Vec3 centroid() const;
+    AABB pad(double delta = 0.0001) const;
 
     Vec3 minimum;
     Vec3 maximum;
@@ -58,6 +65,156 @@ inline bool AABB::hit(const Ray &r, double t_min, double t_max) const
     return true;
 }
 
+/**
+ * @brief      Determines if the ray hits the AABB and where it enters the box.
+ * @param[in]  r       The ray
+ * @param[in]  t_min   The minimum distance along the ray
+ * @param[in]  t_max   The maximum distance along the ray
+ * @param[out] t_enter The distance along the ray where it enters the box, at least t_min
+ * @return     True if the ray hits the AABB, False otherwise.
+ */
+inline bool AABB::hit(const Ray &r, double t_min, double t_max, double &t_enter) const
+{
+    for (int a = 0; a < 3; a++)
+    {
+        double invD = 1.0 / r.direction[a];
+        double t0 = (minimum[a] - r.origin[a]) * invD;
+        double t1 = (maximum[a] - r.origin[a]) * invD;
+        if (invD < 0.0)
+        {
+            std::swap(t0, t1);
+        }
+        t_min = t0 > t_min ? t0 : t_min;
+        t_max = t1 < t_max ? t1 : t_max;
+        if (t_max <= t_min)
+        {
+            return false;
+        }
+    }
+    t_enter = t_min;
+    return true;
+}
+
+/**
+ * @brief      Intersects the ray with the surface of the box, so that the box can be rendered.
+ *             The hit is where the ray enters the box, or where it leaves the box
+ *             if it starts inside. The normal points out of the face that was hit,
+ *             the texture coordinates are the position on that face, along the
+ *             same axes as on the rectangle of the same orientation.
+ *             Use the other overloads to only test for an intersection.
+ * @param[in]  r         The ray
+ * @param[in]  t_min     The minimum distance along the ray
+ * @param[in]  t_max     The maximum distance along the ray
+ * @param[out] rec       The hit record
+ * @param[in]  material  The material stored in the hit record
+ * @return     True if the ray hits the surface of the AABB, False otherwise.
+ */
+inline bool AABB::hit(const Ray &r, double t_min, double t_max, HitRecord &rec, Material *material) const
+{
+    double t_enter = -infinity;
+    double t_exit = infinity;
+    int enter_axis = 0;
+    int exit_axis = 0;
+    for (int a = 0; a < 3; a++)
+    {
+        double invD = 1.0 / r.direction[a];
+        double t0 = (minimum[a] - r.origin[a]) * invD;
+        double t1 = (maximum[a] - r.origin[a]) * invD;
+        if (invD < 0.0)
+        {
+            std::swap(t0, t1);
+        }
+        if (t0 > t_enter)
+        {
+            t_enter = t0;
+            enter_axis = a;
+        }
+        if (t1 < t_exit)
+        {
+            t_exit = t1;
+            exit_axis = a;
+        }
+    }
+    if (t_exit <= t_enter)
+    {
+        return false;
+    }
+
+    double t;
+    int axis;
+    Vec3 normal(0, 0, 0);
+    if (t_enter >= t_min && t_enter <= t_max)
+    {
+        // entering face, facing against the ray
+        t = t_enter;
+        axis = enter_axis;
+        normal[axis] = r.direction[axis] > 0 ? -1 : 1;
+    }
+    else if (t_enter < t_min && t_exit >= t_min && t_exit <= t_max)
+    {
+        // ray starts inside, leaving face, facing along the ray
+        t = t_exit;
+        axis = exit_axis;
+        normal[axis] = r.direction[axis] > 0 ? 1 : -1;
+    }
+    else
+    {
+        return false;
+    }
+
+    Vec3 p = r.point_at_parameter(t);
+    // u along x, or y on the faces normal to x, v along the remaining axis
+    int axis_u = axis == 0 ? 1 : 0;
+    int axis_v = axis == 2 ? 1 : 2;
+    double u = (p[axis_u] - minimum[axis_u]) / (maximum[axis_u] - minimum[axis_u]);
+    double v = (p[axis_v] - minimum[axis_v]) / (maximum[axis_v] - minimum[axis_v]);
+    rec.set(t, normal, p, material, u, v);
+    Vec3 tangent(0, 0, 0);
+    tangent[axis_u] = 1;
+    rec.set_tangent(tangent);
+    return true;
+}
+
+/**
+ * @brief      Computes the surface area of the AABB.
+ * @return     The surface area.
+ */
+inline double AABB::surface_area() const
+{
+    Vec3 extent = maximum - minimum;
+    return 2 * (extent.x() * extent.y() + extent.y() * extent.z() + extent.z() * extent.x());
+}
+
+/**
+ * @brief      The center of the AABB.
+ * @return     The point halfway between the minimum and the maximum.
+ */
+inline Vec3 AABB::centroid() const
+{
+    return 0.5 * (minimum + maximum);
+}
+
+/**
+ * @brief      Widens the axes of the AABB that are thinner than delta, so that
+ *             flat objects get a box with non-zero volume.
+ * @param[in]  delta  The minimum thickness, thinner axes are widened by delta on both sides
+ * @return     The padded AABB.
+ */
+inline AABB AABB::pad(double delta) const
+{
+    Vec3 padded_minimum = minimum;
+    Vec3 padded_maximum = maximum;
+    for (int a = 0; a < 3; a++)
+    {
+        if (maximum[a] - minimum[a] < delta)
+        {
+            padded_minimum[a] -= delta;
+            padded_maximum[a] += delta;
+        }
+    }
+    return AABB(padded_minimum, padded_maximum);
+}
+
 /**
  * @brief      Constructs an AABB that contains two AABBs.
  * @param[in]  box0  The first AABB
@@ -66,13 +223,7 @@ inline bool AABB::hit(const Ray &r, double t_min, double t_max) const
  */
 AABB surrounding_box(AABB box0, AABB box1)
 {
-    Vec3 small(fmin(box0.minimum.x(), box1.minimum.x()),
-               fmin(box0.minimum.y(), box1.minimum.y()),
-               fmin(box0.minimum.z(), box1.minimum.z()));
-    Vec3 big(fmax(box0.maximum.x(), box1.maximum.x()),
-             fmax(box0.maximum.y(), box1.maximum.y()),
-             fmax(box0.maximum.z(), box1.maximum.z()));
-    return AABB(small, big);
+    return AABB(Vec3::min(box0.minimum, box1.minimum), Vec3::max(box0.maximum, box1.maximum));
 }
 
 #endif
\ No newline at end of file
@@ -8,14 +8,16 @@
  * @param[in]  end      The end index
  * @param[in]  time0    The time 0
  * @param[in]  time1    The time 1
+ * @param[in]  split_method  The method used to split the objects into the child nodes
  */
-BVHNode::BVHNode(std::vector<std::shared_ptr<Hittable>> &objects, size_t start, size_t end, double time0, double time1)
+BVHNode::BVHNode(std::vector<std::shared_ptr<Hittable>> &objects, size_t start, size_t end, double time0, double time1, SplitMethod split_method)
 {
     int axis = random_int(0, 2);
     auto comparator = (axis == 0)   ? box_x_compare
                       : (axis == 1) ? box_y_compare
                                     : box_z_compare;
     size_t object_span = end - start;
+    leaf = object_span <= 2;
 
     if (object_span == 1)
     {
@@ -34,44 +36,156 @@ BVHNode::BVHNode(std::vector<std::shared_ptr<Hittable>> &objects, size_t start,
             right = objects[start];
         }
     }
+    else if (split_method == SplitMethod::SurfaceAreaHeuristic && object_span > 4)
+    {
+        auto mid = surface_area_split(objects, start, end, time0, time1);
+        build_children(objects, start, mid, end, time0, time1, split_method);
+    }
     else
     {
         std::sort(objects.begin() + start, objects.begin() + end, comparator);
 
         auto mid = start + object_span / 2;
-        left = std::make_shared<BVHNode>(objects, start, mid, time0, time1);
-        right = std::make_shared<BVHNode>(objects, mid, end, time0, time1);
+        build_children(objects, start, mid, end, time0, time1, split_method);
     }
 
-    AABB box_left, box_right;
-
-    if (!left->bounding_box(time0, time1, box_left) || !right->bounding_box(time0, time1, box_right))
+    if (!left->bounding_box(time0, time1, left_box) || !right->bounding_box(time0, time1, right_box))
     {
         std::cerr << "No bounding box in BVHNode constructor." << std::endl;
     }
 
-    box = surrounding_box(box_left, box_right);
+    box = surrounding_box(left_box, right_box);
+}
+
+/**
+ * @brief      Builds the child nodes for the objects in [start, mid) and [mid, end).
+ *             Above bvh_parallel_threshold objects the left child is built in an
+ *             OpenMP task. Both children only sort their own range of the objects,
+ *             so they can be built at the same time.
+ * @param[in]  objects  The objects
+ * @param[in]  start    The start index
+ * @param[in]  mid      The first index of the right child
+ * @param[in]  end      The end index
+ * @param[in]  time0    The time 0
+ * @param[in]  time1    The time 1
+ * @param[in]  split_method  The method used to split the objects into the child nodes
+ */
+void BVHNode::build_children(std::vector<std::shared_ptr<Hittable>> &objects, size_t start, size_t mid, size_t end, double time0, double time1, SplitMethod split_method)
+{
+    if (end - start > bvh_parallel_threshold)
+    {
+        #pragma omp task shared(objects)
+        left = std::make_shared<BVHNode>(objects, start, mid, time0, time1, split_method);
+        right = std::make_shared<BVHNode>(objects, mid, end, time0, time1, split_method);
+        #pragma omp taskwait
+    }
+    else
+    {
+        left = std::make_shared<BVHNode>(objects, start, mid, time0, time1, split_method);
+        right = std::make_shared<BVHNode>(objects, mid, end, time0, time1, split_method);
+    }
+}
+
+/**
+ * @brief      Builds a BVH over all objects, using all threads for large scenes.
+ *             Without OpenMP the tree is built serially.
+ * @param[in]  objects  The objects, reordered while building
+ * @param[in]  time0    The time 0
+ * @param[in]  time1    The time 1
+ * @param[in]  split_method  The method used to split the objects into the child nodes
+ * @return     The root node.
+ */
+std::shared_ptr<BVHNode> build_bvh(std::vector<std::shared_ptr<Hittable>> &objects, double time0, double time1, SplitMethod split_method)
+{
+    std::shared_ptr<BVHNode> root;
+    #pragma omp parallel
+    #pragma omp single
+    root = std::make_shared<BVHNode>(objects, 0, objects.size(), time0, time1, split_method);
+    return root;
 }
 
 /**
- * @brief      Checks if the ray hits the bounding box of the node.
+ * @brief      Checks if the ray hits the objects in the node.
+ *             Only the box of this node is tested here, the boxes of the
+ *             nodes below are tested by their parents, see hit_children.
  * @param[in]  r       The ray
  * @param[in]  t_min   The minimum parameter value
  * @param[in]  t_max   The maximum parameter value
  * @param[out] rec     The hit record
- * @return     True if the ray hits the BVH node bounding box, False otherwise.
+ * @return     True if the ray hits an object in the node, False otherwise.
  */
 bool BVHNode::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
 {
     if (!box.hit(r, t_min, t_max))
     {
+        RenderCounters::count(render_counters().bvh_node_visits);
         return false;
     }
+    return hit_children(r, t_min, t_max, rec);
+}
 
-    bool hit_left = left->hit(r, t_min, t_max, rec);
-    bool hit_right = right->hit(r, t_min, hit_left ? rec.get_t() : t_max, rec);
+/**
+ * @brief      Checks if the ray hits the objects in the node, whose box the ray
+ *             is known to hit.
+ *             The child whose bounding box the ray enters first is tested first.
+ *             The other child is skipped if the ray enters its box only behind the closest hit.
+ * @param[in]  r       The ray
+ * @param[in]  t_min   The minimum parameter value
+ * @param[in]  t_max   The maximum parameter value
+ * @param[out] rec     The hit record
+ * @return     True if the ray hits an object in the node, False otherwise.
+ */
+bool BVHNode::hit_children(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    RenderCounters::count(render_counters().bvh_node_visits);
+    if (left == right)
+    {
+        RenderCounters::count(render_counters().primitive_tests);
+        return left->hit(r, t_min, t_max, rec);
+    }
+
+    double t_left, t_right;
+    bool hit_left_box = left_box.hit(r, t_min, t_max, t_left);
+    bool hit_right_box = right_box.hit(r, t_min, t_max, t_right);
 
-    return hit_left || hit_right;
+    // order children by entry distance
+    const Hittable *first = left.get();
+    const Hittable *second = right.get();
+    bool hit_first_box = hit_left_box;
+    bool hit_second_box = hit_right_box;
+    double t_second = t_right;
+    if (hit_right_box && (!hit_left_box || t_right < t_left))
+    {
+        std::swap(first, second);
+        std::swap(hit_first_box, hit_second_box);
+        t_second = t_left;
+    }
+
+    bool hit_first = false;
+    if (hit_first_box)
+    {
+        if (leaf)
+        {
+            RenderCounters::count(render_counters().primitive_tests);
+            hit_first = first->hit(r, t_min, t_max, rec);
+        }
+        else
+            hit_first = static_cast<const BVHNode *>(first)->hit_children(r, t_min, t_max, rec);
+    }
+    double closest_so_far = hit_first ? rec.get_t() : t_max;
+    bool hit_second = false;
+    if (hit_second_box && t_second < closest_so_far)
+    {
+        if (leaf)
+        {
+            RenderCounters::count(render_counters().primitive_tests);
+            hit_second = second->hit(r, t_min, closest_so_far, rec);
+        }
+        else
+            hit_second = static_cast<const BVHNode *>(second)->hit_children(r, t_min, closest_so_far, rec);
+    }
+
+    return hit_first || hit_second;
 }
 
 /**
@@ -88,11 +202,13 @@ bool BVHNode::bounding_box(double time0, double time1, AABB &output_box) const
 }
 
 /**
- * @brief      Compares the bounding boxes of two objects.
+ * @brief      Compares the centroids of the bounding boxes of two objects.
+ *             Sorting by the minimum instead would order a large object before
+ *             a small one that lies mostly in front of it.
  * @param[in]  a      The first object
  * @param[in]  b      The second object
  * @param[in]  axis   The axis
- * @return     True if the bounding box of the first object is smaller than the bounding box of the second object, False otherwise.
+ * @return     True if the centroid of the first object is smaller than the centroid of the second object, False otherwise.
  */
 bool box_compare(const std::shared_ptr<Hittable> a, const std::shared_ptr<Hittable> b, int axis)
 {
@@ -104,7 +220,7 @@ bool box_compare(const std::shared_ptr<Hittable> a, const std::shared_ptr<Hittab
         std::cerr << "No bounding box in bvh_node constructor." << std::endl;
     }
 
-    return box_a.get_min().e[axis] < box_b.get_min().e[axis];
+    return box_a.centroid()[axis] < box_b.centroid()[axis];
 }
 
 /**
@@ -140,3 +256,76 @@ bool box_z_compare(const std::shared_ptr<Hittable> a, const std::shared_ptr<Hitt
 {
     return box_compare(a, b, 2);
 }
+
+/**
+ * @brief      Sorts the objects along the axis with the cheapest split and returns the split index.
+ *             For every axis the objects are sorted by the centroids of their bounding boxes.
+ *             The cost of splitting after the i-th object is estimated with the surface area
+ *             heuristic: area(left) * count(left) + area(right) * count(right).
+ * @param      objects  The objects
+ * @param[in]  start    The start index
+ * @param[in]  end      The end index
+ * @param[in]  time0    The time 0
+ * @param[in]  time1    The time 1
+ * @return     The index of the first object of the right child.
+ */
+size_t surface_area_split(std::vector<std::shared_ptr<Hittable>> &objects, size_t start, size_t end, double time0, double time1)
+{
+    size_t object_span = end - start;
+    double best_cost = infinity;
+    int best_axis = 0;
+    size_t best_split = object_span / 2;
+
+    for (int axis = 0; axis < 3; axis++)
+    {
+        auto centroid_compare = [axis, time0, time1](const std::shared_ptr<Hittable> a, const std::shared_ptr<Hittable> b)
+        {
+            AABB box_a;
+            AABB box_b;
+            a->bounding_box(time0, time1, box_a);
+            b->bounding_box(time0, time1, box_b);
+            return box_a.centroid()[axis] < box_b.centroid()[axis];
+        };
+        std::sort(objects.begin() + start, objects.begin() + end, centroid_compare);
+
+        // areas of the boxes around the first i objects and around the last i objects
+        std::vector<double> left_area(object_span);
+        std::vector<double> right_area(object_span);
+        AABB box;
+        AABB accumulated;
+        for (size_t i = 0; i < object_span; i++)
+        {
+            objects[start + i]->bounding_box(time0, time1, box);
+            accumulated = (i == 0) ? box : surrounding_box(accumulated, box);
+            left_area[i] = accumulated.surface_area();
+        }
+        for (size_t i = 0; i < object_span; i++)
+        {
+            objects[end - 1 - i]->bounding_box(time0, time1, box);
+            accumulated = (i == 0) ? box : surrounding_box(accumulated, box);
+            right_area[object_span - 1 - i] = accumulated.surface_area();
+        }
+
+        for (size_t split = 1; split < object_span; split++)
+        {
+            double cost = left_area[split - 1] * split + right_area[split] * (object_span - split);
+            if (cost < best_cost)
+            {
+                best_cost = cost;
+                best_axis = axis;
+                best_split = split;
+            }
+        }
+    }
+
+    auto centroid_compare = [best_axis, time0, time1](const std::shared_ptr<Hittable> a, const std::shared_ptr<Hittable> b)
+    {
+        AABB box_a;
+        AABB box_b;
+        a->bounding_box(time0, time1, box_a);
+        b->bounding_box(time0, time1, box_b);
+        return box_a.centroid()[best_axis] < box_b.centroid()[best_axis];
+    };
+    std::sort(objects.begin() + start, objects.begin() + end, centroid_compare);
+    return start + best_split;
+}
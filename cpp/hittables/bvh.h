@@ -10,7 +10,8 @@ into two groups based on the median of the longest axis.
 The tree is traversed by checking if the ray hits the bounding box of the node.
 If the ray hits the bounding box, then the ray is recursively checked against
 the left and right child nodes. If the ray does not hit the bounding box, then
-the ray does not hit any of the objects in the node.
+the ray does not hit any of the objects in the node. Every node keeps the boxes
+of its children, so each box is tested once, by the parent of its node.
 */
 
 #ifndef BVG_H
@@ -22,6 +23,25 @@ the ray does not hit any of the objects in the node.
 
 #include "hittable.h"
 #include "aabb.h"
+#include "render_stats.h"
+
+/**
+ * @brief      Method used to split the objects of a node.
+ * @details    Median: split at the median along a random axis.
+ *             SurfaceAreaHeuristic: split where the estimated traversal cost,
+ *             the surface area of each child times its number of objects, is lowest.
+ */
+enum class SplitMethod
+{
+    Median,
+    SurfaceAreaHeuristic
+};
+
+/**
+ * @brief      Number of objects above which the children of a node are built
+ *             in parallel tasks, see build_bvh.
+ */
+const size_t bvh_parallel_threshold = 10000;
 
 /**
  * @brief      Class for bounding volume hierarchy node.
@@ -30,20 +50,30 @@ class BVHNode : public Hittable
 {
 public:
     BVHNode() {}
-    BVHNode(std::vector<std::shared_ptr<Hittable>> &objects, size_t start, size_t end, double time0, double time1);
+    BVHNode(std::vector<std::shared_ptr<Hittable>> &objects, size_t start, size_t end, double time0, double time1, SplitMethod split_method = SplitMethod::Median);
 
     virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
     virtual bool bounding_box(double time0, double time1, AABB &output_box) const override;
     std::string to_string() const { return "BVHNode"; }
+private:
+    bool hit_children(const Ray &r, double t_min, double t_max, HitRecord &rec) const;
+    void build_children(std::vector<std::shared_ptr<Hittable>> &objects, size_t start, size_t mid, size_t end, double time0, double time1, SplitMethod split_method);
+
 public:
     std::shared_ptr<Hittable> left;
     std::shared_ptr<Hittable> right;
     AABB box;
+    AABB left_box;
+    AABB right_box;
+    bool leaf = false; // the children are objects, not nodes
 };
 
+std::shared_ptr<BVHNode> build_bvh(std::vector<std::shared_ptr<Hittable>> &objects, double time0, double time1, SplitMethod split_method = SplitMethod::Median);
+
 bool box_compare(const std::shared_ptr<Hittable> a, const std::shared_ptr<Hittable> b, int axis);
 bool box_x_compare(const std::shared_ptr<Hittable> a, const std::shared_ptr<Hittable> b);
 bool box_y_compare(const std::shared_ptr<Hittable> a, const std::shared_ptr<Hittable> b);
 bool box_z_compare(const std::shared_ptr<Hittable> a, const std::shared_ptr<Hittable> b);
+size_t surface_area_split(std::vector<std::shared_ptr<Hittable>> &objects, size_t start, size_t end, double time0, double time1);
 
 #endif // BVG_H
\ No newline at end of file
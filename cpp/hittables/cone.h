@@ -0,0 +1,190 @@
+/*
+Cone.h
+======
+
+The Cone class represents a cone with its tip at the apex, opening along
+the axis with the given half angle. The cone ends at the given height
+along the axis, where it can be closed with a cap. Only the half of the
+double cone on the side of the axis is hit.
+*/
+
+#ifndef CONE_H
+#define CONE_H
+
+#include <cmath>
+
+#include "hittable.h"
+#include "vec3.h"
+#include "onb.h"
+#include "ray.h"
+#include "hit_record.h"
+
+/**
+ * @brief      Class for cone.
+ */
+class Cone : public Hittable
+{
+public:
+    Cone() {}
+    Cone(const Vec3 &_apex, const Vec3 &_axis, double _half_angle, double _height, Material *m, bool _capped = true)
+        : apex(_apex), axis(unit_vector(_axis)), half_angle(_half_angle), height(_height), material(m), capped(_capped)
+    {
+        uvw.build_from_w(axis);
+        double radians = degrees_to_radians(half_angle);
+        cos_squared = cos(radians) * cos(radians);
+        radius = height * tan(radians);
+    }
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override;
+    std::string to_string() const { return "Cone"; }
+
+public:
+    Vec3 apex;
+    Vec3 axis;          // unit vector from the apex towards the base
+    double half_angle;  // in degrees
+    double height;
+    Material *material;
+    bool capped;
+
+private:
+    ONB uvw;
+    double cos_squared;
+    double radius;      // radius of the base
+    bool hit_side(const Ray &r, double t_min, double t_max, HitRecord &rec) const;
+    bool hit_cap(const Ray &r, double t_min, double t_max, HitRecord &rec) const;
+};
+
+/**
+ * @brief     Check if a ray hits the cone.
+ *            The side and, if the cone is capped, the base are tested
+ *            and the nearest hit is kept.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Cone::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    bool hit_anything = hit_side(r, t_min, t_max, rec);
+    double closest_so_far = hit_anything ? rec.get_t() : t_max;
+    if (capped && hit_cap(r, t_min, closest_so_far, rec))
+        hit_anything = true;
+    return hit_anything;
+}
+
+/**
+ * @brief     Check if a ray hits the side of the cone.
+ *            A point q relative to the apex is on the double cone if
+ *            dot(q, axis)^2 = cos^2(half angle) * |q|^2. The quadratic along
+ *            the ray is solved and only hits between the apex and the base
+ *            are accepted. The normal is perpendicular to the slant.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Cone::hit_side(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    Vec3 co = r.origin - apex;
+    double d_axis = dot(r.direction, axis);
+    double co_axis = dot(co, axis);
+
+    double a = d_axis * d_axis - cos_squared * r.direction.length_squared();
+    double half_b = d_axis * co_axis - cos_squared * dot(r.direction, co);
+    double c = co_axis * co_axis - cos_squared * co.length_squared();
+
+    double roots[2];
+    int number_of_roots;
+    if (fabs(a) < 1e-12)
+    {
+        // ray parallel to the slant, a single hit
+        if (fabs(half_b) < 1e-12)
+            return false;
+        roots[0] = -c / (2 * half_b);
+        number_of_roots = 1;
+    }
+    else
+    {
+        double discriminant = half_b * half_b - a * c;
+        if (discriminant < 0)
+            return false;
+        double sqrt_discriminant = sqrt(discriminant);
+        double t0 = (-half_b - sqrt_discriminant) / a;
+        double t1 = (-half_b + sqrt_discriminant) / a;
+        roots[0] = fmin(t0, t1);
+        roots[1] = fmax(t0, t1);
+        number_of_roots = 2;
+    }
+
+    for (int k = 0; k < number_of_roots; k++)
+    {
+        double t = roots[k];
+        if (t < t_min || t > t_max)
+            continue;
+        Vec3 p = r.point_at_parameter(t);
+        Vec3 q = p - apex;
+        double h = dot(q, axis);
+        if (h <= 0 || h > height)
+            continue;
+
+        Vec3 normal = unit_vector(cos_squared * q - h * axis);
+        double u = (atan2(dot(q, uvw.v()), dot(q, uvw.u())) + M_PI) / (2 * M_PI);
+        double v = h / height;
+        rec.set(t, normal, p, material, u, v);
+        rec.set_tangent(unit_vector(cross(axis, q)));
+        return true;
+    }
+    return false;
+}
+
+/**
+ * @brief     Check if a ray hits the base of the cone.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Cone::hit_cap(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    double d_axis = dot(r.direction, axis);
+    if (fabs(d_axis) < 1e-12)
+        // ray parallel to the base
+        return false;
+    Vec3 center = apex + height * axis;
+    double t = dot(center - r.origin, axis) / d_axis;
+    if (t < t_min || t > t_max)
+        return false;
+    Vec3 p = r.point_at_parameter(t);
+    Vec3 offset = p - center;
+    if (offset.length_squared() > radius * radius)
+        return false;
+    double u = 0.5 * (dot(offset, uvw.u()) / radius + 1);
+    double v = 0.5 * (dot(offset, uvw.v()) / radius + 1);
+    rec.set(t, axis, p, material, u, v);
+    rec.set_tangent(uvw.u());
+    return true;
+}
+
+/**
+ * @brief     Get the bounding box of the cone, enclosing the apex and the base.
+ * @param[in] time0 Start time.
+ * @param[in] time1 End time.
+ * @param[out] output_box Bounding box.
+ * @return    True if bounding box is valid.
+ */
+bool Cone::bounding_box(double time0, double time1, AABB &output_box) const
+{
+    // extent of the base circle along each coordinate axis
+    Vec3 center = apex + height * axis;
+    Vec3 extent(radius * sqrt(fmax(0.0, 1 - axis.x() * axis.x())),
+                radius * sqrt(fmax(0.0, 1 - axis.y() * axis.y())),
+                radius * sqrt(fmax(0.0, 1 - axis.z() * axis.z())));
+    Vec3 padding(0.0001, 0.0001, 0.0001);
+    output_box = AABB(Vec3::min(apex, center - extent) - padding, Vec3::max(apex, center + extent) + padding);
+    return true;
+}
+
+#endif // CONE_H
@@ -0,0 +1,96 @@
+/*
+Constant Medium
+===============
+A volume of constant density, such as fog or smoke, enclosed by a boundary.
+
+A ray travelling through the volume is scattered with a probability that
+grows with the distance it travels inside the boundary.
+*/
+
+#ifndef CONSTANT_MEDIUM_H
+#define CONSTANT_MEDIUM_H
+
+#include <cmath>
+#include <memory>
+
+#include "hittable.h"
+#include "hit_record.h"
+#include "isotropic.h"
+
+/**
+ * @brief      Class for constant medium.
+ */
+class ConstantMedium : public Hittable
+{
+public:
+    ConstantMedium(std::shared_ptr<Hittable> boundary, double density, Material *phase_function)
+        : boundary(boundary), neg_inv_density(-1 / density), phase_function(phase_function)
+    {
+        set_material(phase_function);
+    }
+    ConstantMedium(std::shared_ptr<Hittable> boundary, double density, const Color &color)
+        : boundary(boundary), neg_inv_density(-1 / density), owned_phase_function(std::make_shared<Isotropic>(color)),
+          phase_function(owned_phase_function.get())
+    {
+        set_material(phase_function);
+    }
+
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override
+    {
+        return boundary->bounding_box(time0, time1, output_box);
+    }
+    std::string to_string() const { return "ConstantMedium"; }
+
+public:
+    std::shared_ptr<Hittable> boundary;
+    double neg_inv_density;
+    std::shared_ptr<Material> owned_phase_function; // null if the phase function is passed in
+    Material *phase_function;
+};
+
+/**
+ * @brief      Determines if the ray scatters inside the medium.
+ *             The entry and exit points of the boundary are found first. The
+ *             distance to the scattering event is drawn from an exponential
+ *             distribution; if it lies beyond the exit point, the ray passes through.
+ *             The boundary is assumed to be convex.
+ * @param[in]  r      The ray
+ * @param[in]  t_min  The minimum parameter
+ * @param[in]  t_max  The maximum parameter
+ * @param[out] rec    The hit record
+ * @return     True if the ray scatters inside the medium, False otherwise.
+ */
+bool ConstantMedium::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    HitRecord rec1, rec2;
+
+    if (!boundary->hit(r, -infinity, infinity, rec1))
+        return false;
+
+    if (!boundary->hit(r, rec1.get_t() + 0.0001, infinity, rec2))
+        return false;
+
+    double t1 = fmax(rec1.get_t(), t_min);
+    double t2 = fmin(rec2.get_t(), t_max);
+
+    if (t1 >= t2)
+        return false;
+
+    if (t1 < 0)
+        t1 = 0;
+
+    double ray_length = r.direction.length();
+    double distance_inside_boundary = (t2 - t1) * ray_length;
+    double hit_distance = neg_inv_density * log(random_double());
+
+    if (hit_distance > distance_inside_boundary)
+        return false;
+
+    double t = t1 + hit_distance / ray_length;
+    // normal and texture coordinates are arbitrary inside a volume
+    rec.set(t, Vec3(1, 0, 0), r.point_at_parameter(t), phase_function, 0, 0);
+    return true;
+}
+
+#endif // CONSTANT_MEDIUM_H
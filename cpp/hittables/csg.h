@@ -0,0 +1,186 @@
+/*
+csg.h
+=====
+
+The Csg class combines two closed hittables with a boolean operation,
+constructive solid geometry:
+- Union: inside of either object
+- Intersection: inside of both objects
+- Difference: inside of the first object but not of the second
+
+To find where the ray enters and leaves each object, the object is hit
+repeatedly along the whole ray, starting behind each previous hit, as
+ConstantMedium does for its boundary. A hit on the front face enters the
+object, a hit on the back face leaves it. The result is hit where the
+ray changes from outside to inside the combination or back. Surfaces
+carved out by the second object of a difference keep its material, with
+the normal pointing into the carved object.
+*/
+
+#ifndef CSG_H
+#define CSG_H
+
+#include <vector>
+#include <memory>
+#include <algorithm>
+
+#include "hittable.h"
+#include "vec3.h"
+#include "ray.h"
+#include "hit_record.h"
+#include "aabb.h"
+
+/**
+ * @brief      Boolean operation of a Csg object.
+ */
+enum class CsgOperation
+{
+    Union,
+    Intersection,
+    Difference
+};
+
+/**
+ * @brief      A point where a ray crosses the surface of a Csg operand.
+ */
+struct CsgCrossing
+{
+    HitRecord rec;
+    bool entering;
+    bool from_right;
+};
+
+/**
+ * @brief      Class for constructive solid geometry.
+ */
+class Csg : public Hittable
+{
+public:
+    Csg() {}
+    Csg(std::shared_ptr<Hittable> _left, std::shared_ptr<Hittable> _right, CsgOperation _operation)
+        : left(_left), right(_right), operation(_operation) {}
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override;
+    std::string to_string() const { return "Csg"; }
+
+public:
+    std::shared_ptr<Hittable> left;
+    std::shared_ptr<Hittable> right;
+    CsgOperation operation;
+
+private:
+    bool is_inside(bool inside_left, bool inside_right) const;
+};
+
+/**
+ * @brief     Collect all crossings of the ray with the surface of an object, in order.
+ * @param[in] object The object, must be closed.
+ * @param[in] r Ray to check.
+ * @param[in] from_right Whether the object is the right operand.
+ * @param[out] crossings Crossings, appended.
+ */
+inline void csg_crossings(const Hittable &object, const Ray &r, bool from_right, std::vector<CsgCrossing> &crossings)
+{
+    // guards against objects that report the same hit again and again
+    const int max_crossings = 64;
+    double t = -infinity;
+    HitRecord rec;
+    for (int k = 0; k < max_crossings && object.hit(r, t, infinity, rec); k++)
+    {
+        crossings.push_back({rec, rec.is_front_face(r), from_right});
+        t = rec.get_t() + 0.0001;
+    }
+}
+
+/**
+ * @brief     Check if a point is inside the combination.
+ * @param[in] inside_left Whether the point is inside the left object.
+ * @param[in] inside_right Whether the point is inside the right object.
+ * @return    True if inside.
+ */
+bool Csg::is_inside(bool inside_left, bool inside_right) const
+{
+    switch (operation)
+    {
+    case CsgOperation::Union:
+        return inside_left || inside_right;
+    case CsgOperation::Intersection:
+        return inside_left && inside_right;
+    default:
+        return inside_left && !inside_right;
+    }
+}
+
+/**
+ * @brief     Check if a ray hits the combination.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Csg::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    std::vector<CsgCrossing> crossings;
+    csg_crossings(*left, r, false, crossings);
+    csg_crossings(*right, r, true, crossings);
+    std::sort(crossings.begin(), crossings.end(), [](const CsgCrossing &a, const CsgCrossing &b)
+              { return a.rec < b.rec; });
+
+    bool inside_left = false;
+    bool inside_right = false;
+    bool inside = false;
+    for (const CsgCrossing &crossing : crossings)
+    {
+        if (crossing.from_right)
+            inside_right = crossing.entering;
+        else
+            inside_left = crossing.entering;
+
+        bool was_inside = inside;
+        inside = is_inside(inside_left, inside_right);
+        double t = crossing.rec.get_t();
+        if (inside == was_inside || t < t_min)
+            continue;
+        if (t > t_max)
+            return false;
+
+        rec = crossing.rec;
+        if (crossing.from_right && operation == CsgOperation::Difference)
+        {
+            // the carved surface faces into the removed object
+            rec.set_normal(-rec.get_normal());
+            rec.set_shading_normal(-rec.get_shading_normal());
+        }
+        return true;
+    }
+    return false;
+}
+
+/**
+ * @brief     Get the bounding box of the combination.
+ * @param[in] time0 Start time.
+ * @param[in] time1 End time.
+ * @param[out] output_box Bounding box.
+ * @return    True if bounding box is valid.
+ */
+bool Csg::bounding_box(double time0, double time1, AABB &output_box) const
+{
+    AABB left_box, right_box;
+    if (!left->bounding_box(time0, time1, left_box))
+        return false;
+    if (operation == CsgOperation::Difference)
+    {
+        output_box = left_box;
+        return true;
+    }
+    if (!right->bounding_box(time0, time1, right_box))
+        return false;
+    if (operation == CsgOperation::Union)
+        output_box = surrounding_box(left_box, right_box);
+    else
+        output_box = AABB(Vec3::max(left_box.minimum, right_box.minimum), Vec3::min(left_box.maximum, right_box.maximum));
+    return true;
+}
+
+#endif // CSG_H
@@ -0,0 +1,165 @@
+/*
+Cylinder.h
+==========
+
+The Cylinder class represents a cylinder standing on a base center and
+extending upwards along the y-axis. The cylinder can be open or closed
+with caps at both ends.
+*/
+
+#ifndef CYLINDER_H
+#define CYLINDER_H
+
+#include <cmath>
+
+#include "hittable.h"
+#include "vec3.h"
+#include "ray.h"
+#include "hit_record.h"
+
+/**
+ * @brief      Class for cylinder.
+ */
+class Cylinder : public Hittable
+{
+public:
+    Cylinder() {}
+    Cylinder(const Vec3 &_base, double _height, double _radius, Material *m, bool _capped = true)
+        : base(_base), height(_height), radius(_radius), material(m), capped(_capped) {}
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override;
+    std::string to_string() const { return "Cylinder"; }
+
+public:
+    Vec3 base;
+    double height;
+    double radius;
+    Material *material;
+    bool capped;
+
+private:
+    bool hit_side(const Ray &r, double t_min, double t_max, HitRecord &rec) const;
+    bool hit_cap(const Ray &r, double y, const Vec3 &normal, double t_min, double t_max, HitRecord &rec) const;
+};
+
+/**
+ * @brief     Check if a ray hits the cylinder.
+ *            The side and, if the cylinder is capped, both caps are tested
+ *            and the nearest hit is kept.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Cylinder::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    bool hit_anything = hit_side(r, t_min, t_max, rec);
+    double closest_so_far = hit_anything ? rec.get_t() : t_max;
+    if (capped)
+    {
+        if (hit_cap(r, base.y(), Vec3(0, -1, 0), t_min, closest_so_far, rec))
+        {
+            hit_anything = true;
+            closest_so_far = rec.get_t();
+        }
+        if (hit_cap(r, base.y() + height, Vec3(0, 1, 0), t_min, closest_so_far, rec))
+        {
+            hit_anything = true;
+        }
+    }
+    return hit_anything;
+}
+
+/**
+ * @brief     Check if a ray hits the side of the cylinder.
+ *            Solves the quadratic for the infinite cylinder and only accepts
+ *            hits between the bottom and the top.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Cylinder::hit_side(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    double ox = r.origin.x() - base.x();
+    double oz = r.origin.z() - base.z();
+    double dx = r.direction.x();
+    double dz = r.direction.z();
+
+    double a = dx * dx + dz * dz;
+    if (a < 1e-12)
+        // ray parallel to the axis
+        return false;
+    double half_b = ox * dx + oz * dz;
+    double c = ox * ox + oz * oz - radius * radius;
+    double discriminant = half_b * half_b - a * c;
+    if (discriminant < 0)
+        return false;
+
+    double sqrt_discriminant = sqrt(discriminant);
+    double roots[2] = {(-half_b - sqrt_discriminant) / a, (-half_b + sqrt_discriminant) / a};
+    for (double t : roots)
+    {
+        if (t < t_min || t > t_max)
+            continue;
+        Vec3 p = r.point_at_parameter(t);
+        double y = p.y() - base.y();
+        if (y < 0 || y > height)
+            continue;
+
+        Vec3 normal = Vec3(p.x() - base.x(), 0, p.z() - base.z()) / radius;
+        double u = (atan2(normal.z(), normal.x()) + M_PI) / (2 * M_PI);
+        double v = y / height;
+        rec.set(t, normal, p, material, u, v);
+        rec.set_tangent(Vec3(-normal.z(), 0, normal.x()));
+        return true;
+    }
+    return false;
+}
+
+/**
+ * @brief     Check if a ray hits one of the caps of the cylinder.
+ * @param[in] r Ray to check.
+ * @param[in] y Height of the cap.
+ * @param[in] normal Outward normal of the cap.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Cylinder::hit_cap(const Ray &r, double y, const Vec3 &normal, double t_min, double t_max, HitRecord &rec) const
+{
+    if (fabs(r.direction.y()) < 1e-12)
+        // ray parallel to the cap
+        return false;
+    double t = (y - r.origin.y()) / r.direction.y();
+    if (t < t_min || t > t_max)
+        return false;
+    Vec3 p = r.point_at_parameter(t);
+    double x = p.x() - base.x();
+    double z = p.z() - base.z();
+    if (x * x + z * z > radius * radius)
+        return false;
+    double u = 0.5 * (x / radius + 1);
+    double v = 0.5 * (z / radius + 1);
+    rec.set(t, normal, p, material, u, v);
+    rec.set_tangent(Vec3(1, 0, 0));
+    return true;
+}
+
+/**
+ * @brief     Get the bounding box of the cylinder.
+ * @param[in] time0 Start time.
+ * @param[in] time1 End time.
+ * @param[out] output_box Bounding box.
+ * @return    True if bounding box is valid.
+ */
+bool Cylinder::bounding_box(double time0, double time1, AABB &output_box) const
+{
+    output_box = AABB(base - Vec3(radius, 0.0001, radius), base + Vec3(radius, height + 0.0001, radius));
+    return true;
+}
+
+#endif // CYLINDER_H
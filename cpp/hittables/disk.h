@@ -0,0 +1,92 @@
+/*
+Disk.h
+======
+
+The Disk class represents a flat disk defined by a center, a normal and a radius.
+*/
+
+#ifndef DISK_H
+#define DISK_H
+
+#include <cmath>
+
+#include "hittable.h"
+#include "vec3.h"
+#include "ray.h"
+#include "hit_record.h"
+#include "onb.h"
+
+/**
+ * @brief      Class for disk.
+ */
+class Disk : public Hittable
+{
+public:
+    Disk() {}
+    Disk(const Vec3 &_center, const Vec3 &_normal, double _radius, Material *m) : center(_center), normal(unit_vector(_normal)), radius(_radius), material(m)
+    {
+        uvw.build_from_w(normal);
+    }
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override;
+    std::string to_string() const { return "Disk"; }
+
+public:
+    Vec3 center;
+    Vec3 normal;
+    double radius;
+    Material *material;
+
+private:
+    ONB uvw;
+};
+
+/**
+ * @brief     Check if a ray hits the disk.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Disk::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    double denominator = dot(normal, r.direction);
+    if (fabs(denominator) < 1e-12)
+        // ray parallel to the disk
+        return false;
+    double t = dot(center - r.origin, normal) / denominator;
+    if (t < t_min || t > t_max)
+        return false;
+    Vec3 p = r.point_at_parameter(t);
+    Vec3 to_center = p - center;
+    if (to_center.length_squared() > radius * radius)
+        return false;
+    double u = 0.5 * (dot(to_center, uvw.u()) / radius + 1);
+    double v = 0.5 * (dot(to_center, uvw.v()) / radius + 1);
+    rec.set(t, normal, p, material, u, v);
+    rec.set_tangent(uvw.u());
+    return true;
+}
+
+/**
+ * @brief     Get the bounding box of the disk.
+ *            The extent along each axis is radius * sqrt(1 - normal[axis]^2),
+ *            padded so that the box never has zero thickness.
+ * @param[in] time0 Start time.
+ * @param[in] time1 End time.
+ * @param[out] output_box Bounding box.
+ * @return    True if bounding box is valid.
+ */
+bool Disk::bounding_box(double time0, double time1, AABB &output_box) const
+{
+    Vec3 extent;
+    for (int a = 0; a < 3; a++)
+    {
+        extent[a] = radius * sqrt(fmax(0.0, 1 - normal[a] * normal[a])) + 0.0001;
+    }
+    output_box = AABB(center - extent, center + extent);
+    return true;
+}
+
+#endif // DISK_H
@@ -0,0 +1,59 @@
+/*
+Flip_normals.h
+==============
+
+Implements a hittable object with reversed normals. The front face of the
+object becomes its back face, e.g. a sphere hit from the outside behaves
+like a surface hit from the inside, which changes how dielectrics refract
+at it.
+*/
+
+#ifndef FLIP_NORMALS_H
+#define FLIP_NORMALS_H
+
+#include <memory>
+
+#include "hittable.h"
+#include "hit_record.h"
+
+/**
+ * @brief      Class for flip normals.
+ */
+class FlipNormals : public Hittable
+{
+public:
+    FlipNormals(std::shared_ptr<Hittable> hittable) : hittable(hittable) {}
+
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override
+    {
+        return hittable->bounding_box(time0, time1, output_box);
+    }
+    double pdf_value(const Vec3 &o, const Vec3 &v, double time) const override { return hittable->pdf_value(o, v, time); }
+    Vec3 random(const Vec3 &o) const override { return hittable->random(o); }
+    double surface_area() const override { return hittable->surface_area(); }
+
+public:
+    std::shared_ptr<Hittable> hittable;
+};
+
+/**
+ * @brief      Determines if the ray hits the hittable object and reverses the normals.
+ * @param[in]  r      The ray
+ * @param[in]  t_min  The minimum parameter
+ * @param[in]  t_max  The maximum parameter
+ * @param[out] rec    The hit record
+ * @return     True if the ray hits the hittable object, False otherwise.
+*/
+bool FlipNormals::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    if (!hittable->hit(r, t_min, t_max, rec))
+        return false;
+
+    rec.set_normal(-rec.get_normal());
+    rec.set_shading_normal(-rec.get_shading_normal());
+
+    return true;
+}
+
+#endif
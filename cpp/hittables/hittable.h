@@ -41,6 +41,14 @@ public:
     {
         return Vec3(1, 0, 0);
     }
+    /**
+     * @brief      The surface area, used to weight lights.
+     * @return     The area, zero if unknown.
+     */
+    virtual double surface_area() const
+    {
+        return 0.0;
+    }
 private:
     Material *material;
 };
@@ -56,16 +64,42 @@ void Hittable::set_material(Material *m)
 
 /**
  * @brief   Get the UV coordinates of a point on a sphere.
- * @param   p   The point
+ *          u = (phi + pi) / (2 pi) with the azimuth phi measured around the y-axis
+ *          from -z, so (0, 0, -1) maps to u = 0.5 and u grows towards -x.
+ *          v = theta / pi with the polar angle theta measured from -y.
+ * @param   p   The point on the unit sphere
  * @param   u   The u coordinate
  * @param   v   The v coordinate
  */
 void get_sphere_uv(const Vec3 &p, double &u, double &v)
 {
-    double phi = atan2(p.z(), p.x());
-    double theta = asin(p.y());
-    u = 1 - (phi + M_PI) / (2 * M_PI);
-    v = (theta + M_PI / 2) / M_PI;
+    double phi = atan2(-p.x(), -p.z());
+    double theta = acos(clamp(-p.y(), -1.0, 1.0));
+    u = (phi + M_PI) / (2 * M_PI);
+    v = theta / M_PI;
+}
+
+/**
+ * @brief   Get the direction of increasing u at a point on a sphere, see get_sphere_uv.
+ * @param   p   The point on the unit sphere
+ * @return  The tangent, zero at the poles.
+ */
+Vec3 get_sphere_tangent(const Vec3 &p)
+{
+    return Vec3(p.z(), 0, -p.x());
+}
+
+/**
+ * @brief   Distance between two texture coordinates on a sphere.
+ *          u wraps around at the seam, so 0.99 and 0.01 are close.
+ * @return  The distance in texture coordinates.
+ */
+double sphere_uv_distance(double u0, double v0, double u1, double v1)
+{
+    double du = fabs(u1 - u0);
+    du = fmin(du, 1 - du);
+    double dv = v1 - v0;
+    return sqrt(du * du + dv * dv);
 }
 
 
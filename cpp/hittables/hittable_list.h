@@ -3,6 +3,10 @@ Hittable List
 =============
 
 The HittableList class contains a list of hittable objects.
+
+Without a BVH, every object of the list is checked for each ray. After
+build_bvh the hit queries go through the tree instead, until the next
+object is added.
 */
 
 #ifndef HITTABLE_LIST_H
@@ -12,6 +16,8 @@ The HittableList class contains a list of hittable objects.
 #include <memory>
 #include "hittable.h"
 #include "hit_record.h"
+#include "bvh.h"
+#include "render_stats.h"
 
 
 /**
@@ -30,6 +36,7 @@ public:
     int add(std::shared_ptr<Hittable> object)
     {
         objects.push_back(object);
+        bvh.reset();
         return 0;
     }
     int size() const
@@ -37,6 +44,11 @@ public:
         return objects.size();
     }
     virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const;
+    bool build_bvh(double time0 = 0, double time1 = 1, SplitMethod split_method = SplitMethod::Median);
+    bool has_bvh() const
+    {
+        return bvh != nullptr;
+    }
     virtual bool bounding_box(double t0, double t1, AABB &box) const;
     std::shared_ptr<Hittable> get(int i) const
     {
@@ -58,10 +70,12 @@ public:
 
 private:
     std::vector<std::shared_ptr<Hittable>> objects;
+    std::shared_ptr<BVHNode> bvh;
 };
 
 /**
  * @brief      Determines if the ray hits any of the objects in the list.
+ *             Uses the BVH if it was built, otherwise checks every object.
  * @param[in]  r       The ray
  * @param[in]  t_min   The minimum distance along the ray
  * @param[in]  t_max   The maximum distance along the ray
@@ -70,12 +84,16 @@ private:
  */
 bool HittableList::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
 {
+    if (bvh)
+        return bvh->hit(r, t_min, t_max, rec);
+
     HitRecord temp_rec;
     bool hit_anything = false;
     double closest_so_far = t_max;
 
     for (const auto &object : objects)
     {
+        RenderCounters::count(render_counters().primitive_tests);
         if (object->hit(r, t_min, closest_so_far, temp_rec))
         {
             hit_anything = true;
@@ -87,6 +105,26 @@ bool HittableList::hit(const Ray &r, double t_min, double t_max, HitRecord &rec)
     return hit_anything;
 }
 
+/**
+ * @brief      Builds a BVH over the objects, used by hit until the next object is added.
+ *             The order of the objects in the list is not changed.
+ * @param[in]  time0         The start time
+ * @param[in]  time1         The end time
+ * @param[in]  split_method  The method used to split the objects into the child nodes
+ * @return     True if the BVH was built, False if the list is empty or an object has no bounding box.
+ */
+bool HittableList::build_bvh(double time0, double time1, SplitMethod split_method)
+{
+    bvh.reset();
+    AABB box;
+    if (!bounding_box(time0, time1, box))
+        return false;
+
+    std::vector<std::shared_ptr<Hittable>> bvh_objects = objects;
+    bvh = ::build_bvh(bvh_objects, time0, time1, split_method);
+    return true;
+}
+
 /**
  * @brief      Constructs an AABB that contains all of the objects in the list.
  * @param[in]  t0    The start time
@@ -0,0 +1,70 @@
+/*
+Instance.h
+==========
+
+Implements an instance of shared geometry, e.g. a triangle mesh with its
+own BVH that appears several times in a scene. The geometry and its BVH
+are built once and shared by all instances, which are placed with the
+Translate, Rotate and Scale wrappers. The scene BVH then only holds the
+instances, and a ray descends into the shared BVH after it has been
+transformed into the space of the instance.
+
+Example:
+```cpp
+HittableList mesh;
+load_obj("tree.obj", &bark, mesh);
+mesh.build_bvh();
+auto tree = std::make_shared<HittableList>(mesh);
+world.add(std::make_shared<Translate>(std::make_shared<Instance>(tree), Vec3(-2, 0, 0)));
+world.add(std::make_shared<Translate>(std::make_shared<Instance>(tree, &leaves), Vec3(2, 0, 0)));
+```
+*/
+
+#ifndef INSTANCE_H
+#define INSTANCE_H
+
+#include <memory>
+
+#include "hittable.h"
+#include "hit_record.h"
+
+/**
+ * @brief      Class for instance.
+ */
+class Instance : public Hittable
+{
+public:
+    Instance(std::shared_ptr<Hittable> geometry, Material *m = nullptr) : geometry(geometry), material(m) {}
+
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override
+    {
+        return geometry->bounding_box(time0, time1, output_box);
+    }
+    std::string to_string() const { return "Instance"; }
+
+public:
+    std::shared_ptr<Hittable> geometry; // shared by all instances
+    Material *material;                 // replaces the material of the geometry, unless null
+};
+
+/**
+ * @brief      Determines if the ray hits the shared geometry.
+ * @param[in]  r      The ray
+ * @param[in]  t_min  The minimum parameter
+ * @param[in]  t_max  The maximum parameter
+ * @param[out] rec    The hit record
+ * @return     True if the ray hits the geometry, False otherwise.
+*/
+bool Instance::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    if (!geometry->hit(r, t_min, t_max, rec))
+        return false;
+
+    if (material)
+        rec.set_material(material);
+
+    return true;
+}
+
+#endif
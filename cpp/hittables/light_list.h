@@ -0,0 +1,136 @@
+/*
+light_list.h
+============
+A list of the emissive objects of a scene, for picking one light per sample.
+
+Each light has a weight, by default its surface area, and is picked with a
+probability proportional to its weight. Bright or large lights are chosen
+more often than with a uniform pick. Lights without a known area get an
+equal share of the picks, the average weight of the other lights. The
+probability of the pick is returned with the light, so it can be included
+in the density of the sampled direction.
+*/
+
+#ifndef LIGHT_LIST_H
+#define LIGHT_LIST_H
+
+#include <vector>
+#include <memory>
+
+#include "ray_tracer.h"
+#include "hittable.h"
+
+/**
+ * @brief      Class for a weighted list of lights.
+ */
+class LightList
+{
+public:
+    LightList() {}
+
+    /**
+     * @brief      Adds a light weighted by its surface area.
+     *             Lights without a known area get an equal share of the picks.
+     * @param[in]  light  The light
+     */
+    void add(std::shared_ptr<Hittable> light)
+    {
+        double area = light->surface_area();
+        if (area > 0)
+        {
+            add(light, area);
+            return;
+        }
+        lights.push_back(light);
+        weights.push_back(0.0);
+        equal_share.push_back(true);
+    }
+
+    /**
+     * @brief      Adds a light with a given weight, e.g. its emitted power.
+     * @param[in]  light   The light
+     * @param[in]  weight  The weight, lights with a weight of zero are never picked
+     */
+    void add(std::shared_ptr<Hittable> light, double weight)
+    {
+        lights.push_back(light);
+        weights.push_back(weight);
+        equal_share.push_back(false);
+        known_weight += weight;
+        known_count++;
+    }
+
+    int size() const
+    {
+        return lights.size();
+    }
+    std::shared_ptr<Hittable> get(int i) const
+    {
+        return lights[i];
+    }
+
+    /**
+     * @brief      The probability that sample_one picks a light.
+     * @param[in]  i     The index of the light
+     * @return     The probability.
+     */
+    double selection_probability(int i) const
+    {
+        double total_weight = known_weight + (lights.size() - known_count) * share();
+        return total_weight > 0 ? weight(i) / total_weight : 0.0;
+    }
+
+    std::shared_ptr<Hittable> sample_one(double &pdf) const;
+
+private:
+    /**
+     * @brief      The weight of a light without a known area: the average weight
+     *             of the other lights, or 1 if no light has a known weight.
+     * @return     The weight.
+     */
+    double share() const
+    {
+        return known_count > 0 ? known_weight / known_count : 1.0;
+    }
+    double weight(size_t i) const
+    {
+        return equal_share[i] ? share() : weights[i];
+    }
+
+    std::vector<std::shared_ptr<Hittable>> lights;
+    std::vector<double> weights;
+    std::vector<bool> equal_share;
+    double known_weight = 0.0;
+    size_t known_count = 0;
+};
+
+/**
+ * @brief      Picks one light with weighted reservoir sampling.
+ *             Each light replaces the current pick with the probability of its
+ *             weight over the sum of the weights seen so far, so every light ends
+ *             up picked with the probability of its weight over the total weight.
+ * @param[out] pdf   The probability of the picked light
+ * @return     The light, nullptr if the list is empty or all weights are zero.
+ */
+std::shared_ptr<Hittable> LightList::sample_one(double &pdf) const
+{
+    std::shared_ptr<Hittable> picked = nullptr;
+    double picked_weight = 0.0;
+    double weight_sum = 0.0;
+    for (size_t i = 0; i < lights.size(); i++)
+    {
+        double light_weight = weight(i);
+        if (light_weight <= 0)
+            continue;
+        weight_sum += light_weight;
+        if (random_double() * weight_sum < light_weight)
+        {
+            picked = lights[i];
+            picked_weight = light_weight;
+        }
+    }
+    pdf = weight_sum > 0 ? picked_weight / weight_sum : 0.0;
+    return picked;
+}
+
+#endif // LIGHT_LIST_H
@@ -68,7 +68,8 @@ public:
             Vec3 normal = (r.point_at_parameter(hit_at_t) - center) / radius;
             double u, v;
             get_sphere_uv(normal, u, v);
-        rec.    set(hit_at_t, normal, r.point_at_parameter(hit_at_t), material, u, v);
+            rec.set(hit_at_t, normal, r.point_at_parameter(hit_at_t), material, u, v);
+            rec.set_tangent(get_sphere_tangent(normal));
             
             return true;
         }
@@ -83,8 +84,8 @@ public:
     */
     virtual bool bounding_box(double t0, double t1, AABB &box) const override
     {
-        AABB box0(get_center(t0) - Vec3(radius, radius, radius), getCenter(t0) + Vec3(radius, radius, radius));
-        AABB box1(get_center(t1) - Vec3(radius, radius, radius), getCenter(t1) + Vec3(radius, radius, radius));
+        AABB box0(get_center(t0) - Vec3(radius, radius, radius), get_center(t0) + Vec3(radius, radius, radius));
+        AABB box1(get_center(t1) - Vec3(radius, radius, radius), get_center(t1) + Vec3(radius, radius, radius));
         box = surrounding_box(box0, box1);
         return true;
     }
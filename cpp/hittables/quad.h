@@ -0,0 +1,150 @@
+/*
+Quad.h
+======
+
+The Quad class represents a parallelogram defined by a corner Q and two
+edge vectors u and v. Unlike the rectangles in rectangle.h, a quad can
+have any orientation.
+*/
+
+#ifndef QUAD_H
+#define QUAD_H
+
+#include <cmath>
+
+#include "hittable.h"
+#include "vec3.h"
+#include "ray.h"
+#include "hit_record.h"
+
+/**
+ * @brief      Class for quad.
+ */
+class Quad : public Hittable
+{
+public:
+    Quad() {}
+    Quad(const Vec3 &_Q, const Vec3 &_u, const Vec3 &_v, Material *m) : Q(_Q), u(_u), v(_v), material(m)
+    {
+        Vec3 n = cross(u, v);
+        normal = unit_vector(n);
+        D = dot(normal, Q);
+        w = n / dot(n, n);
+        area = n.length();
+    }
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override;
+    std::string to_string() const { return "Quad"; }
+    double pdf_value(const Vec3 &o, const Vec3 &direction, double time) const override;
+    Vec3 random(const Vec3 &o) const override;
+    double surface_area() const override { return area; }
+
+public:
+    Vec3 Q, u, v;
+    Material *material;
+
+private:
+    Vec3 normal;
+    double D;    // plane offset, dot(normal, p) = D for points p on the plane
+    Vec3 w;      // cross(u, v) / |cross(u, v)|^2, used for the planar coordinates
+    double area;
+};
+
+/**
+ * @brief     Check if a ray hits the quad.
+ *            The planar coordinates (alpha, beta) of the hit point along u and v
+ *            are stored as texture coordinates.
+ *            With ray differentials the footprint of the pixel is stored as well.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Quad::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    double denominator = dot(normal, r.direction);
+    if (fabs(denominator) < 1e-12)
+        // ray parallel to the quad
+        return false;
+    double t = (D - dot(normal, r.origin)) / denominator;
+    if (t < t_min || t > t_max)
+        return false;
+
+    Vec3 p = r.point_at_parameter(t);
+    Vec3 planar_hit = p - Q;
+    double alpha = dot(w, cross(planar_hit, v));
+    double beta = dot(w, cross(u, planar_hit));
+    if (alpha < 0 || alpha > 1 || beta < 0 || beta > 1)
+        return false;
+
+    rec.set(t, normal, p, material, alpha, beta);
+    rec.set_tangent(unit_vector(u));
+
+    // texture footprint of the pixel
+    Vec3 px, py;
+    if (r.differential_hits(p, normal, px, py))
+    {
+        double dx_alpha = dot(w, cross(px - Q, v)) - alpha;
+        double dx_beta = dot(w, cross(u, px - Q)) - beta;
+        double dy_alpha = dot(w, cross(py - Q, v)) - alpha;
+        double dy_beta = dot(w, cross(u, py - Q)) - beta;
+        rec.set_footprint(fmax(sqrt(dx_alpha * dx_alpha + dx_beta * dx_beta), sqrt(dy_alpha * dy_alpha + dy_beta * dy_beta)));
+    }
+    return true;
+}
+
+/**
+ * @brief     Get the bounding box of the quad.
+ *            The box encloses all four corners and is padded on every axis.
+ * @param[in] time0 Start time.
+ * @param[in] time1 End time.
+ * @param[out] output_box Bounding box.
+ * @return    True if bounding box is valid.
+ */
+bool Quad::bounding_box(double time0, double time1, AABB &output_box) const
+{
+    Vec3 corners[4] = {Q, Q + u, Q + v, Q + u + v};
+    Vec3 minimum = corners[0];
+    Vec3 maximum = corners[0];
+    for (const Vec3 &corner : corners)
+    {
+        minimum = Vec3::min(minimum, corner);
+        maximum = Vec3::max(maximum, corner);
+    }
+    output_box = AABB(minimum - Vec3(0.0001, 0.0001, 0.0001), maximum + Vec3(0.0001, 0.0001, 0.0001));
+    return true;
+}
+
+/**
+ * @brief     Get the probability density function value for a given ray.
+ * @param[in] origin Origin of the ray.
+ * @param[in] direction Direction of the ray.
+ * @param[in] time Time of the ray.
+ * @return    Probability density function value.
+ */
+double Quad::pdf_value(const Vec3 &origin, const Vec3 &direction, double time) const
+{
+    HitRecord rec;
+    if (this->hit(Ray(origin, direction, time), ray_epsilon, infinity, rec))
+    {
+        double distance_squared = rec.get_t() * rec.get_t() * direction.length_squared();
+        double cosine = fabs(dot(direction, rec.get_normal()) / direction.length());
+        return distance_squared / (cosine * area);
+    }
+    else
+        return 0;
+}
+
+/**
+ * @brief     Get a random direction from the origin towards a point on the quad.
+ * @param[in] origin Origin of the direction.
+ * @return    Direction towards the quad.
+ */
+Vec3 Quad::random(const Vec3 &origin) const
+{
+    Vec3 random_point = Q + random_double() * u + random_double() * v;
+    return random_point - origin;
+}
+
+#endif // QUAD_H
@@ -30,6 +30,7 @@ public:
     std::string to_string() const { return "XY_Rectangle"; }
     double pdf_value(const Vec3 &o, const Vec3 &v, double time) const override;
     Vec3 random(const Vec3 &o) const override;
+    double surface_area() const override { return (x1 - x0) * (y1 - y0); }
 
 public:
     double x0, x1, y0, y1, k;
@@ -49,6 +50,7 @@ public:
     std::string to_string() const { return "XZ_Rectangle"; }
     double pdf_value(const Vec3 &o, const Vec3 &v, double time) const override;
     Vec3 random(const Vec3 &o) const override;
+    double surface_area() const override { return (x1 - x0) * (z1 - z0); }
 
 public:
     double x0, x1, z0, z1, k;
@@ -68,6 +70,7 @@ public:
     std::string to_string() const { return "YZ_Rectangle"; }
     double pdf_value(const Vec3 &o, const Vec3 &v, double time) const override;
     Vec3 random(const Vec3 &o) const override;
+    double surface_area() const override { return (y1 - y0) * (z1 - z0); }
 
 public:
     double y0, y1, z0, z1, k;
@@ -94,6 +97,7 @@ bool XY_Rectangle::hit(const Ray &r, double t_min, double t_max, HitRecord &rec)
     double v = (y - y0) / (y1 - y0);
     Vec3 normal = Vec3(0, 0, 1);
     rec.set(t, normal, r.point_at_parameter(t), material, u, v);
+    rec.set_tangent(Vec3(1, 0, 0));
     return true;
 }
 
@@ -106,7 +110,7 @@ bool XY_Rectangle::hit(const Ray &r, double t_min, double t_max, HitRecord &rec)
  */
 bool XY_Rectangle::bounding_box(double time0, double time1, AABB &output_box) const
 {
-    output_box = AABB(Vec3(x0, y0, k - 0.0001), Vec3(x1, y1, k + 0.0001));
+    output_box = AABB(Vec3(x0, y0, k), Vec3(x1, y1, k)).pad();
     return true;
 }
 
@@ -130,6 +134,7 @@ bool XZ_Rectangle::hit(const Ray &r, double t_min, double t_max, HitRecord &rec)
     double v = (z - z0) / (z1 - z0);
     Vec3 normal = Vec3(0, 1, 0);
     rec.set(t, normal, r.point_at_parameter(t), material, u, v); 
+    rec.set_tangent(Vec3(1, 0, 0));
     return true;
 }
 
@@ -142,7 +147,7 @@ bool XZ_Rectangle::hit(const Ray &r, double t_min, double t_max, HitRecord &rec)
 */
 bool XZ_Rectangle::bounding_box(double time0, double time1, AABB &output_box) const
 {
-    output_box = AABB(Vec3(x0, k - 0.0001, z0), Vec3(x1, k + 0.0001, z1));
+    output_box = AABB(Vec3(x0, k, z0), Vec3(x1, k, z1)).pad();
     return true;
 }
 
@@ -167,6 +172,7 @@ bool YZ_Rectangle::hit(const Ray &r, double t_min, double t_max, HitRecord &rec)
     double v = (z - z0) / (z1 - z0);
     Vec3 normal = Vec3(1, 0, 0);
     rec.set(t, normal, r.point_at_parameter(t), material, u, v);
+    rec.set_tangent(Vec3(0, 1, 0));
     return true;
 }
 
@@ -179,7 +185,7 @@ bool YZ_Rectangle::hit(const Ray &r, double t_min, double t_max, HitRecord &rec)
 */
 bool YZ_Rectangle::bounding_box(double time0, double time1, AABB &output_box) const
 {
-    output_box = AABB(Vec3(k - 0.0001, y0, z0), Vec3(k + 0.0001, y1, z1));
+    output_box = AABB(Vec3(k, y0, z0), Vec3(k, y1, z1)).pad();
     return true;
 }
 
@@ -226,7 +232,7 @@ Vec3 YZ_Rectangle::random(const Vec3 &origin) const
 double XY_Rectangle::pdf_value(const Vec3 &origin, const Vec3 &v, double time) const
 {
     HitRecord rec;
-    if (this->hit(Ray(origin, v, time), 0.001, infinity, rec))
+    if (this->hit(Ray(origin, v, time), ray_epsilon, infinity, rec))
     {
         double area = (x1 - x0) * (y1 - y0);
         double distance_squared = rec.get_t() * rec.get_t() * v.length_squared();
@@ -247,7 +253,7 @@ double XY_Rectangle::pdf_value(const Vec3 &origin, const Vec3 &v, double time) c
 double XZ_Rectangle::pdf_value(const Vec3 &origin, const Vec3 &v, double time) const
 {
     HitRecord rec;
-    if (this->hit(Ray(origin, v, time), 0.001, infinity, rec))
+    if (this->hit(Ray(origin, v, time), ray_epsilon, infinity, rec))
     {
         double area = (x1 - x0) * (z1 - z0);
         double distance_squared = rec.get_t() * rec.get_t() * v.length_squared();
@@ -268,7 +274,7 @@ double XZ_Rectangle::pdf_value(const Vec3 &origin, const Vec3 &v, double time) c
 double YZ_Rectangle::pdf_value(const Vec3 &origin, const Vec3 &v, double time) const
 {
     HitRecord rec;
-    if (this->hit(Ray(origin, v, time), 0.001, infinity, rec))
+    if (this->hit(Ray(origin, v, time), ray_epsilon, infinity, rec))
     {
         double area = (y1 - y0) * (z1 - z0);
         double distance_squared = rec.get_t() * rec.get_t() * v.length_squared();
@@ -281,6 +287,9 @@ double YZ_Rectangle::pdf_value(const Vec3 &origin, const Vec3 &v, double time) c
 
 /**
  * @brief   Box class.
+ * @details An axis-aligned box, hit with the slab test of its AABB.
+ *          The normals point out of the box. As a light, the box is sampled
+ *          on the faces visible from the origin of the ray.
  */
 class Box : public Hittable
 {
@@ -293,11 +302,19 @@ public:
         output_box = AABB(box_min, box_max);
         return true;
     }
+    double pdf_value(const Vec3 &o, const Vec3 &v, double time) const override;
+    Vec3 random(const Vec3 &o) const override;
+    double surface_area() const override { return AABB(box_min, box_max).surface_area(); }
     std::string to_string() const { return "Box"; }
 
+private:
+    bool is_inside(const Vec3 &origin) const;
+    bool is_face_visible(const Vec3 &origin, int axis, int side) const;
+    double visible_area(const Vec3 &origin) const;
+
 public:
     Vec3 box_min, box_max;
-    HittableList sides;
+    Material *material;
 };
 
 /**
@@ -308,20 +325,9 @@ public:
  */
 Box::Box(const Vec3 &p0, const Vec3 &p1, Material *material)
 {
-    box_min = p0;
-    box_max = p1;
-    double min_x = std::min(p0.x(), p1.x());
-    double min_y = std::min(p0.y(), p1.y());
-    double min_z = std::min(p0.z(), p1.z());
-    double max_x = std::max(p0.x(), p1.x());
-    double max_y = std::max(p0.y(), p1.y());
-    double max_z = std::max(p0.z(), p1.z());
-    sides.add(std::make_shared<XY_Rectangle>(min_x, max_x, min_y, max_y, max_z, material));
-    sides.add(std::make_shared<XY_Rectangle>(min_x, max_x, min_y, max_y, min_z, material));
-    sides.add(std::make_shared<XZ_Rectangle>(min_x, max_x, min_z, max_z, max_y, material));
-    sides.add(std::make_shared<XZ_Rectangle>(min_x, max_x, min_z, max_z, min_y, material));
-    sides.add(std::make_shared<YZ_Rectangle>(min_y, max_y, min_z, max_z, max_x, material));
-    sides.add(std::make_shared<YZ_Rectangle>(min_y, max_y, min_z, max_z, min_x, material));
+    box_min = Vec3::min(p0, p1);
+    box_max = Vec3::max(p0, p1);
+    this->material = material;
 }
 
 /**
@@ -334,7 +340,113 @@ Box::Box(const Vec3 &p0, const Vec3 &p1, Material *material)
  */
 bool Box::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
 {
-    return sides.hit(r, t_min, t_max, rec);
+    return AABB(box_min, box_max).hit(r, t_min, t_max, rec, material);
+}
+
+/**
+ * @brief     Check if a point is inside the box.
+ * @param[in] origin The point.
+ * @return    True if the point is inside or on the box, false otherwise.
+ */
+bool Box::is_inside(const Vec3 &origin) const
+{
+    for (int axis = 0; axis < 3; axis++)
+    {
+        if (origin[axis] < box_min[axis] || origin[axis] > box_max[axis])
+            return false;
+    }
+    return true;
+}
+
+/**
+ * @brief     Check if a face of the box is visible from a point.
+ *            From inside the box every face is visible, from outside the
+ *            faces whose plane lies between the point and the box.
+ * @param[in] origin The point.
+ * @param[in] axis   The axis the face is perpendicular to.
+ * @param[in] side   0 for the face at box_min, 1 for the face at box_max.
+ * @return    True if the face is visible, false otherwise.
+ */
+bool Box::is_face_visible(const Vec3 &origin, int axis, int side) const
+{
+    if (is_inside(origin))
+        return true;
+    return side == 0 ? origin[axis] < box_min[axis] : origin[axis] > box_max[axis];
+}
+
+/**
+ * @brief     Get the area of the faces visible from a point.
+ * @param[in] origin The point.
+ * @return    The visible area.
+ */
+double Box::visible_area(const Vec3 &origin) const
+{
+    Vec3 size = box_max - box_min;
+    double area = 0;
+    for (int axis = 0; axis < 3; axis++)
+    {
+        double face_area = size[(axis + 1) % 3] * size[(axis + 2) % 3];
+        for (int side = 0; side < 2; side++)
+        {
+            if (is_face_visible(origin, axis, side))
+                area += face_area;
+        }
+    }
+    return area;
+}
+
+/**
+ * @brief     Get a random point on the faces of the box visible from the origin.
+ *            The faces are picked with a probability proportional to their area.
+ * @param[in] origin Origin of the ray.
+ * @return    Direction from the origin to the point.
+ */
+Vec3 Box::random(const Vec3 &origin) const
+{
+    Vec3 size = box_max - box_min;
+    double pick = random_double() * visible_area(origin);
+    int picked_axis = 0;
+    int picked_side = 0;
+    for (int axis = 0; axis < 3; axis++)
+    {
+        double face_area = size[(axis + 1) % 3] * size[(axis + 2) % 3];
+        for (int side = 0; side < 2; side++)
+        {
+            if (!is_face_visible(origin, axis, side) || pick < 0)
+                continue;
+            picked_axis = axis;
+            picked_side = side;
+            pick -= face_area;
+        }
+    }
+
+    Vec3 random_point = box_min + Vec3(random_double() * size.x(), random_double() * size.y(), random_double() * size.z());
+    random_point[picked_axis] = picked_side == 0 ? box_min[picked_axis] : box_max[picked_axis];
+    return random_point - origin;
+}
+
+/**
+ * @brief     Get the probability density function value for a given ray.
+ *            A ray from outside enters the box through a visible face, a ray
+ *            from inside leaves it through one, so each direction belongs to
+ *            exactly one sampled point.
+ * @param[in] origin Origin of the ray.
+ * @param[in] v Direction of the ray.
+ * @param[in] time Time of the ray.
+ * @return    Probability density function value.
+ */
+double Box::pdf_value(const Vec3 &origin, const Vec3 &v, double time) const
+{
+    HitRecord rec;
+    if (this->hit(Ray(origin, v, time), ray_epsilon, infinity, rec))
+    {
+        double area = visible_area(origin);
+        double distance_squared = rec.get_t() * rec.get_t() * v.length_squared();
+        double cosine = fabs(dot(v, rec.get_normal()) / v.length());
+        return distance_squared / (cosine * area);
+    }
+    else
+        return 0;
 }
 
 #endif
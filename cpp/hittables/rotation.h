@@ -1,7 +1,7 @@
 /*
 Rotation.h
 ==========
-Rotate hittable objects around the y-axis.
+Rotate hittable objects around the y-axis, or around any axis through the origin.
 */
 
 
@@ -12,6 +12,7 @@ Rotate hittable objects around the y-axis.
 #include <memory>
 
 #include "hittable.h"
+#include "hit_record.h"
 #include "vec3.h"
 #include "ray.h"
 #include "aabb.h"
@@ -39,20 +40,17 @@ public:
             {
                 for (int k = 0; k < 2; k++)
                 {
-                    auto x = i * bbox.getMax().x() + (1 - i) * bbox.getMin().x();
-                    auto y = j * bbox.getMax().y() + (1 - j) * bbox.getMin().y();
-                    auto z = k * bbox.getMax().z() + (1 - k) * bbox.getMin().z();
+                    auto x = i * bbox.get_max().x() + (1 - i) * bbox.get_min().x();
+                    auto y = j * bbox.get_max().y() + (1 - j) * bbox.get_min().y();
+                    auto z = k * bbox.get_max().z() + (1 - k) * bbox.get_min().z();
 
                     auto newx = cos_theta * x + sin_theta * z;
                     auto newz = -sin_theta * x + cos_theta * z;
 
                     Vec3 tester(newx, y, newz);
 
-                    for (int c = 0; c < 3; c++)
-                    {
-                        min[c] = fmin(min[c], tester[c]);
-                        max[c] = fmax(max[c], tester[c]);
-                    }
+                    min = Vec3::min(min, tester);
+                    max = Vec3::max(max, tester);
                 }
             }
         }
@@ -102,6 +100,8 @@ bool RotateY::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) cons
 
     auto p = rec.get_hit_point();
     auto normal = rec.get_normal();
+    auto shading_normal = rec.get_shading_normal();
+    auto tangent = rec.get_tangent();
 
     p[0] = cos_theta * rec.get_hit_point()[0] + sin_theta * rec.get_hit_point()[2];
     p[2] = -sin_theta * rec.get_hit_point()[0] + cos_theta * rec.get_hit_point()[2];
@@ -109,8 +109,88 @@ bool RotateY::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) cons
     normal[0] = cos_theta * rec.get_normal()[0] + sin_theta * rec.get_normal()[2];
     normal[2] = -sin_theta * rec.get_normal()[0] + cos_theta * rec.get_normal()[2];
 
+    shading_normal[0] = cos_theta * rec.get_shading_normal()[0] + sin_theta * rec.get_shading_normal()[2];
+    shading_normal[2] = -sin_theta * rec.get_shading_normal()[0] + cos_theta * rec.get_shading_normal()[2];
+
+    tangent[0] = cos_theta * rec.get_tangent()[0] + sin_theta * rec.get_tangent()[2];
+    tangent[2] = -sin_theta * rec.get_tangent()[0] + cos_theta * rec.get_tangent()[2];
+
     rec.set_hit_point(p);
     rec.set_normal(normal);
+    rec.set_shading_normal(shading_normal);
+    rec.set_tangent(tangent);
+
+    return true;
+}
+
+/**
+ * @brief      Class for rotation around an arbitrary axis through the origin.
+ */
+class Rotate : public Hittable
+{
+public:
+    Rotate(std::shared_ptr<Hittable> hittable, const Vec3 &axis, double angle)
+    {
+        this->hittable = hittable;
+        this->axis = unit_vector(axis);
+        radians = degrees_to_radians(angle);
+        has_box = hittable->bounding_box(0, 1, bbox);
+
+        Vec3 min(infinity, infinity, infinity);
+        Vec3 max(-infinity, -infinity, -infinity);
+
+        for (int corner = 0; corner < 8; corner++)
+        {
+            Vec3 p((corner & 1) ? bbox.get_max().x() : bbox.get_min().x(),
+                   (corner & 2) ? bbox.get_max().y() : bbox.get_min().y(),
+                   (corner & 4) ? bbox.get_max().z() : bbox.get_min().z());
+            Vec3 tester = p.rotate_around(this->axis, radians);
+
+            min = Vec3::min(min, tester);
+            max = Vec3::max(max, tester);
+        }
+
+        bbox = AABB(min, max);
+    }
+
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override
+    {
+        output_box = bbox;
+        return has_box;
+    }
+
+public:
+    std::shared_ptr<Hittable> hittable;
+    Vec3 axis;
+    double radians;
+    bool has_box;
+    AABB bbox;
+};
+
+/**
+ * @brief      Determines if the ray hits the rotated object.
+ *             The ray is rotated back into the space of the object, the hit
+ *             point, normals and tangent are rotated forward again.
+ * @param[in]  r       The ray
+ * @param[in]  t_min   The minimum parameter
+ * @param[in]  t_max   The maximum parameter
+ * @param[out] rec     The hit record
+ * @return     True if the ray hits the rotated object, False otherwise.
+*/
+bool Rotate::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    Vec3 origin = r.origin.rotate_around(axis, -radians);
+    Vec3 direction = r.direction.rotate_around(axis, -radians);
+    Ray rotated_r(origin, direction, r.get_time());
+
+    if (!hittable->hit(rotated_r, t_min, t_max, rec))
+        return false;
+
+    rec.set_hit_point(rec.get_hit_point().rotate_around(axis, radians));
+    rec.set_normal(rec.get_normal().rotate_around(axis, radians));
+    rec.set_shading_normal(rec.get_shading_normal().rotate_around(axis, radians));
+    rec.set_tangent(rec.get_tangent().rotate_around(axis, radians));
 
     return true;
 }
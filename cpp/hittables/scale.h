@@ -0,0 +1,79 @@
+/*
+Scale.h
+=======
+
+Implements a scaling of a hittable object along the axes, e.g. to stretch
+a sphere into an ellipsoid. The ray is scaled into the space of the object
+with the inverse scale. Because origin and direction are scaled the same
+way, the ray parameter t of a hit does not change. Normals are transformed
+with the inverse transpose of the scaling, which is the inverse scale.
+*/
+
+#ifndef SCALE_H
+#define SCALE_H
+
+#include <memory>
+
+#include "hittable.h"
+#include "hit_record.h"
+
+/**
+ * @brief      Class for scale.
+ */
+class Scale : public Hittable
+{
+public:
+    Scale(std::shared_ptr<Hittable> hittable, const Vec3 &factor) : hittable(hittable), factor(factor) {}
+
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override;
+
+public:
+    std::shared_ptr<Hittable> hittable;
+    Vec3 factor; // scale along x, y and z, must not be zero
+};
+
+/**
+ * @brief      Determines if the ray hits the scaled object.
+ * @param[in]  r      The ray
+ * @param[in]  t_min  The minimum parameter
+ * @param[in]  t_max  The maximum parameter
+ * @param[out] rec    The hit record
+ * @return     True if the ray hits the scaled object, False otherwise.
+*/
+bool Scale::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    Ray scaled_r(r.origin / factor, r.direction / factor, r.get_time());
+    if (!hittable->hit(scaled_r, t_min, t_max, rec))
+        return false;
+
+    // normals transform with the inverse scale to stay perpendicular to the surface
+    rec.set_hit_point(rec.get_hit_point() * factor);
+    rec.set_normal(unit_vector(rec.get_normal() / factor));
+    rec.set_shading_normal(unit_vector(rec.get_shading_normal() / factor));
+    Vec3 tangent = rec.get_tangent() * factor;
+    if (tangent.length_squared() > 0)
+        rec.set_tangent(unit_vector(tangent));
+
+    return true;
+}
+
+/**
+ * @brief      Determines if the ray hits the bounding box.
+ * @param[in]  time0       The time 0
+ * @param[in]  time1       The time 1
+ * @param[out] output_box  The output box
+*/
+bool Scale::bounding_box(double time0, double time1, AABB &output_box) const
+{
+    if (!hittable->bounding_box(time0, time1, output_box))
+        return false;
+
+    // negative factors swap the minimum and maximum
+    Vec3 a = output_box.get_min() * factor;
+    Vec3 b = output_box.get_max() * factor;
+    output_box = AABB(Vec3::min(a, b), Vec3::max(a, b));
+    return true;
+}
+
+#endif
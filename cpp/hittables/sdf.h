@@ -0,0 +1,124 @@
+/*
+Sdf.h
+=====
+
+The Sdf class represents an implicit surface given by a signed distance
+function, negative inside and positive outside of the surface. The
+surface is found by sphere tracing: the ray advances by the distance to
+the surface until the distance drops below a small epsilon. The distance
+function must not overestimate the true distance, otherwise the tracer
+can step through the surface.
+
+Example:
+```cpp
+Sdf sphere([](const Vec3 &p) { return p.length() - 1.0; },
+           AABB(Vec3(-1, -1, -1), Vec3(1, 1, 1)), material);
+```
+*/
+
+#ifndef SDF_H
+#define SDF_H
+
+#include <cmath>
+#include <functional>
+
+#include "hittable.h"
+#include "vec3.h"
+#include "ray.h"
+#include "hit_record.h"
+#include "aabb.h"
+
+/**
+ * @brief      Class for signed distance field.
+ */
+class Sdf : public Hittable
+{
+public:
+    Sdf() {}
+    Sdf(std::function<double(const Vec3 &)> _distance, const AABB &_box, Material *m, int _max_steps = 256, double _epsilon = 1e-6)
+        : distance(_distance), box(_box), material(m), max_steps(_max_steps), epsilon(_epsilon) {}
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override;
+    std::string to_string() const { return "Sdf"; }
+    Vec3 get_normal(const Vec3 &p) const;
+
+public:
+    std::function<double(const Vec3 &)> distance;
+    AABB box;   // encloses the surface, marching stops when the ray leaves it
+    Material *material;
+    int max_steps;
+    double epsilon;
+};
+
+/**
+ * @brief     Check if a ray hits the surface.
+ *            The ray is marched through the part inside the bounding box.
+ *            Rays starting inside the surface march on the absolute distance
+ *            and find the surface from the inside.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Sdf::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    // clip the ray to the bounding box
+    for (int a = 0; a < 3; a++)
+    {
+        double invD = 1.0 / r.direction[a];
+        double t0 = (box.minimum[a] - r.origin[a]) * invD;
+        double t1 = (box.maximum[a] - r.origin[a]) * invD;
+        if (invD < 0.0)
+            std::swap(t0, t1);
+        t_min = fmax(t0, t_min);
+        t_max = fmin(t1, t_max);
+        if (t_max <= t_min)
+            return false;
+    }
+
+    double direction_length = r.direction.length();
+    double t = t_min;
+    for (int step = 0; step < max_steps && t <= t_max; step++)
+    {
+        Vec3 p = r.point_at_parameter(t);
+        double d = fabs(distance(p));
+        if (d < epsilon)
+        {
+            Vec3 normal = get_normal(p);
+            rec.set(t, normal, p, material, 0, 0);
+            return true;
+        }
+        t += d / direction_length;
+    }
+    return false;
+}
+
+/**
+ * @brief     Estimate the outward normal with central differences of the distance function.
+ * @param[in] p Point on the surface.
+ * @return    Unit normal.
+ */
+Vec3 Sdf::get_normal(const Vec3 &p) const
+{
+    const double h = 1e-5;
+    Vec3 gradient(distance(p + Vec3(h, 0, 0)) - distance(p - Vec3(h, 0, 0)),
+                  distance(p + Vec3(0, h, 0)) - distance(p - Vec3(0, h, 0)),
+                  distance(p + Vec3(0, 0, h)) - distance(p - Vec3(0, 0, h)));
+    return unit_vector(gradient);
+}
+
+/**
+ * @brief     Get the bounding box of the surface.
+ * @param[in] time0 Start time.
+ * @param[in] time1 End time.
+ * @param[out] output_box Bounding box.
+ * @return    True if bounding box is valid.
+ */
+bool Sdf::bounding_box(double time0, double time1, AABB &output_box) const
+{
+    output_box = box;
+    return true;
+}
+
+#endif // SDF_H
@@ -7,6 +7,7 @@ Sphere.h
 #define SPHERE_H
 
 #include "hittable.h"
+#include "onb.h"
 
 Vec3 random_to_sphere(double radius, double distance_squared);
 
@@ -45,7 +46,11 @@ public:
     void set_material(Material *m) { material = m; }
     double pdf_value(const Vec3& o, const Vec3& v, double time) const override {
         HitRecord rec;
-        if (this->hit(Ray(o, v, time), 0.001, infinity, rec)) {
+        if (this->hit(Ray(o, v, time), ray_epsilon, infinity, rec)) {
+            if ((center - o).length_squared() <= radius * radius) {
+                // from inside, the sphere covers all directions
+                return 1 / (4 * pi);
+            }
             auto cos_theta_max = sqrt(1 - radius*radius/(center-o).length_squared());
             auto solid_angle = 2*pi*(1-cos_theta_max);
             return 1 / solid_angle;
@@ -54,9 +59,16 @@ public:
             return 0;
         }
     }
+    double surface_area() const override {
+        return 4 * pi * radius * radius;
+    }
     Vec3 random(const Vec3& o) const override {
         Vec3 direction = center - o;
         auto distance_squared = direction.length_squared();
+        if (distance_squared <= radius * radius) {
+            // from inside, every direction hits the sphere
+            return random_unit_vector();
+        }
         ONB uvw;
         uvw.build_from_w(direction);
         return uvw.local(random_to_sphere(radius, distance_squared));
@@ -70,6 +82,7 @@ private:
 
 /**
  * @brief      Check if ray hits sphere
+ *             With ray differentials the footprint of the pixel is stored as well.
  * @param[in]  r     Ray
  * @param[in]  t_min  Minimum distance
  * @param[in]  t_max  Maximum distance
@@ -102,9 +115,22 @@ bool Sphere::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
         }
 
         // sphere in range, compute hit
+        Vec3 hit_point = r.point_at_parameter(hit_at_t);
+        Vec3 normal = get_normal(hit_point);
         double u, v;
-        get_sphere_uv((rec.get_hit_point() - center) / radius, u, v);
-        rec.set(hit_at_t, get_normal(r.point_at_parameter(hit_at_t)), r.point_at_parameter(hit_at_t), material, u, v);
+        get_sphere_uv(normal, u, v);
+        rec.set(hit_at_t, normal, hit_point, material, u, v);
+        rec.set_tangent(get_sphere_tangent(normal));
+
+        // texture footprint of the pixel
+        Vec3 px, py;
+        if (r.differential_hits(hit_point, normal, px, py))
+        {
+            double ux, vx, uy, vy;
+            get_sphere_uv(unit_vector(px - center), ux, vx);
+            get_sphere_uv(unit_vector(py - center), uy, vy);
+            rec.set_footprint(fmax(sphere_uv_distance(u, v, ux, vx), sphere_uv_distance(u, v, uy, vy)));
+        }
         
 
         return true;
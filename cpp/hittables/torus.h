@@ -0,0 +1,203 @@
+/*
+Torus.h
+=======
+
+The Torus class represents a torus centered at the origin around the
+y-axis: a tube with the minor radius swept along a circle with the major
+radius in the xz-plane. Use the Rotate and Translate wrappers to place it.
+
+A point p is on the torus if
+    (|p|^2 + R^2 - r^2)^2 = 4 R^2 (p_x^2 + p_z^2),
+which is a quartic in the ray parameter t. Its real roots are isolated
+between the roots of its derivatives, which split the range of t into
+intervals where the quartic is monotonic, and found by bisection in the
+intervals where it changes sign.
+*/
+
+#ifndef TORUS_H
+#define TORUS_H
+
+#include <cmath>
+#include <vector>
+#include <algorithm>
+
+#include "hittable.h"
+#include "vec3.h"
+#include "ray.h"
+#include "hit_record.h"
+
+/**
+ * @brief     Evaluate a polynomial.
+ * @param[in] coefficients Coefficients from the constant term up.
+ * @param[in] degree Degree of the polynomial.
+ * @param[in] x Point to evaluate at.
+ * @return    Value of the polynomial.
+ */
+inline double evaluate_polynomial(const double *coefficients, int degree, double x)
+{
+    double value = coefficients[degree];
+    for (int k = degree - 1; k >= 0; k--)
+        value = value * x + coefficients[k];
+    return value;
+}
+
+/**
+ * @brief     Find the real roots of a polynomial in an interval, in increasing order.
+ *            Roots where the polynomial touches zero without changing sign are missed.
+ * @param[in] coefficients Coefficients from the constant term up, at most degree 4.
+ * @param[in] degree Degree of the polynomial.
+ * @param[in] lo Lower end of the interval.
+ * @param[in] hi Upper end of the interval.
+ * @param[out] roots Roots, appended.
+ */
+inline void solve_polynomial(const double *coefficients, int degree, double lo, double hi, std::vector<double> &roots)
+{
+    if (degree == 1)
+    {
+        if (coefficients[1] != 0)
+        {
+            double root = -coefficients[0] / coefficients[1];
+            if (root >= lo && root <= hi)
+                roots.push_back(root);
+        }
+        return;
+    }
+
+    // the polynomial is monotonic between the roots of its derivative
+    double derivative[4];
+    for (int k = 1; k <= degree; k++)
+        derivative[k - 1] = k * coefficients[k];
+    std::vector<double> bounds = {lo};
+    solve_polynomial(derivative, degree - 1, lo, hi, bounds);
+    bounds.push_back(hi);
+
+    for (size_t k = 0; k + 1 < bounds.size(); k++)
+    {
+        double a = bounds[k];
+        double b = bounds[k + 1];
+        double value_a = evaluate_polynomial(coefficients, degree, a);
+        double value_b = evaluate_polynomial(coefficients, degree, b);
+        if (value_a == 0)
+        {
+            if (roots.empty() || roots.back() != a)
+                roots.push_back(a);
+            continue;
+        }
+        if ((value_a < 0) == (value_b < 0))
+            continue;
+        for (int iteration = 0; iteration < 64 && b - a > 1e-12 * (1 + fabs(a)); iteration++)
+        {
+            double middle = 0.5 * (a + b);
+            double value_middle = evaluate_polynomial(coefficients, degree, middle);
+            if ((value_middle < 0) == (value_a < 0))
+            {
+                a = middle;
+                value_a = value_middle;
+            }
+            else
+                b = middle;
+        }
+        roots.push_back(0.5 * (a + b));
+    }
+}
+
+/**
+ * @brief      Class for torus.
+ */
+class Torus : public Hittable
+{
+public:
+    Torus() {}
+    Torus(double _major_radius, double _minor_radius, Material *m)
+        : major_radius(_major_radius), minor_radius(_minor_radius), material(m) {}
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override;
+    std::string to_string() const { return "Torus"; }
+
+public:
+    double major_radius;
+    double minor_radius;
+    Material *material;
+};
+
+/**
+ * @brief     Check if a ray hits the torus.
+ *            The ray is first clipped to the bounding sphere of the torus, and
+ *            the quartic is set up from the point where the ray enters it, which
+ *            keeps the coefficients small for rays starting far away.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Torus::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    // bounding sphere
+    double outer = major_radius + minor_radius;
+    double a = r.direction.length_squared();
+    double half_b = dot(r.origin, r.direction);
+    double c = r.origin.length_squared() - outer * outer;
+    double discriminant = half_b * half_b - a * c;
+    if (discriminant < 0)
+        return false;
+    double sqrt_discriminant = sqrt(discriminant);
+    double t_enter = fmax(t_min, (-half_b - sqrt_discriminant) / a);
+    double t_exit = fmin(t_max, (-half_b + sqrt_discriminant) / a);
+    if (t_exit < t_enter)
+        return false;
+
+    // quartic in s = t - t_enter
+    Vec3 o = r.point_at_parameter(t_enter);
+    const Vec3 &d = r.direction;
+    double R2 = major_radius * major_radius;
+    double k = o.length_squared() + R2 - minor_radius * minor_radius;
+    double b = 2 * dot(o, d);
+    double e = d.x() * d.x() + d.z() * d.z();
+    double f = 2 * (o.x() * d.x() + o.z() * d.z());
+    double g = o.x() * o.x() + o.z() * o.z();
+    double coefficients[5] = {
+        k * k - 4 * R2 * g,
+        2 * b * k - 4 * R2 * f,
+        b * b + 2 * a * k - 4 * R2 * e,
+        2 * a * b,
+        a * a};
+
+    std::vector<double> roots;
+    solve_polynomial(coefficients, 4, 0, t_exit - t_enter, roots);
+    for (double s : roots)
+    {
+        double t = t_enter + s;
+        if (t < t_min || t > t_max)
+            continue;
+
+        Vec3 p = r.point_at_parameter(t);
+        // the normal points away from the closest point on the major circle
+        double ring = sqrt(p.x() * p.x() + p.z() * p.z());
+        Vec3 center = ring > 0 ? Vec3(p.x(), 0, p.z()) * (major_radius / ring) : Vec3(major_radius, 0, 0);
+        Vec3 normal = unit_vector(p - center);
+        double u = (atan2(p.z(), p.x()) + M_PI) / (2 * M_PI);
+        double v = (atan2(p.y(), ring - major_radius) + M_PI) / (2 * M_PI);
+        rec.set(t, normal, p, material, u, v);
+        if (ring > 0)
+            rec.set_tangent(Vec3(-p.z(), 0, p.x()) / ring);
+        return true;
+    }
+    return false;
+}
+
+/**
+ * @brief     Get the bounding box of the torus.
+ * @param[in] time0 Start time.
+ * @param[in] time1 End time.
+ * @param[out] output_box Bounding box.
+ * @return    True if bounding box is valid.
+ */
+bool Torus::bounding_box(double time0, double time1, AABB &output_box) const
+{
+    double outer = major_radius + minor_radius;
+    output_box = AABB(Vec3(-outer, -minor_radius, -outer), Vec3(outer, minor_radius, outer));
+    return true;
+}
+
+#endif // TORUS_H
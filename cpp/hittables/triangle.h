@@ -0,0 +1,134 @@
+/*
+Triangle.h
+==========
+
+The Triangle class represents a triangle defined by three vertices.
+Intersections are computed with the Möller–Trumbore algorithm.
+
+The SmoothTriangle class additionally stores a normal at each vertex. The
+shading normal at a hit is interpolated from them with the barycentric
+coordinates, which hides the edges between the triangles of a curved mesh.
+*/
+
+#ifndef TRIANGLE_H
+#define TRIANGLE_H
+
+#include <cmath>
+
+#include "hittable.h"
+#include "vec3.h"
+#include "ray.h"
+#include "hit_record.h"
+
+/**
+ * @brief      Class for triangle.
+ */
+class Triangle : public Hittable
+{
+public:
+    Triangle() {}
+    Triangle(const Vec3 &_v0, const Vec3 &_v1, const Vec3 &_v2, Material *m) : v0(_v0), v1(_v1), v2(_v2), material(m) {}
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    virtual bool bounding_box(double time0, double time1, AABB &output_box) const override;
+    std::string to_string() const { return "Triangle"; }
+
+public:
+    Vec3 v0, v1, v2;
+    Material *material;
+};
+
+/**
+ * @brief     Check if a ray hits the triangle.
+ *            The barycentric coordinates of the hit point are stored as texture coordinates.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool Triangle::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    Vec3 edge1 = v1 - v0;
+    Vec3 edge2 = v2 - v0;
+    Vec3 p = cross(r.direction, edge2);
+    double determinant = dot(edge1, p);
+    if (fabs(determinant) < 1e-12)
+        // ray parallel to the triangle
+        return false;
+
+    double inverse_determinant = 1.0 / determinant;
+    Vec3 s = r.origin - v0;
+    double u = dot(s, p) * inverse_determinant;
+    if (u < 0 || u > 1)
+        return false;
+
+    Vec3 q = cross(s, edge1);
+    double v = dot(r.direction, q) * inverse_determinant;
+    if (v < 0 || u + v > 1)
+        return false;
+
+    double t = dot(edge2, q) * inverse_determinant;
+    if (t < t_min || t > t_max)
+        return false;
+
+    Vec3 normal = unit_vector(cross(edge1, edge2));
+    rec.set(t, normal, r.point_at_parameter(t), material, u, v);
+    return true;
+}
+
+/**
+ * @brief     Get the bounding box of the triangle.
+ *            Axes with (almost) no extent are padded, so that axis-aligned triangles
+ *            still get a box with non-zero volume.
+ * @param[in] time0 Start time.
+ * @param[in] time1 End time.
+ * @param[out] output_box Bounding box.
+ * @return    True if bounding box is valid.
+ */
+bool Triangle::bounding_box(double time0, double time1, AABB &output_box) const
+{
+    Vec3 minimum = Vec3::min(v0, Vec3::min(v1, v2));
+    Vec3 maximum = Vec3::max(v0, Vec3::max(v1, v2));
+    output_box = AABB(minimum, maximum).pad();
+    return true;
+}
+
+/**
+ * @brief      Class for triangle with vertex normals.
+ */
+class SmoothTriangle : public Triangle
+{
+public:
+    SmoothTriangle() {}
+    SmoothTriangle(const Vec3 &_v0, const Vec3 &_v1, const Vec3 &_v2, const Vec3 &_n0, const Vec3 &_n1, const Vec3 &_n2, Material *m)
+        : Triangle(_v0, _v1, _v2, m), n0(unit_vector(_n0)), n1(unit_vector(_n1)), n2(unit_vector(_n2)) {}
+    virtual bool hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const override;
+    std::string to_string() const { return "SmoothTriangle"; }
+
+public:
+    Vec3 n0, n1, n2;
+};
+
+/**
+ * @brief     Check if a ray hits the triangle.
+ *            The normal stays the normal of the plane of the triangle, the
+ *            shading normal is interpolated from the vertex normals.
+ * @param[in] r Ray to check.
+ * @param[in] t_min Minimum distance along the ray to check.
+ * @param[in] t_max Maximum distance along the ray to check.
+ * @param[out] rec Hit record.
+ * @return    True if hit.
+ */
+bool SmoothTriangle::hit(const Ray &r, double t_min, double t_max, HitRecord &rec) const
+{
+    if (!Triangle::hit(r, t_min, t_max, rec))
+        return false;
+
+    // u and v are the barycentric weights of v1 and v2
+    Vec3 shading_normal = (1 - rec.u - rec.v) * n0 + rec.u * n1 + rec.v * n2;
+    if (shading_normal.length_squared() > 0)
+        rec.set_shading_normal(unit_vector(shading_normal));
+    return true;
+}
+
+#endif // TRIANGLE_H
@@ -15,6 +15,21 @@ It also saves the image data.
 #include "color.h"
 #include "image_data.h"
 
+/**
+ * @brief      How the camera maps the image to rays.
+ * @details    Perspective shoots rays from the lens through the viewport.
+ *             Orthographic shoots parallel rays from an image plane, see set_orthographic.
+ *             Panoramic shoots rays in all directions from look_from, with the
+ *             longitude across the width and the latitude across the height of
+ *             the image (equirectangular), an aspect ratio of 2 keeps the pixels square.
+ */
+enum class Projection
+{
+    Perspective,
+    Orthographic,
+    Panoramic
+};
+
 /**
  * @brief      Class for camera.
  */
@@ -27,8 +42,20 @@ public:
         set_up(vfov, aspect_ratio, aperture, focus_dist, look_from, look_at, time0, time1);
     };
 
+    /**
+     * @brief      Sets the up direction of the camera, (0, 1, 0) by default.
+     *             It is used by the next call of set_up and must not be parallel
+     *             to the viewing direction, e.g. use (0, 0, 1) to look straight down.
+     * @param[in]  vup   The up direction
+     */
+    void set_vup(const Vec3 &vup)
+    {
+        this->vup = vup;
+    }
+
     /**
      * @brief      Sets up the camera.
+     *             The image is upright with respect to the up direction, see set_vup.
      * @param[in]  vfov              The vertical field of view
      * @param[in]  aspect_ratio      The aspect ratio
      * @param[in]  aperture          The aperture
@@ -47,7 +74,7 @@ public:
         this->aspect_ratio = aspect_ratio;
 
         this->w = unit_vector(look_from - look_at);
-        this->u = unit_vector(cross(Vec3(0, 1, 0), w));
+        this->u = unit_vector(cross(vup, w));
         this->v = cross(w, u);
 
         this->look_from = look_from;
@@ -61,6 +88,62 @@ public:
         this->time1 = time1;
     };
 
+    /**
+     * @brief      Switches the camera to orthographic projection.
+     *             All rays are parallel to the viewing direction and start on
+     *             an image plane of the given width through look_from.
+     *             The basis vectors of set_up are kept.
+     * @param[in]  width  The width of the image plane in world units
+     */
+    void set_orthographic(double width)
+    {
+        this->orthographic_width = width;
+        this->projection = Projection::Orthographic;
+    }
+
+    /**
+     * @brief      Sets how the camera maps the image to rays.
+     *             The basis vectors of set_up are kept, so the projections can
+     *             be switched without setting up the camera again.
+     * @param[in]  projection  The projection
+     */
+    void set_projection(Projection projection)
+    {
+        this->projection = projection;
+    }
+
+    /**
+     * @brief      Sets the shape of the aperture for defocus blur.
+     *             With three or more blades the lens is sampled in a regular
+     *             polygon with that many corners, giving polygonal bokeh.
+     * @param[in]  blades  The number of aperture blades, 0 for a round aperture
+     */
+    void set_bokeh_blades(int blades)
+    {
+        this->bokeh_blades = blades;
+    }
+
+    /**
+     * @brief      Sets the strength of chromatic aberration of the lens.
+     *             The red, green and blue channels are focused at the focus distance
+     *             times 1 - amount, 1 and 1 + amount, which gives colored fringes
+     *             at edges that are out of focus. Needs an aperture larger than zero.
+     * @param[in]  amount  The relative difference of the focus distances, 0 to disable
+     */
+    void set_chromatic_aberration(double amount)
+    {
+        this->chromatic_aberration = amount;
+    }
+
+    /**
+     * @brief      Enables ray differentials on the rays of the renderer.
+     * @param[in]  enabled  Whether camera rays carry ray differentials
+     */
+    void set_ray_differentials(bool enabled)
+    {
+        this->ray_differentials = enabled;
+    }
+
     /**
      * @brief      Sets the image data.
      * @param[in]  image_width  The image width
@@ -78,16 +161,96 @@ public:
     */
     Ray get_ray(double u, double v) const
     {
-        Vec3 rd = lens_radius * random_in_unit_disk();
+        return get_ray(u, v, 1);
+    };
+
+    /**
+     * @brief      Generates a ray for one color channel, see set_chromatic_aberration.
+     *             Without chromatic aberration the ray is the same as for all channels.
+     * @param[in]  u        The horizontal coordinate
+     * @param[in]  v        The vertical coordinate
+     * @param[in]  channel  The color channel, 0 red, 1 green, 2 blue
+     * @return     A ray from the camera to the scene.
+    */
+    Ray get_ray(double u, double v, int channel) const
+    {
+        if (projection == Projection::Orthographic)
+        {
+            return Ray(orthographic_origin(u, v), -w, random_double(time0, time1));
+        }
+        if (projection == Projection::Panoramic)
+        {
+            return Ray(look_from, panoramic_direction(u, v), random_double(time0, time1));
+        }
+        Vec3 rd = lens_radius * (bokeh_blades >= 3 ? random_in_unit_polygon(bokeh_blades) : random_in_unit_disk());
         Vec3 offset = this->u * rd.x() + this->v * rd.y();
+        double focus_scale = 1 + chromatic_aberration * (channel - 1);
+        Vec3 target = look_from + focus_scale * (lower_left_corner + u * horizontal + v * vertical - look_from);
         return Ray(look_from + offset,
-                   lower_left_corner + u * horizontal + v * vertical - look_from - offset,
+                   target - look_from - offset,
                    random_double(time0, time1));
     };
 
+    /**
+     * @brief      Generates a ray with ray differentials through the neighbouring pixels.
+     *             The differential rays start at the origin of the ray, or at the
+     *             shifted image plane of an orthographic camera.
+     * @param[in]  u     The horizontal coordinate
+     * @param[in]  v     The vertical coordinate
+     * @param[in]  du    The width of a pixel in the horizontal coordinate
+     * @param[in]  dv    The height of a pixel in the vertical coordinate
+     * @return     A ray from the camera to the scene with differentials.
+    */
+    Ray get_ray(double u, double v, double du, double dv) const
+    {
+        Ray r = get_ray(u, v);
+        if (projection == Projection::Orthographic)
+        {
+            r.set_differentials(orthographic_origin(u + du, v), r.direction, orthographic_origin(u, v + dv), r.direction);
+        }
+        else if (projection == Projection::Panoramic)
+        {
+            r.set_differentials(r.origin, panoramic_direction(u + du, v), r.origin, panoramic_direction(u, v + dv));
+        }
+        else
+        {
+            Vec3 target = lower_left_corner + u * horizontal + v * vertical;
+            r.set_differentials(r.origin, target + du * horizontal - r.origin, r.origin, target + dv * vertical - r.origin);
+        }
+        return r;
+    }
+
+    /**
+     * @brief      The origin of an orthographic ray on the image plane through look_from.
+     * @param[in]  u     The horizontal coordinate
+     * @param[in]  v     The vertical coordinate
+     * @return     The origin
+     */
+    Vec3 orthographic_origin(double u, double v) const
+    {
+        return look_from + (u - 0.5) * orthographic_width * this->u + (v - 0.5) * orthographic_width / aspect_ratio * this->v;
+    }
+
+    /**
+     * @brief      The direction of a panoramic ray.
+     *             The center of the image looks along -w, the left and right
+     *             edges meet behind the camera and the top and bottom edges
+     *             look along v and -v.
+     * @param[in]  u     The horizontal coordinate, mapped to longitudes from -180 to 180 degrees
+     * @param[in]  v     The vertical coordinate, mapped to latitudes from -90 to 90 degrees
+     * @return     The unit direction
+     */
+    Vec3 panoramic_direction(double u, double v) const
+    {
+        double longitude = (u - 0.5) * 2 * pi;
+        double latitude = (v - 0.5) * pi;
+        return cos(latitude) * (sin(longitude) * this->u - cos(longitude) * w) + sin(latitude) * this->v;
+    }
+
 public:
     Vec3 look_from;
     Vec3 look_at;
+    Vec3 vup = Vec3(0, 1, 0);
     Vec3 lower_left_corner;
     Vec3 horizontal;
     Vec3 vertical;
@@ -97,6 +260,11 @@ public:
     double viewport_height;
     double time0, time1; // shutter open/close times
     double aspect_ratio;
+    Projection projection = Projection::Perspective;
+    double orthographic_width = 1.0;
+    int bokeh_blades = 0;
+    bool ray_differentials = false;
+    double chromatic_aberration = 0.0;
     ImageData image;
 };
 
@@ -12,17 +12,138 @@ Contains functions to write the image data to a file.
 #include <vector>
 #include <iostream>
 #include <fstream>
+#include <functional>
 #include "color.h"
 #include "ray_tracer.h"
+#include "png.h"
+#include "sampler.h"
 
 /**
- * @brief      Gamma correction with gamma 2.2.
- * @param[in]  x     The value to be gamma corrected
+ * @brief      Gamma correction, gamma 2.2 by default.
+ *             A gamma of 1.0 leaves the value unchanged.
+ * @param[in]  x      The value to be gamma corrected
+ * @param[in]  gamma  The gamma
  * @return     The gamma corrected value
  */
-double gamma_correction(double x)
+double gamma_correction(double x, double gamma = 2.2)
 {
-    return std::pow(x, 1 / 2.2);
+    return std::pow(x, 1 / gamma);
+}
+
+/**
+ * @brief      Transfer function from linear values to the values written to 8 bit images.
+ * @details    Gamma uses the power curve with the gamma of the image data.
+ *             Srgb uses the piecewise sRGB curve, linear near black and a
+ *             2.4 power curve above. Linear writes the values unchanged.
+ */
+enum class ColorSpace
+{
+    Gamma,
+    Srgb,
+    Linear
+};
+
+/**
+ * @brief      The sRGB transfer function.
+ * @param[in]  x     The linear value
+ * @return     The encoded value
+ */
+inline double srgb_encode(double x)
+{
+    if (x <= 0.0031308)
+        return 12.92 * x;
+    return 1.055 * std::pow(x, 1 / 2.4) - 0.055;
+}
+
+/**
+ * @brief      Encode a linear color channel in a color space.
+ * @param[in]  x            The linear value
+ * @param[in]  color_space  The color space
+ * @param[in]  gamma        The gamma of the Gamma color space
+ * @return     The encoded value
+ */
+inline double encode_color_space(double x, ColorSpace color_space, double gamma)
+{
+    switch (color_space)
+    {
+    case ColorSpace::Srgb:
+        return srgb_encode(x);
+    case ColorSpace::Linear:
+        return x;
+    default:
+        return gamma_correction(x, gamma);
+    }
+}
+
+/**
+ * @brief      Operator that maps averaged pixel values to the displayable range.
+ * @details    Clamp cuts off values above 1. Reinhard maps c to c / (1 + c).
+ *             ReinhardExtended maps c to c (1 + c / w^2) / (1 + c), so that the
+ *             white point w is mapped to 1.
+ */
+enum class ToneMap
+{
+    Clamp,
+    Reinhard,
+    ReinhardExtended
+};
+
+/**
+ * @brief      Apply a tone mapping operator to a color channel.
+ * @param[in]  x            The value
+ * @param[in]  tone_map     The tone mapping operator
+ * @param[in]  white_point  The smallest value mapped to 1 by ReinhardExtended
+ * @return     The tone mapped value
+ */
+inline double tone_map_value(double x, ToneMap tone_map, double white_point = 1.0)
+{
+    switch (tone_map)
+    {
+    case ToneMap::Reinhard:
+        return x / (1 + x);
+    case ToneMap::ReinhardExtended:
+        return x * (1 + x / (white_point * white_point)) / (1 + x);
+    default:
+        return x;
+    }
+}
+
+/**
+ * @brief      Reconstruction filter used to place the samples of a pixel.
+ * @details    Box samples uniformly within the pixel. Tent samples within one
+ *             pixel width around the pixel center, with a density that falls
+ *             off linearly towards the neighbouring pixel centers.
+ */
+enum class PixelFilter
+{
+    Box,
+    Tent
+};
+
+/**
+ * @brief      How the samples of a pixel are distributed.
+ * @details    Random places every sample independently. Stratified divides
+ *             the pixel into a k x k grid for k * k samples and places one
+ *             sample in every cell. If the number of samples is not a square,
+ *             the samples are placed randomly. Halton places the samples with
+ *             the Halton sequence, see HaltonSampler, for any number of samples.
+ */
+enum class SampleStrategy
+{
+    Random,
+    Stratified,
+    Halton
+};
+
+/**
+ * @brief      Map a uniform number in [0, 1) to the tent distribution on [-1, 1].
+ * @param[in]  xi    The uniform number
+ * @return     The offset
+ */
+inline double tent_offset(double xi)
+{
+    double r = 2 * xi;
+    return r < 1 ? sqrt(r) - 1 : 1 - sqrt(2 - r);
 }
 
 /**
@@ -36,6 +157,14 @@ private:
     double aspect_ratio;
     std::vector<std::vector<std::vector<double>>> pixels;
     std::vector<std::vector<int>> number_of_samples;
+    double gamma = 2.2;
+    ColorSpace color_space = ColorSpace::Gamma;
+    PixelFilter pixel_filter = PixelFilter::Box;
+    SampleStrategy sample_strategy = SampleStrategy::Random;
+    ToneMap tone_map = ToneMap::Clamp;
+    double white_point = 1.0;
+    double get_pixel_offset(double xi) const;
+    void get_rgb(int i, int j, int &r, int &g, int &b) const;
 
 public:
     ImageData(){};
@@ -43,13 +172,35 @@ public:
     ImageData(int width, int height);
     int get_width() const;
     int get_height() const;
-    int write_ppm(std::ostream &out);
-    void write_to_ppm(std::string filename);
-    void write_to_png(std::string filename);
+    int write_ppm(std::ostream &out) const;
+    void write_to_ppm(std::string filename) const;
+    int write_ppm_binary(std::ostream &out) const;
+    void write_to_ppm_binary(std::string filename) const;
+    int write_png(std::ostream &out) const;
+    void write_to_png(std::string filename) const;
+    int write_hdr(std::ostream &out) const;
+    void write_to_hdr(std::string filename) const;
+    Color get_radiance(int i, int j) const;
+    void for_each_pixel(const std::function<void(int, int, const Color &)> &visit) const;
+    ImageData bilateral_denoise(double spatial_sigma, double range_sigma) const;
     int add_color(int i, int j, Color color);
+    int add_samples(int i, int j, Color color_sum, int samples);
+    int get_number_of_samples(int i, int j) const;
+    void clear();
     double get_aspect_ratio() const;
+    void set_gamma(double gamma);
+    double get_gamma() const;
+    void set_color_space(ColorSpace color_space);
+    ColorSpace get_color_space() const;
+    void set_pixel_filter(PixelFilter filter);
+    PixelFilter get_pixel_filter() const;
+    void set_sample_strategy(SampleStrategy strategy);
+    SampleStrategy get_sample_strategy() const;
+    void set_tone_map(ToneMap tone_map, double white_point = 1.0);
+    ToneMap get_tone_map() const;
     double get_u(int i) const;
     double get_v(int j) const;
+    void get_uv(int i, int j, int sample, int number_of_samples, double &u, double &v) const;
 };
 
 /**
@@ -95,6 +246,41 @@ int ImageData::add_color(int i, int j, Color color)
     return 0;
 }
 
+/**
+ * @brief      Adds the sum of several samples to the pixel at (i, j).
+ * @param[in]  i          The i coordinate of the pixel
+ * @param[in]  j          The j coordinate of the pixel
+ * @param[in]  color_sum  The sum of the sample colors
+ * @param[in]  samples    The number of samples in the sum
+*/
+int ImageData::add_samples(int i, int j, Color color_sum, int samples)
+{
+    this->pixels[j][i][0] += color_sum.r();
+    this->pixels[j][i][1] += color_sum.g();
+    this->pixels[j][i][2] += color_sum.b();
+    this->number_of_samples[j][i] += samples;
+    return 0;
+}
+
+/**
+ * @brief      Removes all samples, keeping the size and the output settings.
+*/
+void ImageData::clear()
+{
+    this->pixels = std::vector<std::vector<std::vector<double>>>(this->height, std::vector<std::vector<double>>(this->width, std::vector<double>(3, 0.0f)));
+    this->number_of_samples = std::vector<std::vector<int>>(this->height, std::vector<int>(this->width, 0));
+}
+
+/**
+ * @brief      Get the number of samples added to a pixel.
+ * @param[in]  i     The column of the pixel
+ * @param[in]  j     The row of the pixel
+*/
+int ImageData::get_number_of_samples(int i, int j) const
+{
+    return this->number_of_samples[j][i];
+}
+
 /**
  * @brief      Get image width in pixels.
 */
@@ -119,13 +305,104 @@ double ImageData::get_aspect_ratio() const
     return this->aspect_ratio;
 }
 
+/**
+ * @brief      Set the gamma used when writing the image.
+ *             1.0 disables gamma correction, the default is 2.2.
+ * @param[in]  gamma  The gamma
+*/
+void ImageData::set_gamma(double gamma)
+{
+    this->gamma = gamma;
+}
+
+/**
+ * @brief      Get the gamma used when writing the image.
+*/
+double ImageData::get_gamma() const
+{
+    return this->gamma;
+}
+
+/**
+ * @brief      Set the color space used when writing 8 bit images.
+ *             The gamma is only used by the Gamma color space, the default.
+ * @param[in]  color_space  The color space
+*/
+void ImageData::set_color_space(ColorSpace color_space)
+{
+    this->color_space = color_space;
+}
+
+/**
+ * @brief      Get the color space used when writing 8 bit images.
+*/
+ColorSpace ImageData::get_color_space() const
+{
+    return this->color_space;
+}
+
+/**
+ * @brief      Set the filter used to place the samples within a pixel.
+ *             The samples are drawn from the filter distribution, so every
+ *             sample has the same weight and the accumulation does not change.
+ * @param[in]  filter  The pixel filter
+*/
+void ImageData::set_pixel_filter(PixelFilter filter)
+{
+    this->pixel_filter = filter;
+}
+
+/**
+ * @brief      Get the filter used to place the samples within a pixel.
+*/
+PixelFilter ImageData::get_pixel_filter() const
+{
+    return this->pixel_filter;
+}
+
+/**
+ * @brief      Set how the samples of a pixel are distributed.
+ * @param[in]  strategy  The sample strategy
+*/
+void ImageData::set_sample_strategy(SampleStrategy strategy)
+{
+    this->sample_strategy = strategy;
+}
+
+/**
+ * @brief      Get how the samples of a pixel are distributed.
+*/
+SampleStrategy ImageData::get_sample_strategy() const
+{
+    return this->sample_strategy;
+}
+
+/**
+ * @brief      Set the tone mapping operator applied before gamma correction.
+ * @param[in]  tone_map     The tone mapping operator
+ * @param[in]  white_point  The white point, only used by ReinhardExtended
+*/
+void ImageData::set_tone_map(ToneMap tone_map, double white_point)
+{
+    this->tone_map = tone_map;
+    this->white_point = white_point;
+}
+
+/**
+ * @brief      Get the tone mapping operator applied before gamma correction.
+*/
+ToneMap ImageData::get_tone_map() const
+{
+    return this->tone_map;
+}
+
 /**
  * @brief      Write ppm image data to a file stream.
  *             The color of each pixel is averaged over the number of samples.
- *             The color is gamma corrected.
+ *             The color is encoded in the color space of the image.
  * @param      out   The output stream
 */
-int ImageData::write_ppm(std::ostream &out)
+int ImageData::write_ppm(std::ostream &out) const
 {
 
     // write header
@@ -136,20 +413,11 @@ int ImageData::write_ppm(std::ostream &out)
     int r;
     int g;
     int b;
-    int number_of_samples;
     for (int j = this->height - 1; j >= 0; --j)
     {
         for (int i = 0; i < this->width; ++i)
         {
-            // normalize color and convert to int
-            number_of_samples = this->number_of_samples[j][i];
-            r = int(255.999 * gamma_correction(this->pixels[j][i][0] / number_of_samples));
-            g = int(255.999 * gamma_correction(this->pixels[j][i][1] / number_of_samples));
-            b = int(255.999 * gamma_correction(this->pixels[j][i][2] / number_of_samples));
-            r = clamp(r, 0, 255);
-            g = clamp(g, 0, 255);
-            b = clamp(b, 0, 255);
-
+            this->get_rgb(i, j, r, g, b);
             out << r << " " << g << " " << b << "\n";
         }
     }
@@ -159,10 +427,10 @@ int ImageData::write_ppm(std::ostream &out)
 /**
  * @brief      Write ppm image data to a file.
  *             The color of each pixel is averaged over the number of samples.
- *             The color is gamma corrected.
+ *             The color is encoded in the color space of the image.
  * @param[in]  filename  The filename
 */
-void ImageData::write_to_ppm(std::string filename)
+void ImageData::write_to_ppm(std::string filename) const
 {
     std::cout << "Writing to file " << filename << std::endl;
     std::ofstream out(filename); // opens the file
@@ -171,6 +439,252 @@ void ImageData::write_to_ppm(std::string filename)
 
 }   
 
+/**
+ * @brief      Write binary ppm (P6) image data to a file stream.
+ *             Uses the same header and row order as write_ppm, but stores
+ *             each color channel as a single byte.
+ * @param      out   The output stream
+*/
+int ImageData::write_ppm_binary(std::ostream &out) const
+{
+    // write header
+    out << "P6\n"
+        << this->width << " " << this->height << "\n255\n";
+
+    // write pixels
+    int r;
+    int g;
+    int b;
+    for (int j = this->height - 1; j >= 0; --j)
+    {
+        for (int i = 0; i < this->width; ++i)
+        {
+            this->get_rgb(i, j, r, g, b);
+            out.put(static_cast<char>(r));
+            out.put(static_cast<char>(g));
+            out.put(static_cast<char>(b));
+        }
+    }
+    return 0;
+}
+
+/**
+ * @brief      Write binary ppm (P6) image data to a file.
+ * @param[in]  filename  The filename
+*/
+void ImageData::write_to_ppm_binary(std::string filename) const
+{
+    std::cout << "Writing to file " << filename << std::endl;
+    std::ofstream out(filename, std::ios::binary);
+    this->write_ppm_binary(out);
+    out.close();
+}
+
+/**
+ * @brief      Write png image data to a file stream.
+ *             Uses the same row order as write_ppm.
+ * @param      out   The output stream
+*/
+int ImageData::write_png(std::ostream &out) const
+{
+    std::vector<unsigned char> rgb;
+    rgb.reserve(3 * this->width * this->height);
+    int r;
+    int g;
+    int b;
+    for (int j = this->height - 1; j >= 0; --j)
+    {
+        for (int i = 0; i < this->width; ++i)
+        {
+            this->get_rgb(i, j, r, g, b);
+            rgb.push_back(r);
+            rgb.push_back(g);
+            rgb.push_back(b);
+        }
+    }
+    ::write_png(out, this->width, this->height, rgb);
+    return 0;
+}
+
+/**
+ * @brief      Write png image data to a file.
+ * @param[in]  filename  The filename
+*/
+void ImageData::write_to_png(std::string filename) const
+{
+    std::cout << "Writing to file " << filename << std::endl;
+    std::ofstream out(filename, std::ios::binary);
+    this->write_png(out);
+    out.close();
+}
+
+/**
+ * @brief      Write Radiance HDR (RGBE) image data to a file stream.
+ *             The pixels are the averaged samples without tone mapping,
+ *             gamma correction or clamping, so values above 1 are kept.
+ *             Uses the same row order as write_ppm, without run length encoding.
+ * @param      out   The output stream
+*/
+int ImageData::write_hdr(std::ostream &out) const
+{
+    // write header
+    out << "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n"
+        << "-Y " << this->height << " +X " << this->width << "\n";
+
+    // write pixels, the three channels share the exponent of the largest one
+    for (int j = this->height - 1; j >= 0; --j)
+    {
+        for (int i = 0; i < this->width; ++i)
+        {
+            Color color = this->get_radiance(i, j);
+            double largest = fmax(color.r(), fmax(color.g(), color.b()));
+            unsigned char rgbe[4] = {0, 0, 0, 0};
+            if (largest > 1e-32)
+            {
+                int exponent;
+                double scale = frexp(largest, &exponent) * 256.0 / largest;
+                rgbe[0] = static_cast<unsigned char>(fmax(color.r(), 0.0) * scale);
+                rgbe[1] = static_cast<unsigned char>(fmax(color.g(), 0.0) * scale);
+                rgbe[2] = static_cast<unsigned char>(fmax(color.b(), 0.0) * scale);
+                rgbe[3] = static_cast<unsigned char>(exponent + 128);
+            }
+            out.write(reinterpret_cast<const char *>(rgbe), 4);
+        }
+    }
+    return 0;
+}
+
+/**
+ * @brief      Write Radiance HDR (RGBE) image data to a file.
+ * @param[in]  filename  The filename
+*/
+void ImageData::write_to_hdr(std::string filename) const
+{
+    std::cout << "Writing to file " << filename << std::endl;
+    std::ofstream out(filename, std::ios::binary);
+    this->write_hdr(out);
+    out.close();
+}
+
+/**
+ * @brief      Get the color of a pixel averaged over its samples, without
+ *             tone mapping and gamma correction.
+ * @param[in]  i     The i coordinate of the pixel
+ * @param[in]  j     The j coordinate of the pixel
+ * @return     The color, black if the pixel has no samples.
+*/
+Color ImageData::get_radiance(int i, int j) const
+{
+    int number_of_samples = this->number_of_samples[j][i];
+    if (number_of_samples == 0)
+        return Color(0, 0, 0);
+    return Color(this->pixels[j][i][0], this->pixels[j][i][1], this->pixels[j][i][2]) / number_of_samples;
+}
+
+/**
+ * @brief      Visit every pixel with its color averaged over its samples,
+ *             e.g. for custom tone mapping or output formats.
+ *             The colors are the same as those of get_radiance, the pixels
+ *             are visited row by row, starting with i = 0 and j = 0.
+ * @param[in]  visit  Called with the i and j coordinates and the color of each pixel
+*/
+void ImageData::for_each_pixel(const std::function<void(int, int, const Color &)> &visit) const
+{
+    for (int j = 0; j < this->height; ++j)
+    {
+        for (int i = 0; i < this->width; ++i)
+        {
+            visit(i, j, this->get_radiance(i, j));
+        }
+    }
+}
+
+/**
+ * @brief      Denoise the image with a bilateral filter.
+ *             Every pixel becomes a weighted average of its neighbors. The weight
+ *             falls off with the distance in pixels and with the difference of
+ *             the colors, so flat regions are smoothed while edges are kept.
+ *             The filter works on the colors averaged over their samples,
+ *             pixels without samples are left out.
+ * @param[in]  spatial_sigma  The standard deviation of the distance in pixels
+ * @param[in]  range_sigma    The standard deviation of the color difference
+ * @return     The denoised image with one sample per pixel and the output settings of this image.
+*/
+ImageData ImageData::bilateral_denoise(double spatial_sigma, double range_sigma) const
+{
+    ImageData denoised = *this;
+    denoised.clear();
+    int radius = static_cast<int>(std::ceil(2 * spatial_sigma));
+
+    for (int j = 0; j < this->height; ++j)
+    {
+        for (int i = 0; i < this->width; ++i)
+        {
+            if (this->number_of_samples[j][i] == 0)
+                continue;
+            Color center = this->get_radiance(i, j);
+            Color sum(0, 0, 0);
+            double weight_sum = 0;
+            for (int y = std::max(0, j - radius); y <= std::min(this->height - 1, j + radius); ++y)
+            {
+                for (int x = std::max(0, i - radius); x <= std::min(this->width - 1, i + radius); ++x)
+                {
+                    if (this->number_of_samples[y][x] == 0)
+                        continue;
+                    Color color = this->get_radiance(x, y);
+                    double distance_squared = (x - i) * (x - i) + (y - j) * (y - j);
+                    double weight = std::exp(-distance_squared / (2 * spatial_sigma * spatial_sigma)
+                                             - (color - center).length_squared() / (2 * range_sigma * range_sigma));
+                    sum += weight * color;
+                    weight_sum += weight;
+                }
+            }
+            denoised.add_color(i, j, sum / weight_sum);
+        }
+    }
+    return denoised;
+}
+
+/**
+ * @brief      Get the 8 bit rgb values of a pixel.
+ *             The color is averaged over the number of samples, tone mapped,
+ *             encoded in the color space and clamped to [0, 255].
+ * @param[in]  i     The i coordinate of the pixel
+ * @param[in]  j     The j coordinate of the pixel
+ * @param[out] r     The red value
+ * @param[out] g     The green value
+ * @param[out] b     The blue value
+*/
+void ImageData::get_rgb(int i, int j, int &r, int &g, int &b) const
+{
+    // normalize color and convert to int
+    int number_of_samples = this->number_of_samples[j][i];
+    double red = tone_map_value(this->pixels[j][i][0] / number_of_samples, this->tone_map, this->white_point);
+    double green = tone_map_value(this->pixels[j][i][1] / number_of_samples, this->tone_map, this->white_point);
+    double blue = tone_map_value(this->pixels[j][i][2] / number_of_samples, this->tone_map, this->white_point);
+    r = int(255.999 * encode_color_space(red, this->color_space, this->gamma));
+    g = int(255.999 * encode_color_space(green, this->color_space, this->gamma));
+    b = int(255.999 * encode_color_space(blue, this->color_space, this->gamma));
+    r = clamp(r, 0, 255);
+    g = clamp(g, 0, 255);
+    b = clamp(b, 0, 255);
+}
+
+/**
+ * @brief      Get an offset from the pixel center in pixels.
+ *             The distribution depends on the pixel filter.
+ * @param[in]  xi    A uniform number in [0, 1)
+ * @return     The offset
+*/
+double ImageData::get_pixel_offset(double xi) const
+{
+    if (this->pixel_filter == PixelFilter::Tent)
+    {
+        return tent_offset(xi);
+    }
+    return xi - 0.5;
+}
+
 /**
  * @brief      Get u coordinate of pixel.
  *             The u coordinate is the horizontal coordinate of the pixel center.
@@ -181,7 +695,7 @@ void ImageData::write_to_ppm(std::string filename)
 double ImageData::get_u(int i) const
 {
     double pixel_center = (i + 0.5) / this->width;
-    double random_offset = (random_double() - 0.5) / this->width;
+    double random_offset = get_pixel_offset(random_double()) / this->width;
     return pixel_center + random_offset;
 }
 
@@ -195,8 +709,47 @@ double ImageData::get_u(int i) const
 double ImageData::get_v(int j) const
 {
     double pixel_center = (j + 0.5) / this->height;
-    double random_offset = (random_double() - 0.5) / this->height;
+    double random_offset = get_pixel_offset(random_double()) / this->height;
     return pixel_center + random_offset;
 }
 
+/**
+ * @brief      Get u and v coordinates of one of several samples of a pixel.
+ *             With stratified sampling and a square number of samples, the
+ *             sample is jittered within its own cell of the pixel.
+ *             With Halton sampling, the sample is the point of the Halton
+ *             sequence after the samples already added to the pixel, so
+ *             progressive renders continue the sequence, shifted per pixel.
+ *             Otherwise the sample is drawn from a RandomSampler, which is
+ *             the same as get_u and get_v.
+ * @param[in]  i                  The i coordinate of the pixel
+ * @param[in]  j                  The j coordinate of the pixel
+ * @param[in]  sample             The index of the sample
+ * @param[in]  number_of_samples  The number of samples of the pixel
+ * @param[out] u                  The u coordinate of the sample
+ * @param[out] v                  The v coordinate of the sample
+*/
+void ImageData::get_uv(int i, int j, int sample, int number_of_samples, double &u, double &v) const
+{
+    int k = static_cast<int>(std::round(std::sqrt(number_of_samples)));
+    double xi_u, xi_v;
+    if (this->sample_strategy == SampleStrategy::Stratified && k * k == number_of_samples)
+    {
+        xi_u = ((sample % k) + random_double()) / k;
+        xi_v = ((sample / k) + random_double()) / k;
+    }
+    else if (this->sample_strategy == SampleStrategy::Halton)
+    {
+        HaltonSampler sampler(this->number_of_samples[j][i] + sample, pixel_hash(i, j, 0), pixel_hash(i, j, 1));
+        sampler.next_2d(xi_u, xi_v);
+    }
+    else
+    {
+        RandomSampler sampler;
+        sampler.next_2d(xi_u, xi_v);
+    }
+    u = (i + 0.5 + get_pixel_offset(xi_u)) / this->width;
+    v = (j + 0.5 + get_pixel_offset(xi_v)) / this->height;
+}
+
 #endif // IMAGE_DATA_H
\ No newline at end of file
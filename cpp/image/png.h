@@ -0,0 +1,138 @@
+/*
+png.h
+=====
+Minimal PNG encoder for 8 bit RGB images.
+
+The image data is stored in uncompressed deflate blocks, so no external
+compression library is needed. The files are larger than compressed PNGs,
+but can be opened by any image viewer.
+*/
+
+#ifndef PNG_H
+#define PNG_H
+
+#include <algorithm>
+#include <cstdint>
+#include <iostream>
+#include <string>
+#include <vector>
+
+/**
+ * @brief      Compute the CRC32 checksum used by PNG chunks.
+ * @param[in]  data    The data
+ * @param[in]  length  The number of bytes
+ * @param[in]  crc     The running checksum
+ * @return     The updated checksum.
+ */
+inline uint32_t png_crc(const unsigned char *data, size_t length, uint32_t crc = 0xffffffffu)
+{
+    static uint32_t table[256];
+    static bool table_computed = false;
+    if (!table_computed)
+    {
+        for (uint32_t n = 0; n < 256; n++)
+        {
+            uint32_t c = n;
+            for (int k = 0; k < 8; k++)
+                c = (c & 1) ? 0xedb88320u ^ (c >> 1) : c >> 1;
+            table[n] = c;
+        }
+        table_computed = true;
+    }
+    for (size_t n = 0; n < length; n++)
+        crc = table[(crc ^ data[n]) & 0xff] ^ (crc >> 8);
+    return crc;
+}
+
+/**
+ * @brief      Append a 32 bit big endian integer to a byte buffer.
+ * @param      buffer  The buffer
+ * @param[in]  value   The value
+ */
+inline void png_append_u32(std::vector<unsigned char> &buffer, uint32_t value)
+{
+    buffer.push_back((value >> 24) & 0xff);
+    buffer.push_back((value >> 16) & 0xff);
+    buffer.push_back((value >> 8) & 0xff);
+    buffer.push_back(value & 0xff);
+}
+
+/**
+ * @brief      Write a PNG chunk (length, type, data, crc) to a stream.
+ * @param      out   The output stream
+ * @param[in]  type  The four letter chunk type
+ * @param[in]  data  The chunk data
+ */
+inline void png_write_chunk(std::ostream &out, const std::string &type, const std::vector<unsigned char> &data)
+{
+    std::vector<unsigned char> chunk;
+    png_append_u32(chunk, static_cast<uint32_t>(data.size()));
+    chunk.insert(chunk.end(), type.begin(), type.end());
+    chunk.insert(chunk.end(), data.begin(), data.end());
+    uint32_t crc = png_crc(chunk.data() + 4, chunk.size() - 4) ^ 0xffffffffu;
+    png_append_u32(chunk, crc);
+    out.write(reinterpret_cast<const char *>(chunk.data()), chunk.size());
+}
+
+/**
+ * @brief      Write an 8 bit RGB image as PNG.
+ * @param      out     The output stream
+ * @param[in]  width   The image width in pixels
+ * @param[in]  height  The image height in pixels
+ * @param[in]  rgb     The pixel data, row by row from top to bottom, three bytes per pixel
+ */
+inline void write_png(std::ostream &out, int width, int height, const std::vector<unsigned char> &rgb)
+{
+    const unsigned char signature[8] = {0x89, 'P', 'N', 'G', '\r', '\n', 0x1a, '\n'};
+    out.write(reinterpret_cast<const char *>(signature), 8);
+
+    // header: size, bit depth 8, color type 2 (rgb), default compression, filter and interlacing
+    std::vector<unsigned char> header;
+    png_append_u32(header, width);
+    png_append_u32(header, height);
+    header.push_back(8);
+    header.push_back(2);
+    header.push_back(0);
+    header.push_back(0);
+    header.push_back(0);
+    png_write_chunk(out, "IHDR", header);
+
+    // raw scanlines, each prefixed with filter type 0
+    std::vector<unsigned char> raw;
+    size_t row_size = 3 * static_cast<size_t>(width);
+    for (int j = 0; j < height; j++)
+    {
+        raw.push_back(0);
+        raw.insert(raw.end(), rgb.begin() + j * row_size, rgb.begin() + (j + 1) * row_size);
+    }
+
+    // zlib stream with uncompressed deflate blocks
+    std::vector<unsigned char> data = {0x78, 0x01};
+    size_t position = 0;
+    do
+    {
+        size_t block_size = std::min<size_t>(raw.size() - position, 65535);
+        bool last_block = position + block_size == raw.size();
+        data.push_back(last_block ? 1 : 0);
+        data.push_back(block_size & 0xff);
+        data.push_back((block_size >> 8) & 0xff);
+        data.push_back(~block_size & 0xff);
+        data.push_back((~block_size >> 8) & 0xff);
+        data.insert(data.end(), raw.begin() + position, raw.begin() + position + block_size);
+        position += block_size;
+    } while (position < raw.size());
+
+    uint32_t a = 1;
+    uint32_t b = 0;
+    for (unsigned char byte : raw)
+    {
+        a = (a + byte) % 65521;
+        b = (b + a) % 65521;
+    }
+    png_append_u32(data, (b << 16) | a);
+    png_write_chunk(out, "IDAT", data);
+
+    png_write_chunk(out, "IEND", std::vector<unsigned char>());
+}
+
+#endif // PNG_H
@@ -0,0 +1,116 @@
+/*
+sampler.h
+=========
+Samplers generate the positions of the samples within a pixel as pairs of
+numbers in [0, 1).
+
+RandomSampler draws independent uniform numbers, which can clump and leave
+gaps at low sample counts. HaltonSampler uses the Halton sequence in the
+bases 2 and 3, a low discrepancy sequence that covers the pixel evenly for
+any number of samples. Every pixel shifts the sequence by its own random
+offset (Cranley-Patterson rotation), so neighbouring pixels do not repeat
+the same pattern.
+*/
+
+#ifndef SAMPLER_H
+#define SAMPLER_H
+
+#include <cmath>
+#include <cstdint>
+
+#include "ray_tracer.h"
+
+/**
+ * @brief      Base class for samplers.
+ */
+class Sampler
+{
+public:
+    virtual void next_2d(double &x, double &y) = 0;
+};
+
+/**
+ * @brief      Sampler with independent uniform random numbers.
+ */
+class RandomSampler : public Sampler
+{
+public:
+    virtual void next_2d(double &x, double &y) override
+    {
+        x = random_double();
+        y = random_double();
+    }
+};
+
+/**
+ * @brief      Radical inverse of an index: its digits in the base mirrored
+ *             at the decimal point, e.g. 6 = 110 in base 2 becomes 0.011 = 0.375.
+ * @param[in]  base   The base
+ * @param[in]  index  The index
+ * @return     The radical inverse in [0, 1).
+ */
+inline double radical_inverse(int base, uint64_t index)
+{
+    double inverse_base = 1.0 / base;
+    double factor = inverse_base;
+    double result = 0;
+    while (index > 0)
+    {
+        result += (index % base) * factor;
+        index /= base;
+        factor *= inverse_base;
+    }
+    return result;
+}
+
+/**
+ * @brief      Sampler with the Halton sequence in the bases 2 and 3.
+ *             The sequence starts at index 1, since index 0 is (0, 0).
+ */
+class HaltonSampler : public Sampler
+{
+private:
+    uint64_t index;
+    double shift_x;
+    double shift_y;
+
+public:
+    /**
+     * @brief      Constructs a Halton sampler.
+     * @param[in]  start    The number of points of the sequence to skip
+     * @param[in]  shift_x  The offset added to x modulo 1
+     * @param[in]  shift_y  The offset added to y modulo 1
+     */
+    HaltonSampler(uint64_t start = 0, double shift_x = 0, double shift_y = 0)
+        : index(start + 1), shift_x(shift_x), shift_y(shift_y) {}
+
+    virtual void next_2d(double &x, double &y) override
+    {
+        x = radical_inverse(2, index) + shift_x;
+        y = radical_inverse(3, index) + shift_y;
+        x -= std::floor(x);
+        y -= std::floor(y);
+        index++;
+    }
+};
+
+/**
+ * @brief      Hashes a pixel to a number in [0, 1), the same for every call.
+ * @param[in]  i     The column of the pixel
+ * @param[in]  j     The row of the pixel
+ * @param[in]  salt  Distinguishes several numbers of the same pixel
+ * @return     The number
+ */
+inline double pixel_hash(int i, int j, uint32_t salt)
+{
+    uint32_t h = static_cast<uint32_t>(i) * 73856093u ^ static_cast<uint32_t>(j) * 19349663u ^ salt * 83492791u;
+    // finalizer of MurmurHash3
+    h ^= h >> 16;
+    h *= 0x85ebca6bu;
+    h ^= h >> 13;
+    h *= 0xc2b2ae35u;
+    h ^= h >> 16;
+    return h / 4294967296.0;
+}
+
+#endif // SAMPLER_H
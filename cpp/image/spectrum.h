@@ -0,0 +1,103 @@
+/*
+spectrum.h
+==========
+Conversion of single wavelengths to colors for spectral rendering.
+
+In spectral rendering every sample carries one wavelength of visible light,
+drawn uniformly between min_wavelength and max_wavelength. The radiance of
+the sample is weighted with the color of its wavelength: the CIE 1931 color
+matching functions give the XYZ color, which is converted to linear sRGB.
+The weights are normalized to average to white over all wavelengths, so a
+scene without dispersion renders the same as without spectral sampling.
+
+The color matching functions use the multi-lobe Gaussian fit of Wyman,
+Sloan and Shirley, "Simple Analytic Approximations to the CIE XYZ Color
+Matching Functions" (2013).
+*/
+
+#ifndef SPECTRUM_H
+#define SPECTRUM_H
+
+#include <cmath>
+
+#include "color.h"
+
+const double min_wavelength = 380.0; // nm
+const double max_wavelength = 780.0; // nm
+
+/**
+ * @brief      Gaussian with different widths left and right of its mean.
+ * @param[in]  x       The wavelength in nm
+ * @param[in]  mean    The mean in nm
+ * @param[in]  sigma1  The width below the mean in nm
+ * @param[in]  sigma2  The width above the mean in nm
+ * @return     The value, 1 at the mean.
+ */
+inline double piecewise_gaussian(double x, double mean, double sigma1, double sigma2)
+{
+    double t = (x - mean) / (x < mean ? sigma1 : sigma2);
+    return exp(-0.5 * t * t);
+}
+
+/**
+ * @brief      CIE 1931 XYZ color of a single wavelength.
+ * @param[in]  wavelength  The wavelength in nm
+ * @return     The X, Y and Z values of the color matching functions.
+ */
+inline Vec3 wavelength_to_xyz(double wavelength)
+{
+    double x = 1.056 * piecewise_gaussian(wavelength, 599.8, 37.9, 31.0)
+             + 0.362 * piecewise_gaussian(wavelength, 442.0, 16.0, 26.7)
+             - 0.065 * piecewise_gaussian(wavelength, 501.1, 20.4, 26.2);
+    double y = 0.821 * piecewise_gaussian(wavelength, 568.8, 46.9, 40.5)
+             + 0.286 * piecewise_gaussian(wavelength, 530.9, 16.3, 31.1);
+    double z = 1.217 * piecewise_gaussian(wavelength, 437.0, 11.8, 36.0)
+             + 0.681 * piecewise_gaussian(wavelength, 459.0, 26.0, 13.8);
+    return Vec3(x, y, z);
+}
+
+/**
+ * @brief      Converts an XYZ color to linear sRGB.
+ * @param[in]  xyz   The XYZ color
+ * @return     The linear sRGB color, channels can be negative outside the sRGB gamut.
+ */
+inline Color xyz_to_linear_srgb(const Vec3 &xyz)
+{
+    return Color(3.2406 * xyz.x() - 1.5372 * xyz.y() - 0.4986 * xyz.z(),
+                 -0.9689 * xyz.x() + 1.8758 * xyz.y() + 0.0415 * xyz.z(),
+                 0.0557 * xyz.x() - 0.2040 * xyz.y() + 1.0570 * xyz.z());
+}
+
+/**
+ * @brief      Weight of a sample with a wavelength drawn uniformly from the visible range.
+ *             The weights average to (1, 1, 1) over all wavelengths.
+ * @param[in]  wavelength  The wavelength in nm
+ * @return     The weight of the sample for each color channel.
+ */
+inline Color wavelength_weight(double wavelength)
+{
+    // average color of all wavelengths, integrated once with the midpoint rule
+    static const Color average = []() {
+        const int steps = 4000;
+        Color sum(0, 0, 0);
+        for (int k = 0; k < steps; k++)
+        {
+            double lambda = min_wavelength + (k + 0.5) * (max_wavelength - min_wavelength) / steps;
+            sum += xyz_to_linear_srgb(wavelength_to_xyz(lambda));
+        }
+        return Color(sum / steps);
+    }();
+    Color color = xyz_to_linear_srgb(wavelength_to_xyz(wavelength));
+    return Color(color.r() / average.r(), color.g() / average.g(), color.b() / average.b());
+}
+
+/**
+ * @brief      Draws a wavelength uniformly from the visible range.
+ * @return     The wavelength in nm
+ */
+inline double random_wavelength()
+{
+    return random_double(min_wavelength, max_wavelength);
+}
+
+#endif // SPECTRUM_H
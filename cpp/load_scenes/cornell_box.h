@@ -0,0 +1,63 @@
+/*
+cornell_box.h
+=============
+The Cornell box, the standard test scene for light sampling.
+
+The box is 555 units wide, with a green wall at x = 555, a red wall at
+x = 0, a white floor, ceiling and back wall, a light in the ceiling and
+two rotated white boxes. The camera looks into the open side of the
+box, e.g. from look_from (278, 278, -800) at look_at (278, 278, 0) with
+a vertical field of view of 40 degrees.
+*/
+
+#ifndef CORNELL_BOX_H
+#define CORNELL_BOX_H
+
+#include <memory>
+
+#include "scene_builder.h"
+#include "lambertian.h"
+#include "diffuse_light.h"
+#include "rectangle.h"
+#include "rotation.h"
+#include "translation.h"
+
+/**
+ * @brief Build the Cornell box.
+ *        The diffuse materials sample the ceiling light.
+ * @return Scene with the five walls, the light and the two boxes
+*/
+inline Scene cornell_box()
+{
+    SceneBuilder builder;
+    auto red = builder.add_material<Lambertian>(Color(0.65, 0.05, 0.05));
+    auto white = builder.add_material<Lambertian>(Color(0.73, 0.73, 0.73));
+    auto green = builder.add_material<Lambertian>(Color(0.12, 0.45, 0.15));
+    auto light = builder.add_material<DiffuseLight>(Color(15, 15, 15));
+
+    // walls
+    builder.add_object<YZ_Rectangle>(green, 0, 555, 0, 555, 555);
+    builder.add_object<YZ_Rectangle>(red, 0, 555, 0, 555, 0);
+    builder.add_object<XZ_Rectangle>(white, 0, 555, 0, 555, 0);
+    builder.add_object<XZ_Rectangle>(white, 0, 555, 0, 555, 555);
+    builder.add_object<XY_Rectangle>(white, 0, 555, 0, 555, 555);
+
+    // ceiling light
+    builder.add_object<XZ_Rectangle>(light, 213, 343, 227, 332, 554);
+
+    // boxes
+    std::shared_ptr<Hittable> tall_box = std::make_shared<Box>(Vec3(0, 0, 0), Vec3(165, 330, 165), white);
+    tall_box = std::make_shared<RotateY>(tall_box, 15);
+    tall_box = std::make_shared<Translate>(tall_box, Vec3(265, 0, 295));
+    builder.add(tall_box, white);
+
+    std::shared_ptr<Hittable> short_box = std::make_shared<Box>(Vec3(0, 0, 0), Vec3(165, 165, 165), white);
+    short_box = std::make_shared<RotateY>(short_box, -18);
+    short_box = std::make_shared<Translate>(short_box, Vec3(130, 0, 65));
+    builder.add(short_box, white);
+
+    // the builder sets the ceiling light as light of the diffuse materials
+    return builder.build();
+}
+
+#endif // CORNELL_BOX_H
@@ -0,0 +1,181 @@
+/*
+load_obj.h
+==========
+Load triangle meshes from Wavefront OBJ files.
+
+Only vertex (v), vertex normal (vn) and face (f) lines are read. Faces
+with more than three vertices are split into a fan of triangles around the
+first vertex. Triangles whose vertices all have normals become smooth
+triangles. Texture coordinates in the face indices are ignored, as are all
+other statements of the file.
+*/
+
+#ifndef LOAD_OBJ_H
+#define LOAD_OBJ_H
+
+#include <fstream>
+#include <iostream>
+#include <sstream>
+#include <string>
+#include <vector>
+#include <memory>
+
+#include "vec3.h"
+#include "hittable_list.h"
+#include "triangle.h"
+
+/**
+ * @brief Convert an OBJ face index to a position in the vertex list.
+ *        Positive indices count from 1, negative indices count back from the last vertex.
+ * @param[in] token face vertex token, e.g. "3", "3/1" or "-1//2"
+ * @param[in] number_of_vertices number of vertices read so far
+ * @param[out] index position in the vertex list
+ * @return true if the index refers to an existing vertex
+*/
+inline bool obj_vertex_index(const std::string &token, size_t number_of_vertices, size_t &index)
+{
+    long value;
+    try
+    {
+        value = std::stol(token.substr(0, token.find('/')));
+    }
+    catch (const std::exception &)
+    {
+        return false;
+    }
+    long position = value > 0 ? value - 1 : static_cast<long>(number_of_vertices) + value;
+    if (value == 0 || position < 0 || position >= static_cast<long>(number_of_vertices))
+    {
+        return false;
+    }
+    index = static_cast<size_t>(position);
+    return true;
+}
+
+/**
+ * @brief Find the normal index of an OBJ face vertex token, e.g. "3//2" or "3/1/2".
+ * @param[in] token face vertex token
+ * @param[in] number_of_normals number of normals read so far
+ * @param[out] has_normal whether the token refers to a normal
+ * @param[out] index position in the normal list
+ * @return false if the token refers to a normal that does not exist
+*/
+inline bool obj_normal_index(const std::string &token, size_t number_of_normals, bool &has_normal, size_t &index)
+{
+    size_t first = token.find('/');
+    size_t second = first == std::string::npos ? std::string::npos : token.find('/', first + 1);
+    has_normal = second != std::string::npos && second + 1 < token.size();
+    if (!has_normal)
+    {
+        return true;
+    }
+    return obj_vertex_index(token.substr(second + 1), number_of_normals, index);
+}
+
+/**
+ * @brief Load triangles from OBJ data.
+ * @param in stream with the OBJ data
+ * @param material Material of the triangles
+ * @param triangles HittableList to add the triangles to
+ * @return true if the data was read without errors
+*/
+inline bool load_obj(std::istream &in, Material *material, HittableList &triangles)
+{
+    std::vector<Vec3> vertices;
+    std::vector<Vec3> normals;
+    std::string line;
+    int line_number = 0;
+    while (std::getline(in, line))
+    {
+        line_number++;
+        std::istringstream line_stream(line);
+        std::string keyword;
+        line_stream >> keyword;
+
+        if (keyword == "v")
+        {
+            double x, y, z;
+            if (!(line_stream >> x >> y >> z))
+            {
+                std::cerr << "Invalid vertex in line " << line_number << std::endl;
+                return false;
+            }
+            vertices.push_back(Vec3(x, y, z));
+        }
+        else if (keyword == "vn")
+        {
+            double x, y, z;
+            if (!(line_stream >> x >> y >> z))
+            {
+                std::cerr << "Invalid vertex normal in line " << line_number << std::endl;
+                return false;
+            }
+            normals.push_back(Vec3(x, y, z));
+        }
+        else if (keyword == "f")
+        {
+            std::vector<size_t> face;
+            std::vector<size_t> face_normals;
+            bool smooth = true;
+            std::string token;
+            while (line_stream >> token)
+            {
+                size_t index;
+                if (!obj_vertex_index(token, vertices.size(), index))
+                {
+                    std::cerr << "Invalid face index " << token << " in line " << line_number << std::endl;
+                    return false;
+                }
+                face.push_back(index);
+
+                bool has_normal;
+                if (!obj_normal_index(token, normals.size(), has_normal, index))
+                {
+                    std::cerr << "Invalid normal index " << token << " in line " << line_number << std::endl;
+                    return false;
+                }
+                smooth = smooth && has_normal;
+                face_normals.push_back(index);
+            }
+            if (face.size() < 3)
+            {
+                std::cerr << "Face with less than three vertices in line " << line_number << std::endl;
+                return false;
+            }
+            // triangle fan around the first vertex
+            for (size_t k = 1; k + 1 < face.size(); k++)
+            {
+                if (smooth)
+                {
+                    triangles.add(std::make_shared<SmoothTriangle>(vertices[face[0]], vertices[face[k]], vertices[face[k + 1]],
+                                                                   normals[face_normals[0]], normals[face_normals[k]], normals[face_normals[k + 1]], material));
+                }
+                else
+                {
+                    triangles.add(std::make_shared<Triangle>(vertices[face[0]], vertices[face[k]], vertices[face[k + 1]], material));
+                }
+            }
+        }
+    }
+    return true;
+}
+
+/**
+ * @brief Load triangles from an OBJ file.
+ * @param filename OBJ file to load
+ * @param material Material of the triangles
+ * @param triangles HittableList to add the triangles to
+ * @return true if the file was read without errors
+*/
+inline bool load_obj(const std::string &filename, Material *material, HittableList &triangles)
+{
+    std::ifstream in(filename);
+    if (!in.good())
+    {
+        std::cerr << "File not accessible: " << filename << std::endl;
+        return false;
+    }
+    return load_obj(in, material, triangles);
+}
+
+#endif // LOAD_OBJ_H
@@ -82,6 +82,24 @@ objects:
 ```
 
 The file includes object geometries and materials as well as the camera setup and the image size.
+
+Materials used by several objects can be defined once in a materials table
+next to the objects and referenced by name:
+```yaml
+materials:
+  red:
+    type: diffuse
+    color: [0.8, 0.1, 0.1]
+objects:
+  - type: sphere
+    center: [-1, 0, -1]
+    radius: 0.5
+    material: red
+```
+
+Since JSON is a subset of YAML, scenes can also be written as JSON files with
+the same structure, see scenes/single_sphere.json, or
+scenes/shared_material.json for a materials table.
 */
 
 
@@ -96,14 +114,39 @@ The file includes object geometries and materials as well as the camera setup an
 
 #include "bvh.h"
 #include "camera.h"
+#include "background.h"
+#include "env_map.h"
 #include "materials.h"
 #include "lambertian.h"
 #include "metal.h"
 #include "diffuse_light.h"
+#include "spotlight.h"
 #include "rectangle.h"
 #include "texture.h"
+#include "solid_color.h"
+#include "noise_texture.h"
+#include "marble_texture.h"
+#include "image_texture.h"
+#include "checker_texture.h"
 #include "sphere.h"
+#include "moving_sphere.h"
+#include "triangle.h"
+#include "disk.h"
+#include "cylinder.h"
+#include "cone.h"
+#include "torus.h"
+#include "quad.h"
+#include "load_obj.h"
 #include "dielectric.h"
+#include "translation.h"
+#include "rotation.h"
+#include "scale.h"
+#include "flip_normals.h"
+#include "instance.h"
+#include <map>
+#include "isotropic.h"
+#include "constant_medium.h"
+#include "csg.h"
 
 /**
  * @brief Load Vec3 from yaml node
@@ -123,6 +166,42 @@ inline Vec3 load_vec3(YAML::Node &vector)
     return vec3;
 }
 
+/**
+ * @brief Load Texture from yaml node.
+ * @param texture_data yaml node
+ * @return Texture
+*/
+inline Texture *load_texture(YAML::Node &texture_data)
+{
+    std::string texture_type = texture_data["type"].as<std::string>();
+    if (texture_type.compare("solid") == 0)
+    {
+        auto color_data = texture_data["color"];
+        return new SolidColor(load_vec3(color_data));
+    }
+    else if (texture_type.compare("noise") == 0)
+    {
+        return new NoiseTexture(texture_data["scale"].as<double>());
+    }
+    else if (texture_type.compare("marble") == 0)
+    {
+        return new MarbleTexture(texture_data["scale"].as<double>());
+    }
+    else if (texture_type.compare("checker") == 0)
+    {
+        auto even_data = texture_data["even"];
+        auto odd_data = texture_data["odd"];
+        double scale = texture_data["scale"] ? texture_data["scale"].as<double>() : 10.0;
+        return new CheckerTexture(load_texture(even_data), load_texture(odd_data), scale);
+    }
+    else if (texture_type.compare("image") == 0)
+    {
+        return new ImageTexture(texture_data["file"].as<std::string>());
+    }
+    std::cerr << "Unknown texture type: " << texture_type << std::endl;
+    exit(1);
+}
+
 /**
  * @brief Load Camera setup from yaml node.
  * @param camera Camera object
@@ -143,9 +222,75 @@ inline void load_camera(Camera &camera, YAML::Node &camera_data)
     Vec3 look_from = load_vec3(look_from_data); 
     auto look_at_data = camera_data["look_at"];
     Vec3 look_at = load_vec3(look_at_data);
+    if (camera_data["vup"])
+    {
+        auto vup_data = camera_data["vup"];
+        camera.set_vup(load_vec3(vup_data));
+    }
     
     camera.set_up(vfov, aspect_ratio, aperture, focus_distance, look_from, look_at, time0, time1);
+    if (camera_data["orthographic_width"])
+    {
+        camera.set_orthographic(camera_data["orthographic_width"].as<double>());
+    }
+    if (camera_data["projection"])
+    {
+        std::string projection = camera_data["projection"].as<std::string>();
+        if (projection == "panoramic")
+            camera.set_projection(Projection::Panoramic);
+        else if (projection == "perspective")
+            camera.set_projection(Projection::Perspective);
+        else if (projection == "orthographic")
+            camera.set_projection(Projection::Orthographic);
+        else
+        {
+            std::cerr << "Unknown projection: " << projection << std::endl;
+            exit(1);
+        }
+    }
+    if (camera_data["bokeh_blades"])
+    {
+        camera.set_bokeh_blades(camera_data["bokeh_blades"].as<int>());
+    }
+    if (camera_data["chromatic_aberration"])
+    {
+        camera.set_chromatic_aberration(camera_data["chromatic_aberration"].as<double>());
+    }
+    if (camera_data["ray_differentials"])
+    {
+        camera.set_ray_differentials(camera_data["ray_differentials"].as<bool>());
+    }
     camera.set_image_data(image_width);
+    if (camera_data["pixel_filter"])
+    {
+        std::string filterType = camera_data["pixel_filter"].as<std::string>();
+        if (filterType.compare("tent") == 0)
+        {
+            camera.image.set_pixel_filter(PixelFilter::Tent);
+        }
+        else if (filterType.compare("box") != 0)
+        {
+            std::cerr << "Unknown pixel filter: " << filterType << std::endl;
+            exit(1);
+        }
+    }
+    if (camera_data["sampling"])
+    {
+        std::string samplingType = camera_data["sampling"].as<std::string>();
+        if (samplingType.compare("stratified") == 0)
+        {
+            camera.image.set_sample_strategy(SampleStrategy::Stratified);
+        }
+        else if (samplingType.compare("halton") == 0)
+        {
+            camera.image.set_sample_strategy(SampleStrategy::Halton);
+        }
+        else if (samplingType.compare("random") != 0)
+        {
+            std::cerr << "Unknown sampling: " << samplingType << std::endl;
+            exit(1);
+        }
+    }
 }
 
 /**
@@ -166,6 +311,27 @@ inline void load_sphere(HittableList &objects, YAML::Node &sphere_data, Material
     objects.add(sphere);
 }
 
+/**
+ * @brief Load MovingSphere object from yaml node.
+ * @param objects HittableList objects to add sphere to
+ * @param sphere_data yaml node
+ * @param material Material object to assign to sphere
+*/
+inline void load_moving_sphere(HittableList &objects, YAML::Node &sphere_data, Material *material)
+{
+    std::cerr << "loading moving sphere" << std::endl;
+    auto center0_data = sphere_data["center0"];
+    auto center1_data = sphere_data["center1"];
+    Vec3 center0 = load_vec3(center0_data);
+    Vec3 center1 = load_vec3(center1_data);
+    double time0 = sphere_data["time0"].as<double>();
+    double time1 = sphere_data["time1"].as<double>();
+    double radius = sphere_data["radius"].as<double>();
+
+    auto sphere = std::make_shared<MovingSphere>(center0, center1, time0, time1, radius, material);
+    objects.add(sphere);
+}
+
 /**
  * @brief Load XY Rectangle object from yaml node.
  * @param objects HittableList objects to add rectangle to
@@ -242,12 +408,448 @@ inline void load_box(HittableList &objects, YAML::Node &box_data, Material *mate
 }
 
 /**
- * @brief load camera setup and objects from yaml file
+ * @brief Load Triangle object from yaml node.
+ * @param objects HittableList objects to add triangle to
+ * @param triangle_data yaml node
+ * @param material Material object to assign to triangle
+*/
+inline void load_triangle(HittableList &objects, YAML::Node &triangle_data, Material *material)
+{
+    std::cerr << "loading triangle" << std::endl;
+    auto v0_data = triangle_data["v0"];
+    auto v1_data = triangle_data["v1"];
+    auto v2_data = triangle_data["v2"];
+    Vec3 v0 = load_vec3(v0_data);
+    Vec3 v1 = load_vec3(v1_data);
+    Vec3 v2 = load_vec3(v2_data);
+    auto triangle = std::make_shared<Triangle>(v0, v1, v2, material);
+    objects.add(triangle);
+}
+
+/**
+ * @brief Load Disk object from yaml node.
+ * @param objects HittableList objects to add disk to
+ * @param disk_data yaml node
+ * @param material Material object to assign to disk
+*/
+inline void load_disk(HittableList &objects, YAML::Node &disk_data, Material *material)
+{
+    std::cerr << "loading disk" << std::endl;
+    auto center_data = disk_data["center"];
+    auto normal_data = disk_data["normal"];
+    Vec3 center = load_vec3(center_data);
+    Vec3 normal = load_vec3(normal_data);
+    double radius = disk_data["radius"].as<double>();
+    auto disk = std::make_shared<Disk>(center, normal, radius, material);
+    objects.add(disk);
+}
+
+/**
+ * @brief Load Cylinder object from yaml node.
+ *        The cylinder stands on the base center along the y-axis and is capped by default.
+ * @param objects HittableList objects to add cylinder to
+ * @param cylinder_data yaml node
+ * @param material Material of the cylinder
+*/
+inline void load_cylinder(HittableList &objects, YAML::Node &cylinder_data, Material *material)
+{
+    std::cerr << "loading cylinder" << std::endl;
+    auto base_data = cylinder_data["base"];
+    Vec3 base = load_vec3(base_data);
+    double height = cylinder_data["height"].as<double>();
+    double radius = cylinder_data["radius"].as<double>();
+    bool capped = cylinder_data["capped"] ? cylinder_data["capped"].as<bool>() : true;
+    auto cylinder = std::make_shared<Cylinder>(base, height, radius, material, capped);
+    objects.add(cylinder);
+}
+
+/**
+ * @brief Load Cone object from yaml node.
+ *        The cone opens from the apex along the axis with the half angle in degrees
+ *        and is capped at the base by default.
+ * @param objects HittableList objects to add cone to
+ * @param cone_data yaml node
+ * @param material Material of the cone
+*/
+inline void load_cone(HittableList &objects, YAML::Node &cone_data, Material *material)
+{
+    std::cerr << "loading cone" << std::endl;
+    auto apex_data = cone_data["apex"];
+    auto axis_data = cone_data["axis"];
+    Vec3 apex = load_vec3(apex_data);
+    Vec3 axis = load_vec3(axis_data);
+    double half_angle = cone_data["half_angle"].as<double>();
+    double height = cone_data["height"].as<double>();
+    bool capped = cone_data["capped"] ? cone_data["capped"].as<bool>() : true;
+    auto cone = std::make_shared<Cone>(apex, axis, half_angle, height, material, capped);
+    objects.add(cone);
+}
+
+/**
+ * @brief Load Torus object from yaml node.
+ *        The torus is centered at the origin around the y-axis, use rotate and
+ *        translate to place it.
+ * @param objects HittableList objects to add torus to
+ * @param torus_data yaml node
+ * @param material Material of the torus
+*/
+inline void load_torus(HittableList &objects, YAML::Node &torus_data, Material *material)
+{
+    std::cerr << "loading torus" << std::endl;
+    double major_radius = torus_data["major_radius"].as<double>();
+    double minor_radius = torus_data["minor_radius"].as<double>();
+    auto torus = std::make_shared<Torus>(major_radius, minor_radius, material);
+    objects.add(torus);
+}
+
+/**
+ * @brief Load Quad object from yaml node.
+ *        The quad is spanned by the edges u and v starting at corner q.
+ * @param objects HittableList objects to add quad to
+ * @param quad_data yaml node
+ * @param material Material of the quad
+*/
+inline void load_quad(HittableList &objects, YAML::Node &quad_data, Material *material)
+{
+    std::cerr << "loading quad" << std::endl;
+    auto q_data = quad_data["q"];
+    auto u_data = quad_data["u"];
+    auto v_data = quad_data["v"];
+    Vec3 q = load_vec3(q_data);
+    Vec3 u = load_vec3(u_data);
+    Vec3 v = load_vec3(v_data);
+    auto quad = std::make_shared<Quad>(q, u, v, material);
+    objects.add(quad);
+}
+
+/**
+ * @brief Load triangle mesh from an OBJ file given in the yaml node.
+ *        The triangles are grouped in a BVH. Every file is loaded once per scene,
+ *        objects using the same file are instances of the same BVH with their
+ *        own material.
+ * @param objects HittableList objects to add mesh to
+ * @param mesh_data yaml node
+ * @param material Material of the mesh
+ * @param meshes Meshes of the scene loaded so far, by file name, each with its BVH
+*/
+inline void load_mesh(HittableList &objects, YAML::Node &mesh_data, Material *material, std::map<std::string, std::shared_ptr<Hittable>> &meshes)
+{
+    std::cerr << "loading mesh" << std::endl;
+    std::string filename = mesh_data["file"].as<std::string>();
+    auto &mesh = meshes[filename];
+    if (!mesh)
+    {
+        HittableList triangles;
+        if (!load_obj(filename, material, triangles) || triangles.size() == 0)
+        {
+            std::cerr << "Could not load mesh: " << filename << std::endl;
+            exit(1);
+        }
+        auto triangle_objects = triangles.get_objects();
+        mesh = build_bvh(triangle_objects, 0, 1, SplitMethod::SurfaceAreaHeuristic);
+    }
+    objects.add(std::make_shared<Instance>(mesh, material));
+}
+
+inline std::shared_ptr<Hittable> load_shape(YAML::Node &shape_data, Material *material, std::map<std::string, std::shared_ptr<Hittable>> &meshes);
+
+/**
+ * @brief Load ConstantMedium object from yaml node.
+ *        The boundary is a nested shape node.
+ * @param objects HittableList objects to add medium to
+ * @param medium_data yaml node
+ * @param material Phase function of the medium
+ * @param meshes Meshes of the scene loaded so far, by file name
+*/
+inline void load_constant_medium(HittableList &objects, YAML::Node &medium_data, Material *material, std::map<std::string, std::shared_ptr<Hittable>> &meshes)
+{
+    std::cerr << "loading constant medium" << std::endl;
+    auto boundary_data = medium_data["boundary"];
+    auto boundary = load_shape(boundary_data, material, meshes);
+    double density = medium_data["density"].as<double>();
+    auto medium = std::make_shared<ConstantMedium>(boundary, density, material);
+    objects.add(medium);
+}
+
+/**
+ * @brief Load Csg object from yaml node.
+ *        The operands left and right are nested shape nodes, combined with
+ *        the operation union, intersection or difference.
+ * @param objects HittableList objects to add the combination to
+ * @param csg_data yaml node
+ * @param material Material of both operands
+ * @param meshes Meshes of the scene loaded so far, by file name
+*/
+inline void load_csg(HittableList &objects, YAML::Node &csg_data, Material *material, std::map<std::string, std::shared_ptr<Hittable>> &meshes)
+{
+    auto left_data = csg_data["left"];
+    auto right_data = csg_data["right"];
+    auto left = load_shape(left_data, material, meshes);
+    auto right = load_shape(right_data, material, meshes);
+    std::string operationType = csg_data["operation"].as<std::string>();
+    CsgOperation operation;
+    if (operationType.compare("union") == 0)
+        operation = CsgOperation::Union;
+    else if (operationType.compare("intersection") == 0)
+        operation = CsgOperation::Intersection;
+    else if (operationType.compare("difference") == 0)
+        operation = CsgOperation::Difference;
+    else
+    {
+        std::cerr << "Unknown csg operation: " << operationType << std::endl;
+        exit(1);
+    }
+    objects.add(std::make_shared<Csg>(left, right, operation));
+}
+
+/**
+ * @brief Wrap an object in the transformations given in its yaml node.
+ * @param object Hittable object to transform
+ * @param object_data yaml node
+ * @return Transformed object
+*/
+inline std::shared_ptr<Hittable> load_transforms(std::shared_ptr<Hittable> object, YAML::Node &object_data)
+{
+    // scale and rotate around the origin first, then move into place
+    if (object_data["flip_normals"] && object_data["flip_normals"].as<bool>())
+    {
+        object = std::make_shared<FlipNormals>(object);
+    }
+    if (object_data["scale"])
+    {
+        auto scale_data = object_data["scale"];
+        Vec3 factor = scale_data.IsSequence() ? load_vec3(scale_data) : Vec3(1, 1, 1) * scale_data.as<double>();
+        object = std::make_shared<Scale>(object, factor);
+    }
+    if (object_data["rotate_y"])
+    {
+        double angle = object_data["rotate_y"].as<double>();
+        object = std::make_shared<RotateY>(object, angle);
+    }
+    if (object_data["rotate"])
+    {
+        auto axis_data = object_data["rotate"]["axis"];
+        Vec3 axis = load_vec3(axis_data);
+        double angle = object_data["rotate"]["angle"].as<double>();
+        object = std::make_shared<Rotate>(object, axis, angle);
+    }
+    if (object_data["translate"])
+    {
+        auto offset_data = object_data["translate"];
+        Vec3 offset = load_vec3(offset_data);
+        object = std::make_shared<Translate>(object, offset);
+    }
+    return object;
+}
+
+/**
+ * @brief Load a shape and its transformations from yaml node.
+ * @param shape_data yaml node
+ * @param material Material object to assign to the shape
+ * @param meshes Meshes of the scene loaded so far, by file name
+ * @return Hittable object
+*/
+inline std::shared_ptr<Hittable> load_shape(YAML::Node &shape_data, Material *material, std::map<std::string, std::shared_ptr<Hittable>> &meshes)
+{
+    std::string shapeType = shape_data["type"].as<std::string>();
+    HittableList shape;
+    if (shapeType.compare("sphere") == 0)
+    {
+        load_sphere(shape, shape_data, material);
+    }
+    else if (shapeType.compare("moving_sphere") == 0)
+    {
+        load_moving_sphere(shape, shape_data, material);
+    }
+    else if (shapeType.compare("xy_rectangle") == 0)
+    {
+        load_xy_rectangle(shape, shape_data, material);
+    }
+    else if (shapeType.compare("xz_rectangle") == 0)
+    {
+        load_xz_rectangle(shape, shape_data, material);
+    }
+    else if (shapeType.compare("yz_rectangle") == 0)
+    {
+        load_yz_rectangle(shape, shape_data, material);
+    }
+    else if (shapeType.compare("box") == 0)
+    {
+        load_box(shape, shape_data, material);
+    }
+    else if (shapeType.compare("triangle") == 0)
+    {
+        load_triangle(shape, shape_data, material);
+    }
+    else if (shapeType.compare("disk") == 0)
+    {
+        load_disk(shape, shape_data, material);
+    }
+    else if (shapeType.compare("cylinder") == 0)
+    {
+        load_cylinder(shape, shape_data, material);
+    }
+    else if (shapeType.compare("cone") == 0)
+    {
+        load_cone(shape, shape_data, material);
+    }
+    else if (shapeType.compare("torus") == 0)
+    {
+        load_torus(shape, shape_data, material);
+    }
+    else if (shapeType.compare("quad") == 0)
+    {
+        load_quad(shape, shape_data, material);
+    }
+    else if (shapeType.compare("mesh") == 0)
+    {
+        load_mesh(shape, shape_data, material, meshes);
+    }
+    else if (shapeType.compare("constant_medium") == 0)
+    {
+        load_constant_medium(shape, shape_data, material, meshes);
+    }
+    else if (shapeType.compare("csg") == 0)
+    {
+        load_csg(shape, shape_data, material, meshes);
+    }
+    else {
+        std::cerr << "Unknown shape type: " << shapeType << std::endl;
+        exit(1);
+    }
+
+    std::shared_ptr<Hittable> object = shape.get(0);
+    return load_transforms(object, shape_data);
+}
+
+/**
+ * @brief Load Background from yaml node.
+ * Supported types are solid (color), gradient (bottom, top) and
+ * environment (file, a Radiance HDR image).
+ * Without a background node the background is black.
+ * @param background_data yaml node
+ * @return Background
+*/
+inline std::shared_ptr<Background> load_background(YAML::Node &background_data)
+{
+    if (!background_data)
+    {
+        return std::make_shared<SolidBackground>(Color(0, 0, 0));
+    }
+    std::string backgroundType = background_data["type"].as<std::string>();
+    if (backgroundType.compare("solid") == 0)
+    {
+        auto color_data = background_data["color"];
+        return std::make_shared<SolidBackground>(load_vec3(color_data));
+    }
+    else if (backgroundType.compare("gradient") == 0)
+    {
+        auto bottom_data = background_data["bottom"];
+        auto top_data = background_data["top"];
+        return std::make_shared<GradientBackground>(load_vec3(bottom_data), load_vec3(top_data));
+    }
+    else if (backgroundType.compare("environment") == 0)
+    {
+        return std::make_shared<EnvMap>(background_data["file"].as<std::string>());
+    }
+    std::cerr << "Unknown background type: " << backgroundType << std::endl;
+    exit(1);
+}
+
+/**
+ * @brief Load a material from the yaml node
+ * @param material_data yaml node with the type and the parameters of the material
+ * @return The material, nullptr if the type is unknown
+*/
+inline Material *load_material(YAML::Node &material_data)
+{
+    std::string materialType = material_data["type"].as<std::string>();
+    Material *material;
+
+    if (materialType.compare("diffuse") == 0)
+    {
+        if (material_data["texture"])
+        {
+            auto texture_data = material_data["texture"];
+            material = new Lambertian(load_texture(texture_data));
+        }
+        else
+        {
+            auto color_data = material_data["color"];
+            Color color = load_vec3(color_data);
+            material = new Lambertian(color);
+        }
+    }
+    else if (materialType.compare("metal") == 0)
+    {
+        auto color_data = material_data["albedo"];
+        Color color = load_vec3(color_data);
+        double fuzz = material_data["fuzz"].as<double>();
+        material = new Metal(color, fuzz);
+    }
+    else if (materialType.compare("dielectric") == 0)
+    {
+        double refraction_index = material_data["refraction_index"].as<double>();
+        Color absorption(0, 0, 0);
+        if (material_data["absorption"])
+        {
+            auto absorption_data = material_data["absorption"];
+            absorption = load_vec3(absorption_data);
+        }
+        // Cauchy coefficient B in um^2, used by spectral renders
+        double dispersion = material_data["dispersion"] ? material_data["dispersion"].as<double>() : 0.0;
+        material = new Dielectric(refraction_index, absorption, dispersion);
+    }
+    else if (materialType.compare("diffuse_light") == 0)
+    {
+        if (material_data["texture"])
+        {
+            auto texture_data = material_data["texture"];
+            material = new DiffuseLight(load_texture(texture_data));
+        }
+        else
+        {
+            auto color_data = material_data["color"];
+            Color color = load_vec3(color_data);
+            material = new DiffuseLight(color);
+        }
+    }
+    else if (materialType.compare("spotlight") == 0)
+    {
+        auto color_data = material_data["color"];
+        auto direction_data = material_data["direction"];
+        Color color = load_vec3(color_data);
+        Vec3 direction = load_vec3(direction_data);
+        double inner_angle = material_data["inner_angle"].as<double>();
+        double outer_angle = material_data["outer_angle"].as<double>();
+        material = new Spotlight(color, direction, inner_angle, outer_angle);
+    }
+    else if (materialType.compare("isotropic") == 0)
+    {
+        auto color_data = material_data["color"];
+        Color color = load_vec3(color_data);
+        material = new Isotropic(color);
+    }
+    else {
+        std::cerr << "Unknown material type: " << materialType << std::endl;
+        return nullptr;
+    }
+    if (material_data["normal_map"])
+    {
+        auto normal_map_data = material_data["normal_map"];
+        material->set_normal_map(std::shared_ptr<Texture>(load_texture(normal_map_data)));
+    }
+    return material;
+}
+
+/**
+ * @brief load camera setup, background and objects from yaml file
  * @param filename yaml file to load
  * @param camera Camera object to load
+ * @param background Background to load
  * @return HittableList of objects in scene
 */
-HittableList load_scene(std::string filename, Camera &camera)
+HittableList load_scene(std::string filename, Camera &camera, std::shared_ptr<Background> &background)
 {
     std::ifstream fin(filename);
     // check if file exists
@@ -261,83 +863,58 @@ HittableList load_scene(std::string filename, Camera &camera)
     auto camera_data = scene["scene"]["camera"];
     load_camera(camera, camera_data);
 
+    auto background_data = scene["scene"]["background"];
+    background = load_background(background_data);
+
+    // materials defined once by name and shared by objects
+    std::map<std::string, Material *> named_materials;
+    auto materials_data = scene["scene"]["materials"];
+    for (auto it = materials_data.begin(); it != materials_data.end(); ++it)
+    {
+        std::string name = it->first.as<std::string>();
+        auto material_data = it->second;
+        Material *named_material = load_material(material_data);
+        if (!named_material)
+            exit(1);
+        named_materials[name] = named_material;
+    }
+
     HittableList hittable_list = HittableList();
     std::vector<Material *> materials;
+    // meshes shared by the objects of this scene, read again by the next load_scene call
+    std::map<std::string, std::shared_ptr<Hittable>> meshes;
     auto objects_data = scene["scene"]["objects"];
     int numberOfObjects = objects_data.size();
     std::string shapeType;
-    std::string materialType;
     Material *material;
 
     for (int i=0; i<numberOfObjects; i++) 
     {
         shapeType = objects_data[i]["type"].as<std::string>();
-        materialType = objects_data[i]["material"]["type"].as<std::string>();
         auto material_data = objects_data[i]["material"];
-
-        if (materialType.compare("diffuse") == 0)
-        {
-            auto diffuseData = objects_data[i]["material"];
-            auto color_data = material_data["color"];
-            Color color = load_vec3(color_data);
-            material = new Lambertian(color);
-        }
-        else if (materialType.compare("metal") == 0)
+        if (material_data.IsScalar())
         {
-            auto metalData = objects_data[i]["material"];
-            auto color_data = material_data["albedo"];
-            Color color = load_vec3(color_data);
-            double fuzz = material_data["fuzz"].as<double>();
-            material = new Metal(color, fuzz);
+            // reference to a material of the materials table
+            std::string name = material_data.as<std::string>();
+            auto named_material = named_materials.find(name);
+            if (named_material == named_materials.end())
+            {
+                std::cerr << "Unknown material: " << name << std::endl;
+                exit(1);
+            }
+            material = named_material->second;
         }
-        else if (materialType.compare("dielectric") == 0)
+        else
         {
-            auto dielectricData = objects_data[i]["material"];        
-            double refraction_index = material_data["refraction_index"].as<double>();
-            material = new Dielectric(refraction_index);
-        }
-        else if (materialType.compare("diffuse_light") == 0)
-        {
-            auto diffuseLightData = objects_data[i]["material"];
-            auto color_data = material_data["color"];
-            Color color = load_vec3(color_data);
-            material = new DiffuseLight(color);
-        }
-        else {
-            std::cerr << "Unknown material type: " << materialType << std::endl;
-            exit(1);
+            material = load_material(material_data);
+            if (!material)
+                exit(1);
         }
         materials.push_back(material);
 
-        if (shapeType.compare("sphere") == 0)
-        {
-            auto sphere_data = objects_data[i];
-            load_sphere(hittable_list, sphere_data, material);
-        }
-        else if (shapeType.compare("xy_rectangle") == 0)
-        {
-            auto rectangle_data = objects_data[i];
-            load_xy_rectangle(hittable_list, rectangle_data, material);
-        }
-        else if (shapeType.compare("xz_rectangle") == 0)
-        {
-            auto rectangle_data = objects_data[i];
-            load_xz_rectangle(hittable_list, rectangle_data, material);
-        }
-        else if (shapeType.compare("yz_rectangle") == 0)
-        {
-            auto rectangle_data = objects_data[i];
-            load_yz_rectangle(hittable_list, rectangle_data, material);
-        }
-        else if (shapeType.compare("box") == 0)
-        {
-            auto box_data = objects_data[i];
-            load_box(hittable_list, box_data, material);
-        }
-        else {
-            std::cerr << "Unknown shape type: " << shapeType << std::endl;
-            exit(1);
-        }
+        auto shape_data = objects_data[i];
+        std::shared_ptr<Hittable> object = load_shape(shape_data, material, meshes);
+        hittable_list.add(object);
     }
 
     std::cerr << material->to_string() << std::endl;
@@ -354,7 +931,7 @@ HittableList load_scene(std::string filename, Camera &camera)
         std::cerr << objects[i]->to_string() << std::endl;
         std::cerr << "material: " << objects[i]->get_material()->to_string() << std::endl;
     }
-    BVHNode node = BVHNode(objects, 0, objects.size(), 0, 1);
+    hittable_list.build_bvh(0, 1);
     return hittable_list;
 }
 
@@ -0,0 +1,129 @@
+/*
+scene_builder.h
+===============
+Build scenes in code without managing the lifetime of materials.
+
+The hittables only keep raw pointers to their materials. The SceneBuilder
+owns all materials it creates and hands them on to the Scene, so the
+materials live as long as the scene that uses them. Like the scenes loaded
+from files, the emissive objects are the lights of the scene, which are
+sampled by the Lambertian materials.
+
+Example:
+```cpp
+SceneBuilder builder;
+Material *red = builder.add_material<Lambertian>(Color(0.8, 0.1, 0.1));
+Material *light = builder.add_material<DiffuseLight>(Color(4, 4, 4));
+builder.add_object<Sphere>(red, Vec3(0, 0, 0), 1.0);
+builder.add_object<Sphere>(light, Vec3(0, 3, 0), 0.5);
+Scene scene = builder.build();
+```
+*/
+
+#ifndef SCENE_BUILDER_H
+#define SCENE_BUILDER_H
+
+#include <memory>
+#include <utility>
+#include <vector>
+
+#include "hittable_list.h"
+#include "light_list.h"
+#include "materials.h"
+#include "lambertian.h"
+
+/**
+ * @brief      The objects of a scene together with the materials they use.
+ */
+struct Scene
+{
+    HittableList world;
+    std::vector<std::shared_ptr<Material>> materials;
+};
+
+/**
+ * @brief      Class for building scenes.
+ */
+class SceneBuilder
+{
+public:
+    /**
+     * @brief      Creates a material owned by the scene.
+     * @param[in]  args  The arguments of the material constructor
+     * @return     The material, valid as long as the builder or the built scene exists.
+     */
+    template <class M, class... Args>
+    M *add_material(Args &&...args)
+    {
+        auto material = std::make_shared<M>(std::forward<Args>(args)...);
+        materials.push_back(material);
+        return material.get();
+    }
+
+    /**
+     * @brief      Creates an object and adds it to the scene.
+     *             The material is passed as last argument to the constructor of the object.
+     * @param[in]  material  The material of the object
+     * @param[in]  args      The other arguments of the object constructor
+     * @return     The object.
+     */
+    template <class H, class... Args>
+    std::shared_ptr<H> add_object(Material *material, Args &&...args)
+    {
+        auto object = std::make_shared<H>(std::forward<Args>(args)..., material);
+        add(object, material);
+        return object;
+    }
+
+    /**
+     * @brief      Adds an existing object, e.g. a transformed one, to the scene.
+     * @param[in]  object    The object
+     * @param[in]  material  The material of the object
+     */
+    void add(std::shared_ptr<Hittable> object, Material *material)
+    {
+        object->set_material(material);
+        world.add(object);
+    }
+
+    /**
+     * @brief      Builds the scene.
+     *             The objects with emissive materials are set as lights of all
+     *             Lambertian materials of the scene.
+     * @return     The scene, sharing the materials with the builder.
+     */
+    Scene build() const
+    {
+        auto lights = std::make_shared<HittableList>();
+        auto light_list = std::make_shared<LightList>();
+        for (int i = 0; i < world.size(); i++)
+        {
+            Material *material = world.get(i)->get_material();
+            if (material && material->is_emissive())
+            {
+                lights->add(world.get(i));
+                light_list->add(world.get(i));
+            }
+        }
+        for (const auto &material : materials)
+        {
+            if (material->is_lambertian())
+            {
+                auto lambertian = static_cast<Lambertian *>(material.get());
+                lambertian->set_lights(lights);
+                lambertian->set_light_list(light_list);
+            }
+        }
+
+        Scene scene;
+        scene.world = world;
+        scene.materials = materials;
+        return scene;
+    }
+
+private:
+    HittableList world;
+    std::vector<std::shared_ptr<Material>> materials;
+};
+
+#endif // SCENE_BUILDER_H
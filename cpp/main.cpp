@@ -17,15 +17,22 @@
 #include "load_scene.h"
 #include "background.h"
 #include "shaders.h"
+#include "render.h"
 
 using namespace std::literals;
 
-void find_light_sources(HittableList &world, std::shared_ptr<HittableList>& lights)
+void find_light_sources(HittableList &world, std::shared_ptr<HittableList>& lights, std::shared_ptr<Background> background)
 {
+    // environment maps are sampled as lights as well
+    auto environment = std::dynamic_pointer_cast<EnvMap>(background);
+    auto light_list = std::make_shared<LightList>();
     for (int i = 0; i < world.size(); i++)
     {
         if (world.get(i)->get_material()->is_emissive())
+        {
             lights->add(world.get(i));
+            light_list->add(world.get(i));
+        }
     }
     for (int j = 0; j < world.size(); j++)
     {
@@ -36,6 +43,9 @@ void find_light_sources(HittableList &world, std::shared_ptr<HittableList>& ligh
             std::cout << "setting lights for material " << j << std::endl;
 
             lambertian->set_lights(lights);
+            lambertian->set_light_list(light_list);
+            if (environment)
+                lambertian->set_environment(environment);
         }
     }
 }
@@ -76,9 +86,20 @@ int main(int argc, char** argv)
         std::cout << "List of commands:..." << std::endl;
         std::cout << " -h: Help" << std::endl;
         std::cout << " -i: Input scene file (.yaml)" << std::endl;
-        std::cout << " -o: Output file name (.ppm)" << std::endl;
+        std::cout << " -o: Output file name (.ppm, .png or .hdr)" << std::endl;
         std::cout << " -v: Verbose" << std::endl;
         std::cout << " -s: Number of samples per pixel" << std::endl;
+        std::cout << " -n: Maximum number of bounces per ray (default 16)" << std::endl;
+        std::cout << " -b: Write binary ppm (P6)" << std::endl;
+        std::cout << " -g: Gamma (default 2.2, 1.0 disables gamma correction), or srgb or linear" << std::endl;
+        std::cout << " -t: Tone mapping (clamp, reinhard or reinhard_extended, default clamp)" << std::endl;
+        std::cout << " -d: Output file name for a depth image (.ppm or .png)" << std::endl;
+        std::cout << " -l: Number of bounces before paths are terminated by russian roulette (default off)" << std::endl;
+        std::cout << " -m: Shading mode (path_trace, nee, spectral, normals, depth, albedo or ao, default path_trace)" << std::endl;
+        std::cout << " -r: Random seed for reproducible renders" << std::endl;
+        std::cout << " -w: White point for reinhard_extended tone mapping (default 1.0)" << std::endl;
+        std::cout << " -f: Largest channel of indirect light per sample against fireflies (default off)" << std::endl;
+        std::cout << " -a: Number of rays and distance of ambient occlusion, e.g. -a 16,1.0 (default 16,1.0)" << std::endl;
         return 0;
     }
     
@@ -103,6 +124,14 @@ int main(int argc, char** argv)
         std::cout << "Number of samples not specified: Default=" << samples_per_pixel << std::endl;
     }
 
+    // maximum depth of the rays, rays that reach it return black
+    int max_depth = 16;
+    const std::string max_depth_string = input.getCmdOption("-n");
+    if (!max_depth_string.empty())
+    {
+        max_depth = std::stoi(max_depth_string);
+    }
+
 
     
 
@@ -110,41 +139,126 @@ int main(int argc, char** argv)
     std::cerr << "Loading scene: " << in_file_name << std::endl;
     std::cerr << "Output file: " << out_file_name << std::endl;
 
+    // random seed, also used while loading the scene (bvh, perlin noise)
+    const std::string seed_string = input.getCmdOption("-r");
+    if (!seed_string.empty())
+    {
+        seed_random(std::stoul(seed_string));
+    }
+
     // world
     Camera camera;
-    HittableList world = load_scene(in_file_name, camera);
-    auto night_background = SolidBackground(Color(0.0, 0.0, 0.0));
-    auto day_background = GradientBackground(Color(0.05, 0.07, 0.01), Color(0.1, 0.1, 0.1));
+    std::shared_ptr<Background> background;
+    HittableList world = load_scene(in_file_name, camera, background);
     
+    const std::string gamma_string = input.getCmdOption("-g");
+    if (gamma_string == "srgb")
+    {
+        camera.image.set_color_space(ColorSpace::Srgb);
+    }
+    else if (gamma_string == "linear")
+    {
+        camera.image.set_color_space(ColorSpace::Linear);
+    }
+    else if (!gamma_string.empty())
+    {
+        camera.image.set_gamma(std::stod(gamma_string));
+    }
+
+    const std::string tone_map_string = input.getCmdOption("-t");
+    const std::string white_point_string = input.getCmdOption("-w");
+    double white_point = white_point_string.empty() ? 1.0 : std::stod(white_point_string);
+    if (tone_map_string == "reinhard")
+    {
+        camera.image.set_tone_map(ToneMap::Reinhard);
+    }
+    else if (tone_map_string == "reinhard_extended")
+    {
+        camera.image.set_tone_map(ToneMap::ReinhardExtended, white_point);
+    }
+    else if (!tone_map_string.empty() && tone_map_string != "clamp")
+    {
+        std::cout << "Unknown tone mapping: " << tone_map_string << std::endl;
+        return 1;
+    }
+
     auto lights = std::make_shared<HittableList>(); 
-    find_light_sources(world, lights);
+    find_light_sources(world, lights, background);
     
-    ProgressBar bar(camera.image.get_height());
-    Color color;
-    for (int j = camera.image.get_height() - 1; j >= 0; --j)
+    const std::string mode_string = input.getCmdOption("-m");
+    ShadingMode mode = ShadingMode::PathTrace;
+    if (mode_string == "normals")
     {
-        bar.increment();
-        for (int i = 0; i < camera.image.get_width(); ++i)
-        {
-            // progress bar
-            for (int s = 0; s < samples_per_pixel; ++s)
-            {
-                // ray
-                double u = camera.image.get_u(i);
-                double v = camera.image.get_v(j);
-                Ray ray = camera.get_ray(u, v);
-
-                color = ray_tracing_shader(ray, world, night_background, 16);
-
-                // discard NANs
-                if (color.x() != color.x()) color = Color(0,0,0);
-                if (color.y() != color.y()) color = Color(0,0,0);
-                if (color.z() != color.z()) color = Color(0,0,0);
-                camera.image.add_color(i, j, color);
-            }
-        }
+        mode = ShadingMode::Normals;
+    }
+    else if (mode_string == "depth")
+    {
+        mode = ShadingMode::Depth;
+    }
+    else if (mode_string == "albedo")
+    {
+        mode = ShadingMode::Albedo;
+    }
+    else if (mode_string == "nee")
+    {
+        mode = ShadingMode::NextEvent;
+    }
+    else if (mode_string == "ao")
+    {
+        mode = ShadingMode::AmbientOcclusion;
+    }
+    else if (mode_string == "spectral")
+    {
+        mode = ShadingMode::Spectral;
+    }
+    else if (!mode_string.empty() && mode_string != "path_trace")
+    {
+        std::cout << "Unknown shading mode: " << mode_string << std::endl;
+        return 1;
+    }
+
+    const std::string roulette_string = input.getCmdOption("-l");
+    int roulette_min_depth = roulette_string.empty() ? -1 : std::stoi(roulette_string);
+
+    const std::string firefly_string = input.getCmdOption("-f");
+    double firefly_clamp = firefly_string.empty() ? infinity : std::stod(firefly_string);
+
+    // ambient occlusion as samples,distance
+    const std::string ao_string = input.getCmdOption("-a");
+    int ao_samples = 16;
+    double ao_distance = 1.0;
+    if (!ao_string.empty())
+    {
+        size_t comma = ao_string.find(',');
+        ao_samples = std::stoi(ao_string.substr(0, comma));
+        if (comma != std::string::npos)
+            ao_distance = std::stod(ao_string.substr(comma + 1));
     }
 
-    camera.image.write_to_ppm(out_file_name);
+    if (!seed_string.empty())
+        render_with_seed(camera, world, *background, samples_per_pixel, max_depth, std::stoul(seed_string), mode, roulette_min_depth, firefly_clamp,
+                         ao_samples, ao_distance);
+    else
+        render(camera, world, *background, samples_per_pixel, max_depth, mode, roulette_min_depth, firefly_clamp, ao_samples, ao_distance);
+
+    if (out_file_name.size() >= 4 && out_file_name.compare(out_file_name.size() - 4, 4, ".png") == 0)
+        camera.image.write_to_png(out_file_name);
+    else if (out_file_name.size() >= 4 && out_file_name.compare(out_file_name.size() - 4, 4, ".hdr") == 0)
+        camera.image.write_to_hdr(out_file_name);
+    else if (input.cmdOptionExists("-b"))
+        camera.image.write_to_ppm_binary(out_file_name);
+    else
+        camera.image.write_to_ppm(out_file_name);
+
+    // depth image, normalized to the farthest corner of the scene
+    const std::string depth_file_name = input.getCmdOption("-d");
+    if (!depth_file_name.empty())
+    {
+        ImageData depth = render_depth(camera, world, 0.0, scene_far_distance(camera, world));
+        if (depth_file_name.size() >= 4 && depth_file_name.compare(depth_file_name.size() - 4, 4, ".png") == 0)
+            depth.write_to_png(depth_file_name);
+        else
+            depth.write_to_ppm(depth_file_name);
+    }
     return 0;
 }
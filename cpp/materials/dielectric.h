@@ -7,6 +7,15 @@ A ray that hits a dielectric material can either be reflected or refracted.
 The probability of reflection increases as the angle of incidence increases.
 The probability of reflection also increases as the refractive index of the
 material increases.
+
+Colored glass absorbs light inside the medium following Beer's law: a ray
+that travels the distance d inside is attenuated by exp(-absorption * d).
+A ray that hits the surface from the inside started at the previous hit
+inside the medium, so the distance is the length of that ray up to the hit.
+
+Dispersion follows Cauchy's equation n = A + B / wavelength^2. The refractive
+index of the material is the one at 589.3 nm (sodium D line), so the
+dispersion B only changes the index of rays that carry a wavelength.
 */
 
 #ifndef DIELECTRIC_H
@@ -22,9 +31,26 @@ class Dielectric : public Material
 {
 private:
     double ref_idx;
+    Color absorption;
+    double dispersion; // Cauchy coefficient B in um^2
 
 public:
-    Dielectric(double ri) : ref_idx(ri) {}
+    Dielectric(double ri, const Color &absorption = Color(0, 0, 0), double dispersion = 0)
+        : ref_idx(ri), absorption(absorption), dispersion(dispersion) {}
+
+    /**
+     * @brief      The refractive index for a wavelength.
+     * @param[in]  wavelength  The wavelength in nm, 0 for the index at 589.3 nm
+     * @return     The refractive index
+     */
+    double refractive_index(double wavelength) const
+    {
+        if (wavelength <= 0)
+            return ref_idx;
+        double lambda = wavelength / 1000.0;
+        double lambda_d = 0.5893;
+        return ref_idx + dispersion * (1 / (lambda * lambda) - 1 / (lambda_d * lambda_d));
+    }
 
     /**
      * @brief      Scatter a ray from a dielectric material.
@@ -37,10 +63,19 @@ public:
         scatter_record.is_specular = true;
         scatter_record.pdf = nullptr;
         scatter_record.attenuation = Color(1.0, 1.0, 1.0);
-        double refraction_ratio = hit_record.is_front_face(r_in) ? (1.0 / ref_idx) : ref_idx;
+        if (!hit_record.is_front_face(r_in))
+        {
+            // absorption on the way through the medium
+            double distance = hit_record.get_t() * r_in.direction.length();
+            scatter_record.attenuation = Color(exp(-absorption.r() * distance),
+                                               exp(-absorption.g() * distance),
+                                               exp(-absorption.b() * distance));
+        }
+        double index = refractive_index(r_in.wavelength);
+        double refraction_ratio = hit_record.is_front_face(r_in) ? (1.0 / index) : index;
 
         Vec3 unit_direction = unit_vector(r_in.direction);
-        Vec3 unit_normal = hit_record.is_front_face(r_in) ? hit_record.get_normal() : -hit_record.get_normal();
+        Vec3 unit_normal = hit_record.is_front_face(r_in) ? hit_record.get_shading_normal() : -hit_record.get_shading_normal();
         double cos_theta = fmin(dot(-unit_direction, unit_normal), 1.0);
         double sin_theta = sqrt(1.0 - cos_theta * cos_theta);
 
@@ -60,11 +95,6 @@ public:
         return true;
     }
 
-    Color emitted(double u, double v, const Vec3 &p) const override
-    {
-        return Color(0, 0, 0);
-    }
-    
     std::string toString() {
         return "Dielectric";
     }
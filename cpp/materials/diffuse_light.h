@@ -33,7 +33,7 @@ public:
     {
         return false;
     }
-    Color emitted(double u, double v, const Vec3 &p) const override
+    Color emitted(const Ray &r_in, double u, double v, const Vec3 &p) const override
     {
         return albedo->value(u, v, p);
     }
@@ -0,0 +1,52 @@
+/*
+isotropic.h
+===========
+Phase function of a participating medium (fog, smoke).
+
+Light is scattered uniformly in all directions.
+*/
+
+#ifndef ISOTROPIC_H
+#define ISOTROPIC_H
+
+#include <memory>
+
+#include "materials.h"
+#include "solid_color.h"
+#include "hit_record.h"
+
+/**
+ * @brief      Class for isotropic material.
+ */
+class Isotropic : public Material
+{
+private:
+    std::shared_ptr<Texture> albedo;
+
+public:
+    Isotropic(const Color &a)
+    {
+        this->albedo = std::make_shared<SolidColor>(a);
+    }
+    Isotropic(Texture *a) : albedo(a) {}
+
+    /**
+     * @brief      Scatter a ray in a uniformly random direction.
+     * @param[in]  r_in         The incoming ray
+     * @param[in]  hit_record   The hit record
+     * @param[out] scatter_record   The scatter record
+     */
+    virtual bool scatter(const Ray &r_in, const HitRecord &hit_record, ScatterRecord &scatter_record) const override
+    {
+        scatter_record.is_specular = true;
+        scatter_record.pdf = nullptr;
+        scatter_record.attenuation = albedo->value(hit_record.u, hit_record.v, hit_record.get_hit_point());
+        scatter_record.specular_ray = Ray(hit_record.get_hit_point(), random_unit_vector(), r_in.get_time());
+        return true;
+    }
+    std::string to_string() {
+        return "Isotropic";
+    }
+};
+
+#endif // ISOTROPIC_H
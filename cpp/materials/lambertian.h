@@ -16,6 +16,7 @@ normal and the scattered ray.
 #include "hit_record.h"
 #include "onb.h"
 #include "pdf.h"
+#include "env_map.h"
 
 /**
  * @brief      Class for lamberitan material.
@@ -25,6 +26,8 @@ class Lambertian : public Material
 private:
     std::shared_ptr<Texture> albedo;
     std::shared_ptr<HittableList> lights;
+    std::shared_ptr<LightList> light_list;
+    std::shared_ptr<EnvMap> environment;
 
 public:
     Lambertian(const Color &a)
@@ -42,10 +45,6 @@ public:
     Lambertian(Texture *a) : albedo(a) {}
 
     virtual bool scatter(const Ray &r_in, const HitRecord &hit_record, ScatterRecord &scatter_record) const override;
-    Color emitted(double u, double v, const Vec3 &p) const override
-    {
-        return Color(0, 0, 0);
-    }
     std::string to_string() {
         return "Lambertian";
     }
@@ -56,9 +55,24 @@ public:
     void set_lights(std::shared_ptr<HittableList> lights) {
         this->lights = lights;
     }
+    /**
+     * @brief      Sets weighted lights, sampled instead of the uniformly picked lights.
+     * @param[in]  light_list  The lights
+     */
+    void set_light_list(std::shared_ptr<LightList> light_list) {
+        this->light_list = light_list;
+    }
+    /**
+     * @brief      Sets an environment map that is sampled as a light, together with the other lights.
+     * @param[in]  environment  The environment map of the background
+     */
+    void set_environment(std::shared_ptr<EnvMap> environment) {
+        this->environment = environment;
+    }
     virtual double scattering_pdf(const Ray &r_in, const HitRecord &rec, const Ray &scattered) const override
     {
-        double cosine = dot(rec.get_normal(), unit_vector(scattered.direction));
+        Vec3 normal = rec.is_front_face(r_in) ? rec.get_shading_normal() : -rec.get_shading_normal();
+        double cosine = dot(normal, unit_vector(scattered.direction));
         return cosine < 0 ? 0 : cosine / pi;
     }
 
@@ -69,6 +83,10 @@ public:
  *             The probability of scattering is proportional to the cosine of the angle between the surface
  *             normal and the scattered ray.
  *             The scattered ray is generated with equal probability either from a cosine distribution or from the light source.
+ *             The light source is picked from the weighted light list if one is set.
+ *             With an environment map, half of the light samples go to the map.
+ *             Without lights and environment map, only the cosine distribution is used.
+ *             Both parts of the mixture are also stored on their own for next event estimation.
  * @param[in]  r_in         The incoming ray
  * @param[in]  hit_record   The hit record
  * @param[out] scatter_record  The scatter record
@@ -76,12 +94,30 @@ public:
 */
 bool Lambertian::scatter(const Ray &r_in, const HitRecord &hit_record, ScatterRecord &scatter_record) const
 {
-    auto light_pdf = std::make_shared<HittablePDF>(this->lights, hit_record.get_hit_point(), r_in.get_time());
-    auto cosine_pdf = std::make_shared<CosinePDF>(hit_record.get_normal());
-    auto mixture_pdf = std::make_shared<MixturePDF>(light_pdf, cosine_pdf);
+    std::shared_ptr<PDF> light_pdf;
+    if (this->light_list && this->light_list->size() > 0)
+        light_pdf = std::make_shared<LightPDF>(this->light_list, hit_record.get_hit_point(), r_in.get_time());
+    else
+        light_pdf = std::make_shared<HittablePDF>(this->lights, hit_record.get_hit_point(), r_in.get_time());
+    bool has_lights = (this->light_list && this->light_list->size() > 0) || (this->lights && this->lights->size() > 0);
+    if (this->environment)
+    {
+        auto environment_pdf = std::make_shared<EnvMapPDF>(this->environment);
+        light_pdf = has_lights ? std::static_pointer_cast<PDF>(std::make_shared<MixturePDF>(light_pdf, environment_pdf))
+                               : std::static_pointer_cast<PDF>(environment_pdf);
+    }
+    // scatter into the hemisphere the ray came from
+    Vec3 normal = hit_record.is_front_face(r_in) ? hit_record.get_shading_normal() : -hit_record.get_shading_normal();
+    auto cosine_pdf = std::make_shared<CosinePDF>(normal);
+    bool samples_lights = has_lights || this->environment;
+    scatter_record.light_pdf = samples_lights ? light_pdf : nullptr;
+    scatter_record.material_pdf = cosine_pdf;
     scatter_record.is_specular = false;
-    scatter_record.attenuation = this->albedo->value(hit_record.u, hit_record.v, hit_record.get_hit_point());
-    scatter_record.pdf = mixture_pdf;
+    scatter_record.attenuation = this->albedo->value(hit_record.u, hit_record.v, hit_record.get_hit_point(), hit_record.get_footprint());
+    if (samples_lights)
+        scatter_record.pdf = std::make_shared<MixturePDF>(light_pdf, cosine_pdf);
+    else
+        scatter_record.pdf = cosine_pdf;
     return true;
 }
 
@@ -2,6 +2,10 @@
 materials.h
 ===========
 Contains the abstract class for materials.
+
+Every material can carry a normal map. The colors of the map are the
+tangent space normal, 0.5 * (normal + 1), with x along the tangent of the
+hit record, y along the bitangent and z along the surface normal.
 */
 
 #ifndef MATERIAL_H
@@ -14,8 +18,8 @@ Contains the abstract class for materials.
 #include "texture.h"
 #include "pdf.h"
 #include "scatter_record.h"
-
-class HitRecord;
+#include "hit_record.h"
+#include "onb.h"
 
 class Material
 {
@@ -24,7 +28,11 @@ public:
     {
         return false;
     };
-    virtual Color emitted(double u, double v, const Vec3 &p) const = 0;
+    virtual Color emitted(const Ray &r_in, double u, double v, const Vec3 &p) const
+    {
+        // non-emissive materials emit no light
+        return Color(0, 0, 0);
+    }
     std::shared_ptr<Texture> get_albedo() const
     {
         return albedo;
@@ -33,6 +41,15 @@ public:
     {
         albedo = a;
     }
+    std::shared_ptr<Texture> get_normal_map() const
+    {
+        return normal_map;
+    }
+    void set_normal_map(std::shared_ptr<Texture> map)
+    {
+        normal_map = map;
+    }
+    void apply_normal_map(HitRecord &rec) const;
     virtual std::string to_string() {
         return "Material";
     };
@@ -51,38 +68,42 @@ public:
     
 private:
     std::shared_ptr<Texture> albedo;
+    std::shared_ptr<Texture> normal_map;
 };
 
-// Utility Functions
-
 /**
- * @brief      Reflects an incoming direction against a surface with the given
- *             normal.
- * @param[in]  v     Incoming direction.
- * @param[in]  n     Surface normal.
- * @return     Reflected direction.
-*/
-inline Vec3 reflect(const Vec3 &v, const Vec3 &n)
+ * @brief      Perturbs the shading normal of the hit record with the normal map.
+ *             The tangent space is built from the tangent of the hit record, or
+ *             from an arbitrary tangent if the object does not provide one.
+ *             The geometric normal is not changed.
+ * @param      rec   The hit record
+ */
+void Material::apply_normal_map(HitRecord &rec) const
 {
-    return v - 2 * dot(v, n) * n;
-}
+    if (!normal_map)
+        return;
 
-/**
- * @brief      Refracts an incoming direction against a surface with the given
- *            normal.
- * @param[in]  uv                Incoming direction.
- * @param[in]  normal            Surface normal.
- * @param[in]  refraction_ratio  The refraction ratio
- * @return     Refracted direction.
-*/
-inline Vec3 refract(const Vec3 &uv, const Vec3 &normal, double refraction_ratio)
-{
-    auto cos_theta = fmin(dot(-uv, normal), 1.0);
-    Vec3 r_out_perp = refraction_ratio * (uv + cos_theta * normal);
-    Vec3 r_out_parallel = -sqrt(fabs(1.0 - r_out_perp.length_squared())) * normal;
-    return r_out_perp + r_out_parallel;
+    Vec3 normal = rec.get_normal();
+    Vec3 tangent = rec.get_tangent() - dot(rec.get_tangent(), normal) * normal;
+    if (tangent.length_squared() < 1e-12)
+    {
+        ONB uvw;
+        uvw.build_from_w(normal);
+        tangent = uvw.u();
+    }
+    tangent = unit_vector(tangent);
+    Vec3 bitangent = cross(normal, tangent);
+
+    Color color = normal_map->value(rec.u, rec.v, rec.get_hit_point());
+    Vec3 local = 2 * color - Vec3(1, 1, 1);
+    Vec3 shading_normal = local.x() * tangent + local.y() * bitangent + local.z() * normal;
+    if (shading_normal.length_squared() < 1e-12)
+        return;
+    rec.set_shading_normal(unit_vector(shading_normal));
 }
 
+// Utility Functions
+
 /**
  * @brief      Calculates the reflectance of a surface based on the cosine of
  *             the angle between the surface normal and the incoming ray.
@@ -0,0 +1,84 @@
+/*
+spotlight.h
+===========
+A material that emits light only within a cone around a direction.
+*/
+
+#ifndef SPOTLIGHT_H
+#define SPOTLIGHT_H
+
+#include <cmath>
+
+#include "materials.h"
+#include "color.h"
+#include "vec3.h"
+#include "ray.h"
+#include "hit_record.h"
+#include "texture.h"
+#include "solid_color.h"
+
+/**
+ * @brief      Class for spotlight.
+ * @details    The light is emitted with full intensity within the inner cone
+ *             angle around the direction of the spotlight. Between the inner
+ *             and the outer angle the intensity falls off smoothly, outside
+ *             the outer angle no light is emitted.
+ */
+class Spotlight : public Material
+{
+private:
+    std::shared_ptr<Texture> albedo;
+    Vec3 direction;
+    double cos_inner;
+    double cos_outer;
+
+public:
+    /**
+     * @brief      Constructs a spotlight.
+     * @param[in]  a            The emitted color
+     * @param[in]  direction    The direction of the light
+     * @param[in]  inner_angle  The angle of full intensity in degrees
+     * @param[in]  outer_angle  The angle beyond which no light is emitted in degrees
+     */
+    Spotlight(const Color &a, const Vec3 &direction, double inner_angle, double outer_angle)
+        : albedo(std::make_shared<SolidColor>(a)), direction(unit_vector(direction)),
+          cos_inner(cos(degrees_to_radians(inner_angle))), cos_outer(cos(degrees_to_radians(outer_angle))) {}
+
+    virtual bool scatter(const Ray &r_in, const HitRecord &rec, ScatterRecord &scatter_record) const override
+    {
+        return false;
+    }
+
+    Color emitted(const Ray &r_in, double u, double v, const Vec3 &p) const override
+    {
+        // the light travels from p back along the incoming ray
+        double cos_angle = dot(-unit_vector(r_in.direction), direction);
+        return falloff(cos_angle) * albedo->value(u, v, p);
+    }
+
+    /**
+     * @brief      Intensity factor for the cosine of the angle to the spotlight direction.
+     * @param[in]  cos_angle  The cosine of the angle
+     * @return     1 within the inner cone, 0 outside the outer cone and
+     *             a smoothstep in between.
+     */
+    double falloff(double cos_angle) const
+    {
+        if (cos_angle >= cos_inner)
+            return 1.0;
+        if (cos_angle <= cos_outer)
+            return 0.0;
+        double x = (cos_angle - cos_outer) / (cos_inner - cos_outer);
+        return x * x * (3 - 2 * x);
+    }
+
+    std::string to_string() {
+        return "Spotlight";
+    }
+    virtual bool is_emissive() const override
+    {
+        return true;
+    }
+};
+
+#endif // SPOTLIGHT_H
@@ -14,6 +14,7 @@ of the rendering equation.
 #include "vec3.h"
 #include "hittable_list.h"
 #include "hittable.h"
+#include "light_list.h"
 
 #include "onb.h"
 
@@ -23,6 +24,7 @@ of the rendering equation.
 class PDF
 {
 public:
+    virtual double value(const Vec3 &direction) const = 0;
     virtual void generate(Vec3 &direction, double &value) const = 0;
 };
 
@@ -42,7 +44,7 @@ public:
         uvw.build_from_w(w);
     }
 
-    double get_pdf_value(const Vec3 &direction) const
+    virtual double value(const Vec3 &direction) const override
     {
         auto cosine = dot(unit_vector(direction), uvw.w());
         return (cosine <= 0) ? 0 : cosine / pi;
@@ -51,7 +53,7 @@ public:
     virtual void generate(Vec3 &direction, double &value) const override
     {
         direction = uvw.local(random_cosine_direction());
-        value = this->get_pdf_value(direction);	
+        value = this->value(direction);
     }
 };
 
@@ -68,18 +70,88 @@ private:
 public:
     HittablePDF(std::shared_ptr<HittableList> hittables, const Vec3 &o, double time) : hittables(hittables), o(o), time(time) {}
 
+    /**
+     * @brief      The density of a direction, averaged over all hittables,
+     *             since each of them is sampled with the same probability.
+     * @param[in]  direction  The direction
+     * @return     The density, 0 without hittables
+     */
+    virtual double value(const Vec3 &direction) const override
+    {
+        if (hittables->size() == 0)
+            return 0;
+        double sum = 0;
+        for (int i = 0; i < hittables->size(); i++)
+        {
+            sum += hittables->get(i)->pdf_value(o, direction, time);
+        }
+        return sum / hittables->size();
+    }
+
+    /**
+     * @brief      Generates a direction towards a randomly picked hittable.
+     *             Must not be used without hittables, since no direction can be
+     *             generated, value is 0 in that case.
+     * @param[out] direction  The direction
+     * @param[out] value      The density of the direction
+     */
     virtual void generate(Vec3 &direction, double &value) const override
     {
+        if (hittables->size() == 0)
+        {
+            direction = Vec3(1, 0, 0);
+            value = 0;
+            return;
+        }
         int hittableIndex = random_int(0, hittables->size() - 1);
         std::shared_ptr<Hittable> hittable = hittables->get(hittableIndex);
         direction = hittable->random(o);
-        value = hittable->pdf_value(o, direction, time); 
+        value = this->value(direction);
+    }
+};
+
+/**
+ * @brief      Class for the pdf of a weighted light list.
+ *             Picks a light with LightList::sample_one and a direction towards it.
+ */
+class LightPDF : public PDF
+{
+private:
+    Vec3 o;
+    std::shared_ptr<LightList> lights;
+    double time;
+
+public:
+    LightPDF(std::shared_ptr<LightList> lights, const Vec3 &o, double time) : o(o), lights(lights), time(time) {}
+    /**
+     * @brief      The density of a direction, the densities of all lights
+     *             weighted with the probability of picking them.
+     * @param[in]  direction  The direction
+     * @return     The density
+     */
+    virtual double value(const Vec3 &direction) const override
+    {
+        double sum = 0;
+        for (int i = 0; i < lights->size(); i++)
+        {
+            sum += lights->selection_probability(i) * lights->get(i)->pdf_value(o, direction, time);
+        }
+        return sum;
+    }
+    virtual void generate(Vec3 &direction, double &value) const override
+    {
+        double selection_pdf;
+        std::shared_ptr<Hittable> light = lights->sample_one(selection_pdf);
+        direction = light->random(o);
+        value = this->value(direction);
     }
 };
 
 /**
  * @brief      Class for mixture pdf.
  *             Generates a direction from one of two pdfs with equal probability.
+ *             The density of a direction is the average of both densities,
+ *             regardless of which pdf generated it.
  */
 class MixturePDF : public PDF
 {
@@ -92,6 +164,11 @@ public:
         p[0] = p0;
         p[1] = p1;
     }
+    virtual double value(const Vec3 &direction) const override
+    {
+        return 0.5 * p[0]->value(direction) + 0.5 * p[1]->value(direction);
+    }
+
     virtual void generate(Vec3 &direction, double &value) const override
     {
         if (random_double() < 0.5)
@@ -102,6 +179,7 @@ public:
         {
             p[1]->generate(direction, value);
         }
+        value = this->value(direction);
     }
 };
 
@@ -22,6 +22,9 @@ Usage
     }
 */
 
+#ifndef PROGRESS_BAR_H
+#define PROGRESS_BAR_H
+
 #include <iostream>
 #include <chrono>
 #include <ctime>
@@ -107,3 +110,5 @@ public:
         update(progress + 1);
     }
 };
+
+#endif // PROGRESS_BAR_H
@@ -2,6 +2,12 @@
 ray.h
 =====
 Represents a ray in 3D space, defined by an origin, a direction and a time.
+
+Camera rays can carry ray differentials: two auxiliary rays through the
+neighbouring pixels in x and y. Where they meet the surface around a hit
+gives the footprint of the pixel, used to filter textures.
+
+Rays of spectral renders carry a single wavelength, see spectrum.h.
 */
 
 #ifndef RAY_H
@@ -18,15 +24,49 @@ public:
     Vec3 origin;
     Vec3 direction;
     double time;
+    double wavelength = 0; // in nm, 0 if the ray carries all colors
+    bool has_differentials = false;
+    Vec3 rx_origin, rx_direction; // ray through the next pixel in x
+    Vec3 ry_origin, ry_direction; // ray through the next pixel in y
 
-    Ray() {}
-    Ray(const Vec3 &origin, const Vec3 &direction, double time) : origin(origin), direction(direction), time(time) {}
+    Ray() : time(0) {}
+    Ray(const Vec3 &origin, const Vec3 &direction, double time = 0) : origin(origin), direction(direction), time(time) {}
 
     double get_time() const { return time; }
     Vec3 point_at_parameter(double t) const
     {
         return origin + t * direction;
     }
+
+    void set_differentials(const Vec3 &rx_origin, const Vec3 &rx_direction, const Vec3 &ry_origin, const Vec3 &ry_direction)
+    {
+        this->has_differentials = true;
+        this->rx_origin = rx_origin;
+        this->rx_direction = rx_direction;
+        this->ry_origin = ry_origin;
+        this->ry_direction = ry_direction;
+    }
+
+    /**
+     * @brief      Intersects the differential rays with the tangent plane at a hit.
+     * @param[in]  p     The hit point
+     * @param[in]  n     The normal at the hit point
+     * @param[out] px    The point hit by the ray through the next pixel in x
+     * @param[out] py    The point hit by the ray through the next pixel in y
+     * @return     True if the ray has differentials and both hit the plane.
+     */
+    bool differential_hits(const Vec3 &p, const Vec3 &n, Vec3 &px, Vec3 &py) const
+    {
+        if (!has_differentials)
+            return false;
+        double dx = dot(n, rx_direction);
+        double dy = dot(n, ry_direction);
+        if (fabs(dx) < 1e-12 || fabs(dy) < 1e-12)
+            return false;
+        px = rx_origin + dot(n, p - rx_origin) / dx * rx_direction;
+        py = ry_origin + dot(n, p - ry_origin) / dy * ry_direction;
+        return true;
+    }
 };
 
 #endif
\ No newline at end of file
@@ -3,10 +3,14 @@
 
 #include <cmath>
 #include <limits>
+#include <random>
 
 // constants
 const double infinity = std::numeric_limits<double>::infinity();
 const double pi = 3.1415926535897932385;
+// rays leaving a surface ignore hits closer than this, so that rounding
+// errors of the hit point do not hit the surface again (shadow acne)
+const double ray_epsilon = 0.001;
 
 // utility functions
 inline double degrees_to_radians(double degrees)
@@ -14,10 +18,25 @@ inline double degrees_to_radians(double degrees)
     return degrees * pi / 180.0;
 }
 
+inline std::mt19937 &random_generator()
+{
+    // Every thread has its own generator, so rendering threads do not share state.
+    // The generator is seeded randomly unless seed_random is called.
+    static thread_local std::mt19937 generator(std::random_device{}());
+    return generator;
+}
+
+inline void seed_random(unsigned int seed)
+{
+    // Seeds the generator of the calling thread, for reproducible results.
+    random_generator().seed(seed);
+}
+
 inline double random_double()
 {
     // Returns a random real in [0,1).
-    return rand() / (RAND_MAX + 1.0);
+    std::uniform_real_distribution<double> distribution(0.0, 1.0);
+    return distribution(random_generator());
 }
 
 inline double random_double(double min, double max)
@@ -0,0 +1,96 @@
+/*
+animation.h
+===========
+Renders an animation as a sequence of numbered PPM images.
+
+The scene of every frame is built by a user function of the frame time,
+e.g. to move objects or change materials. The camera stays the same for
+all frames.
+
+Example:
+```cpp
+auto frame = [](double t)
+{
+    SceneBuilder builder;
+    auto red = builder.add_material<Lambertian>(Color(0.8, 0.1, 0.1));
+    auto light = builder.add_material<DiffuseLight>(Color(4, 4, 4));
+    builder.add_object<Sphere>(red, Vec3(0, 2 * t, 0), 1.0);
+    builder.add_object<Sphere>(light, Vec3(0, 5, 0), 0.5);
+    return builder.build(); // the light is set on the Lambertian material
+};
+render_animation(frame, camera, background, 24, "frames", 100, 16);
+```
+*/
+
+#ifndef ANIMATION_H
+#define ANIMATION_H
+
+#include <cstdio>
+#include <filesystem>
+#include <fstream>
+#include <functional>
+#include <iostream>
+#include <string>
+
+#include "render.h"
+#include "scene_builder.h"
+
+/**
+ * @brief      File name of a frame, frame_0000.ppm for the first frame.
+ * @param[in]  frame  The index of the frame
+ * @return     The file name.
+ */
+inline std::string animation_frame_name(int frame)
+{
+    char name[32];
+    std::snprintf(name, sizeof(name), "frame_%04d.ppm", frame);
+    return name;
+}
+
+/**
+ * @brief      Renders the frames of an animation into a directory.
+ *             Frame k is rendered at time t = k / number_of_frames, so t runs
+ *             from 0 up to, but not including, 1. The directory is created if
+ *             it does not exist.
+ * @param[in]  frame_function     Builds the scene at a time t
+ * @param      camera             The camera, its image data is cleared for every frame
+ * @param      background         The background of the scene
+ * @param[in]  number_of_frames   The number of frames
+ * @param[in]  out_dir            The directory of the images
+ * @param[in]  samples_per_pixel  The number of samples per pixel
+ * @param[in]  max_depth          The maximum recursion depth of the shader
+ * @return     True if all frames were written, False at the first error.
+ */
+inline bool render_animation(const std::function<Scene(double)> &frame_function, Camera &camera, Background &background,
+                             int number_of_frames, const std::string &out_dir, int samples_per_pixel, int max_depth)
+{
+    std::error_code error;
+    std::filesystem::create_directories(out_dir, error);
+    if (error)
+    {
+        std::cerr << "Cannot create directory " << out_dir << ": " << error.message() << std::endl;
+        return false;
+    }
+
+    for (int frame = 0; frame < number_of_frames; frame++)
+    {
+        double t = static_cast<double>(frame) / number_of_frames;
+        Scene scene = frame_function(t);
+
+        camera.image.clear();
+        render(camera, scene.world, background, samples_per_pixel, max_depth);
+
+        std::string filename = (std::filesystem::path(out_dir) / animation_frame_name(frame)).string();
+        std::ofstream out(filename);
+        camera.image.write_ppm(out);
+        out.close();
+        if (!out)
+        {
+            std::cerr << "Cannot write frame " << filename << std::endl;
+            return false;
+        }
+    }
+    return true;
+}
+
+#endif // ANIMATION_H
@@ -0,0 +1,503 @@
+/*
+render.h
+========
+Renders a scene into the image data of a camera.
+
+The scanlines of the image are distributed over threads with OpenMP when
+the program is compiled with -fopenmp. Every thread only writes to the rows
+it renders, so no synchronisation of the image data is needed. Without
+-fopenmp the pragmas are ignored and the image is rendered serially.
+*/
+
+#ifndef RENDER_H
+#define RENDER_H
+
+#include "camera.h"
+#include "color.h"
+#include "spectrum.h"
+#include "ray.h"
+#include "hittable_list.h"
+#include "background.h"
+#include "shaders.h"
+#include "progress_bar.h"
+#include "render_stats.h"
+
+#include <chrono>
+#include <functional>
+
+/**
+ * @brief      What the renderer computes for each sample.
+ * @details    PathTrace renders the scene with materials and lights.
+ *             Normals shows the surface normals as colors.
+ *             Depth shows the distance to the camera as gray values.
+ *             Albedo shows the base color of the material at the first hit.
+ *             NextEvent renders like PathTrace, but samples the lights with
+ *             shadow rays at every diffuse bounce (next event estimation).
+ *             AmbientOcclusion shows how much of the hemisphere above the
+ *             first hit is free of geometry as gray values.
+ *             Spectral renders like PathTrace, but every sample carries a single
+ *             wavelength, so dielectrics with dispersion split light into colors.
+ */
+enum class ShadingMode
+{
+    PathTrace,
+    NextEvent,
+    Normals,
+    Depth,
+    Albedo,
+    AmbientOcclusion,
+    Spectral
+};
+
+/**
+ * @brief      Distance from the camera to the farthest corner of the bounding
+ *             box of the scene, used as far plane of depth images.
+ * @param[in]  camera  The camera
+ * @param      world   The hittable objects
+ * @return     The distance, at least 1.
+ */
+double scene_far_distance(const Camera &camera, HittableList &world)
+{
+    AABB box;
+    double far = 1.0;
+    if (world.bounding_box(camera.time0, camera.time1, box))
+    {
+        for (int corner = 0; corner < 8; corner++)
+        {
+            Vec3 p((corner & 1) ? box.get_max().x() : box.get_min().x(),
+                   (corner & 2) ? box.get_max().y() : box.get_min().y(),
+                   (corner & 4) ? box.get_max().z() : box.get_min().z());
+            far = fmax(far, (p - camera.look_from).length());
+        }
+    }
+    return far;
+}
+
+/**
+ * @brief      Renders a single pixel without writing to the image data.
+ *             The sample positions are jittered within the pixel (i, j).
+ *             Samples that are NAN are counted as black.
+ * @param[in]  camera             The camera
+ * @param      world              The hittable objects
+ * @param      background         The background of the scene
+ * @param[in]  i                  The column of the pixel
+ * @param[in]  j                  The row of the pixel, counted from the bottom
+ * @param[in]  samples            The number of samples
+ * @param[in]  max_depth          The maximum recursion depth of the shader
+ * @param[in]  mode               What to compute for each sample
+ * @param[in]  far                The far plane of the Depth mode
+ * @param[in]  roulette_min_depth The number of bounces after which paths are terminated
+ *                                by russian roulette, negative to disable russian roulette
+ * @param[in]  firefly_clamp      The largest channel of indirect light of a sample, infinity to disable it
+ * @param[in]  ao_samples         The number of rays per sample of the AmbientOcclusion mode
+ * @param[in]  ao_distance        The distance within which the rays of the AmbientOcclusion mode are occluded
+ * @return     The sum of the sample colors, divide by samples to get the pixel color.
+ */
+Color render_pixel(const Camera &camera, HittableList &world, Background &background, int i, int j, int samples, int max_depth,
+                   ShadingMode mode = ShadingMode::PathTrace, double far = 1.0, int roulette_min_depth = -1,
+                   double firefly_clamp = infinity, int ao_samples = 16, double ao_distance = 1.0)
+{
+    // the shader counts the depth down from max_depth
+    int roulette_depth = roulette_min_depth >= 0 ? max_depth - roulette_min_depth : 0;
+    auto trace = [&](const Ray &ray) {
+        if (mode == ShadingMode::NextEvent)
+            return next_event_shader(ray, world, background, max_depth, roulette_depth, Color(1, 1, 1), firefly_clamp);
+        return ray_tracing_shader(ray, world, background, max_depth, roulette_depth, Color(1, 1, 1), firefly_clamp);
+    };
+    Color sum(0, 0, 0);
+    for (int s = 0; s < samples; ++s)
+    {
+        // ray
+        double u, v;
+        camera.image.get_uv(i, j, s, samples, u, v);
+        Ray ray = camera.ray_differentials
+                      ? camera.get_ray(u, v, 1.0 / camera.image.get_width(), 1.0 / camera.image.get_height())
+                      : camera.get_ray(u, v);
+
+        Color color;
+        if ((mode == ShadingMode::PathTrace || mode == ShadingMode::NextEvent) && camera.chromatic_aberration != 0)
+        {
+            // every channel is traced with its own ray
+            for (int channel = 0; channel < 3; channel++)
+            {
+                Ray channel_ray = camera.get_ray(u, v, channel);
+                RenderCounters::count(render_counters().primary_rays);
+                color[channel] = trace(channel_ray)[channel];
+            }
+        }
+        else
+        {
+            RenderCounters::count(render_counters().primary_rays);
+            if (mode == ShadingMode::Normals)
+                color = normal_shader(ray, world);
+            else if (mode == ShadingMode::Depth)
+                color = depth_shader(ray, world, 0.0, far);
+            else if (mode == ShadingMode::Albedo)
+                color = albedo_shader(ray, world, background);
+            else if (mode == ShadingMode::AmbientOcclusion)
+                color = ambient_occlusion_shader(ray, world, ao_samples, ao_distance);
+            else if (mode == ShadingMode::Spectral)
+            {
+                ray.wavelength = random_wavelength();
+                color = trace(ray) * wavelength_weight(ray.wavelength);
+            }
+            else
+                color = trace(ray);
+        }
+
+        // discard NANs
+        if (color.x() != color.x()) color = Color(0,0,0);
+        if (color.y() != color.y()) color = Color(0,0,0);
+        if (color.z() != color.z()) color = Color(0,0,0);
+        sum += color;
+    }
+    return sum;
+}
+
+/**
+ * @brief      Renders all rows of the image, optionally seeding the random
+ *             generator at the start of every row.
+ * @param      camera             The camera, holds the image data
+ * @param      world              The hittable objects
+ * @param      background         The background of the scene
+ * @param[in]  samples_per_pixel  The number of samples per pixel
+ * @param[in]  max_depth          The maximum recursion depth of the shader
+ * @param[in]  seeded             Whether to seed the random generator per row
+ * @param[in]  seed               The seed, combined with the row index
+ * @param[in]  mode               What to compute for each sample
+ * @param[in]  roulette_min_depth The number of bounces before russian roulette starts, negative to disable it
+ * @param[in]  firefly_clamp      The largest channel of indirect light of a sample, infinity to disable it
+ * @param[in]  ao_samples         The number of rays per sample of the AmbientOcclusion mode
+ * @param[in]  ao_distance        The distance within which the rays of the AmbientOcclusion mode are occluded
+ */
+void render_rows(Camera &camera, HittableList &world, Background &background, int samples_per_pixel, int max_depth, bool seeded, unsigned int seed,
+                 ShadingMode mode, int roulette_min_depth, double firefly_clamp, int ao_samples, double ao_distance)
+{
+    ProgressBar bar(camera.image.get_height());
+    double far = mode == ShadingMode::Depth ? scene_far_distance(camera, world) : 1.0;
+
+    #pragma omp parallel for schedule(dynamic)
+    for (int j = camera.image.get_height() - 1; j >= 0; --j)
+    {
+        if (seeded)
+        {
+            // the random numbers of a row do not depend on the thread rendering it
+            seed_random(seed + 7919u * static_cast<unsigned int>(j));
+        }
+        for (int i = 0; i < camera.image.get_width(); ++i)
+        {
+            Color color = render_pixel(camera, world, background, i, j, samples_per_pixel, max_depth, mode, far, roulette_min_depth, firefly_clamp,
+                                       ao_samples, ao_distance);
+            camera.image.add_samples(i, j, color, samples_per_pixel);
+        }
+
+        // progress bar
+        #pragma omp critical
+        bar.increment();
+    }
+}
+
+/**
+ * @brief      Renders the scene into the image data of the camera.
+ * @param      camera             The camera, holds the image data
+ * @param      world              The hittable objects
+ * @param      background         The background of the scene
+ * @param[in]  samples_per_pixel  The number of samples per pixel
+ * @param[in]  max_depth          The maximum recursion depth of the shader
+ * @param[in]  mode               What to compute for each sample
+ * @param[in]  roulette_min_depth The number of bounces before russian roulette starts, negative to disable it
+ * @param[in]  firefly_clamp      The largest channel of indirect light of a sample, infinity to disable it
+ * @param[in]  ao_samples         The number of rays per sample of the AmbientOcclusion mode
+ * @param[in]  ao_distance        The distance within which the rays of the AmbientOcclusion mode are occluded
+ */
+void render(Camera &camera, HittableList &world, Background &background, int samples_per_pixel, int max_depth,
+            ShadingMode mode = ShadingMode::PathTrace, int roulette_min_depth = -1, double firefly_clamp = infinity,
+            int ao_samples = 16, double ao_distance = 1.0)
+{
+    render_rows(camera, world, background, samples_per_pixel, max_depth, false, 0, mode, roulette_min_depth, firefly_clamp, ao_samples, ao_distance);
+}
+
+/**
+ * @brief      Renders the scene reproducibly into the image data of the camera.
+ *             Two renders of the same scene with the same seed produce identical
+ *             image data, independent of the number of threads.
+ * @param      camera             The camera, holds the image data
+ * @param      world              The hittable objects
+ * @param      background         The background of the scene
+ * @param[in]  samples_per_pixel  The number of samples per pixel
+ * @param[in]  max_depth          The maximum recursion depth of the shader
+ * @param[in]  seed               The seed
+ * @param[in]  mode               What to compute for each sample
+ * @param[in]  roulette_min_depth The number of bounces before russian roulette starts, negative to disable it
+ * @param[in]  firefly_clamp      The largest channel of indirect light of a sample, infinity to disable it
+ * @param[in]  ao_samples         The number of rays per sample of the AmbientOcclusion mode
+ * @param[in]  ao_distance        The distance within which the rays of the AmbientOcclusion mode are occluded
+ */
+void render_with_seed(Camera &camera, HittableList &world, Background &background, int samples_per_pixel, int max_depth, unsigned int seed,
+                      ShadingMode mode = ShadingMode::PathTrace, int roulette_min_depth = -1, double firefly_clamp = infinity,
+                      int ao_samples = 16, double ao_distance = 1.0)
+{
+    render_rows(camera, world, background, samples_per_pixel, max_depth, true, seed, mode, roulette_min_depth, firefly_clamp, ao_samples, ao_distance);
+}
+
+/**
+ * @brief      Renders the scene with a different number of samples per pixel.
+ *             Every pixel takes samples until the standard error of the mean of
+ *             its luminance drops to the tolerance, at least min_samples and at
+ *             most max_samples. Uniform regions like the background stop early,
+ *             edges and noisy regions take more samples.
+ *             The number of samples of each pixel is kept in the image data.
+ * @param      camera             The camera, holds the image data
+ * @param      world              The hittable objects
+ * @param      background         The background of the scene
+ * @param[in]  min_samples        The number of samples before the error is checked, at least 2
+ * @param[in]  max_samples        The maximum number of samples per pixel
+ * @param[in]  tolerance          The standard error of the mean luminance at which a pixel stops
+ * @param[in]  max_depth          The maximum recursion depth of the shader
+ */
+void render_adaptive(Camera &camera, HittableList &world, Background &background, int min_samples, int max_samples, double tolerance, int max_depth)
+{
+    min_samples = std::max(min_samples, 2);
+    ProgressBar bar(camera.image.get_height());
+
+    #pragma omp parallel for schedule(dynamic)
+    for (int j = camera.image.get_height() - 1; j >= 0; --j)
+    {
+        for (int i = 0; i < camera.image.get_width(); ++i)
+        {
+            // running mean and sum of squared differences of the luminance (Welford)
+            double mean = 0.0;
+            double m2 = 0.0;
+            for (int n = 1; n <= max_samples; ++n)
+            {
+                Color color = render_pixel(camera, world, background, i, j, 1, max_depth);
+                camera.image.add_samples(i, j, color, 1);
+
+                double luminance = 0.2126 * color.r() + 0.7152 * color.g() + 0.0722 * color.b();
+                double delta = luminance - mean;
+                mean += delta / n;
+                m2 += delta * (luminance - mean);
+
+                if (n >= min_samples && m2 / (n - 1) / n <= tolerance * tolerance)
+                    break;
+            }
+        }
+
+        // progress bar
+        #pragma omp critical
+        bar.increment();
+    }
+}
+
+/**
+ * @brief      Renders the scene tile by tile into the image data of the camera.
+ *             Every tile is rendered completely by one thread. Tiles at the right
+ *             and top border are smaller if the image size is not a multiple of
+ *             the tile size. The progress bar counts finished tiles.
+ *             The optional callback receives the fraction of finished tiles after
+ *             every tile. It is called by one thread at a time, with increasing
+ *             values, and the last call reports exactly 1.
+ * @param      camera             The camera, holds the image data
+ * @param      world              The hittable objects
+ * @param      background         The background of the scene
+ * @param[in]  samples_per_pixel  The number of samples per pixel
+ * @param[in]  max_depth          The maximum recursion depth of the shader
+ * @param[in]  tile_size          The width and height of the tiles in pixels
+ * @param[in]  on_progress        Called with the fraction of finished tiles, may be empty
+ */
+void render_tiled(Camera &camera, HittableList &world, Background &background, int samples_per_pixel, int max_depth, int tile_size = 32,
+                  const std::function<void(double)> &on_progress = nullptr)
+{
+    int width = camera.image.get_width();
+    int height = camera.image.get_height();
+    int tiles_x = (width + tile_size - 1) / tile_size;
+    int tiles_y = (height + tile_size - 1) / tile_size;
+    int number_of_tiles = tiles_x * tiles_y;
+    int finished_tiles = 0;
+    ProgressBar bar(number_of_tiles);
+
+    #pragma omp parallel for schedule(dynamic)
+    for (int tile = 0; tile < number_of_tiles; ++tile)
+    {
+        int i0 = (tile % tiles_x) * tile_size;
+        int j0 = (tile / tiles_x) * tile_size;
+        int i1 = std::min(i0 + tile_size, width);
+        int j1 = std::min(j0 + tile_size, height);
+        for (int j = j0; j < j1; ++j)
+        {
+            for (int i = i0; i < i1; ++i)
+            {
+                Color color = render_pixel(camera, world, background, i, j, samples_per_pixel, max_depth);
+                camera.image.add_samples(i, j, color, samples_per_pixel);
+            }
+        }
+
+        // progress bar and callback, one thread at a time keeps the fractions in order
+        #pragma omp critical
+        {
+            bar.increment();
+            finished_tiles++;
+            if (on_progress)
+                on_progress(static_cast<double>(finished_tiles) / number_of_tiles);
+        }
+    }
+    if (number_of_tiles == 0 && on_progress)
+        on_progress(1.0);
+}
+
+/**
+ * @brief      Renders the distance from the camera to the first hit of each pixel.
+ *             One ray through the pixel center is traced per pixel. The distance is
+ *             normalized from [near, far] to [0, 1], rays that miss map to the far plane.
+ *             The depth image is written without gamma correction.
+ * @param[in]  camera  The camera
+ * @param      world   The hittable objects
+ * @param[in]  near    The distance mapped to 0
+ * @param[in]  far     The distance mapped to 1
+ * @return     The grayscale depth image.
+ */
+ImageData render_depth(const Camera &camera, HittableList &world, double near, double far)
+{
+    ImageData depth(camera.image.get_width(), camera.image.get_height());
+    depth.set_gamma(1.0);
+
+    #pragma omp parallel for schedule(dynamic)
+    for (int j = camera.image.get_height() - 1; j >= 0; --j)
+    {
+        for (int i = 0; i < camera.image.get_width(); ++i)
+        {
+            double u = (i + 0.5) / camera.image.get_width();
+            double v = (j + 0.5) / camera.image.get_height();
+            Ray ray = camera.get_ray(u, v);
+
+            depth.add_color(i, j, depth_shader(ray, world, near, far));
+        }
+    }
+    return depth;
+}
+
+/**
+ * @brief      Renders the scene into the image data of the camera together with a depth image.
+ * @param      camera             The camera, holds the image data
+ * @param      world              The hittable objects
+ * @param      background         The background of the scene
+ * @param[in]  samples_per_pixel  The number of samples per pixel
+ * @param[in]  max_depth          The maximum recursion depth of the shader
+ * @param[out] depth              The depth image, see render_depth
+ * @param[in]  near               The distance mapped to 0 in the depth image
+ * @param[in]  far                The distance mapped to 1 in the depth image
+ */
+void render_with_depth(Camera &camera, HittableList &world, Background &background, int samples_per_pixel, int max_depth,
+                       ImageData &depth, double near, double far)
+{
+    render(camera, world, background, samples_per_pixel, max_depth);
+    depth = render_depth(camera, world, near, far);
+}
+
+/**
+ * @brief      Renders the scene together with auxiliary images for denoisers.
+ *             The albedo and normal images use the same jittered samples per pixel
+ *             as the scene, so their edges are anti-aliased the same way.
+ *             Both are written without gamma correction.
+ * @param      camera             The camera, holds the image data
+ * @param      world              The hittable objects
+ * @param      background         The background of the scene
+ * @param[in]  samples_per_pixel  The number of samples per pixel
+ * @param[in]  max_depth          The maximum recursion depth of the shader
+ * @param[out] albedo             The base color at the first hit, the background if nothing is hit
+ * @param[out] normals            The normal at the first hit as 0.5 * (normal + 1), black if nothing is hit
+ */
+void render_with_aux(Camera &camera, HittableList &world, Background &background, int samples_per_pixel, int max_depth,
+                     ImageData &albedo, ImageData &normals)
+{
+    render(camera, world, background, samples_per_pixel, max_depth);
+
+    Camera aux_camera = camera;
+    aux_camera.image = ImageData(camera.image.get_width(), camera.image.get_height());
+    aux_camera.image.set_pixel_filter(camera.image.get_pixel_filter());
+    aux_camera.image.set_sample_strategy(camera.image.get_sample_strategy());
+    aux_camera.image.set_gamma(1.0);
+    ImageData empty_image = aux_camera.image;
+
+    render(aux_camera, world, background, samples_per_pixel, max_depth, ShadingMode::Albedo);
+    albedo = aux_camera.image;
+
+    aux_camera.image = empty_image;
+    render(aux_camera, world, background, samples_per_pixel, max_depth, ShadingMode::Normals);
+    normals = aux_camera.image;
+}
+
+/**
+ * @brief      Renders the scene into the image data of the camera and collects
+ *             statistics of the render, see RenderStats. The counters are shared
+ *             by the whole program, so only one render should collect statistics
+ *             at a time.
+ * @param      camera             The camera, holds the image data
+ * @param      world              The hittable objects
+ * @param      background         The background of the scene
+ * @param[in]  samples_per_pixel  The number of samples per pixel
+ * @param[in]  max_depth          The maximum recursion depth of the shader
+ * @param[in]  mode               What to compute for each sample
+ * @param[in]  roulette_min_depth The number of bounces before russian roulette starts, negative to disable it
+ * @param[in]  firefly_clamp      The largest channel of indirect light of a sample, infinity to disable it
+ * @param[in]  ao_samples         The number of rays per sample of the AmbientOcclusion mode
+ * @param[in]  ao_distance        The distance within which the rays of the AmbientOcclusion mode are occluded
+ * @return     The statistics of the render.
+ */
+RenderStats render_with_stats(Camera &camera, HittableList &world, Background &background, int samples_per_pixel, int max_depth,
+                              ShadingMode mode = ShadingMode::PathTrace, int roulette_min_depth = -1, double firefly_clamp = infinity,
+                              int ao_samples = 16, double ao_distance = 1.0)
+{
+    RenderCounters &counters = render_counters();
+    counters.reset();
+    RenderCounters::enabled = true;
+    auto start = std::chrono::steady_clock::now();
+
+    render(camera, world, background, samples_per_pixel, max_depth, mode, roulette_min_depth, firefly_clamp, ao_samples, ao_distance);
+
+    auto end = std::chrono::steady_clock::now();
+    RenderCounters::enabled = false;
+    RenderStats stats = counters.get_stats();
+    stats.seconds = std::chrono::duration<double>(end - start).count();
+    return stats;
+}
+
+/**
+ * @brief      Renders the scene in passes of one sample per pixel.
+ *             After every pass the callback receives the image data, which then
+ *             holds the average over all completed passes, and the number of
+ *             completed passes. Rendering stops early when the callback returns false.
+ *             The callback can write snapshots of the image data, rendering
+ *             continues on the same data afterwards.
+ * @param      camera      The camera, holds the image data
+ * @param      world       The hittable objects
+ * @param      background  The background of the scene
+ * @param[in]  passes      The maximum number of passes
+ * @param[in]  max_depth   The maximum recursion depth of the shader
+ * @param[in]  on_pass     Called after every pass, return false to stop rendering
+ * @return     The number of completed passes.
+ */
+int render_progressive(Camera &camera, HittableList &world, Background &background, int passes, int max_depth,
+                       const std::function<bool(const ImageData &, int)> &on_pass)
+{
+    for (int pass = 1; pass <= passes; ++pass)
+    {
+        #pragma omp parallel for schedule(dynamic)
+        for (int j = camera.image.get_height() - 1; j >= 0; --j)
+        {
+            for (int i = 0; i < camera.image.get_width(); ++i)
+            {
+                Color color = render_pixel(camera, world, background, i, j, 1, max_depth);
+                camera.image.add_samples(i, j, color, 1);
+            }
+        }
+
+        if (!on_pass(camera.image, pass))
+        {
+            return pass;
+        }
+    }
+    return passes;
+}
+
+#endif // RENDER_H
@@ -0,0 +1,76 @@
+/*
+render_stats.h
+==============
+Statistics of a render, for performance tuning.
+
+While statistics are enabled, the shaders count the rays they cast into the
+scene, render_pixel counts the primary rays from the camera, the BVH counts
+its node visits and the leaves and hittable lists count the intersection
+tests of their objects. The counters are atomic, so the threads of a
+parallel render can share them. When statistics are disabled every counter
+costs a single check of a flag.
+*/
+
+#ifndef RENDER_STATS_H
+#define RENDER_STATS_H
+
+#include <atomic>
+
+/**
+ * @brief      Statistics of a finished render.
+ */
+struct RenderStats
+{
+    long long rays = 0;             // rays cast into the scene
+    long long primary_rays = 0;     // rays from the camera
+    long long secondary_rays = 0;   // rays scattered at surfaces
+    long long bvh_node_visits = 0;  // BVH nodes whose bounding box was tested
+    long long primitive_tests = 0;  // intersection tests of objects that are not BVH nodes
+    double seconds = 0;             // wall-clock time of the render
+};
+
+/**
+ * @brief      Counters shared by all threads while rendering.
+ */
+class RenderCounters
+{
+public:
+    void reset()
+    {
+        rays = 0;
+        primary_rays = 0;
+        bvh_node_visits = 0;
+        primitive_tests = 0;
+    }
+    RenderStats get_stats() const
+    {
+        RenderStats stats;
+        stats.rays = rays;
+        stats.primary_rays = primary_rays;
+        stats.secondary_rays = stats.rays - stats.primary_rays;
+        stats.bvh_node_visits = bvh_node_visits;
+        stats.primitive_tests = primitive_tests;
+        return stats;
+    }
+
+    static void count(std::atomic<long long> &counter)
+    {
+        if (enabled.load(std::memory_order_relaxed))
+            counter.fetch_add(1, std::memory_order_relaxed);
+    }
+
+public:
+    static inline std::atomic<bool> enabled{false};
+    std::atomic<long long> rays{0};
+    std::atomic<long long> primary_rays{0};
+    std::atomic<long long> bvh_node_visits{0};
+    std::atomic<long long> primitive_tests{0};
+};
+
+inline RenderCounters &render_counters()
+{
+    static RenderCounters counters;
+    return counters;
+}
+
+#endif // RENDER_STATS_H
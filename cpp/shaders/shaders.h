@@ -1,3 +1,6 @@
+#ifndef SHADERS_H
+#define SHADERS_H
+
 #include <memory>
 #include <vector>
 #include <cmath>
@@ -8,50 +11,249 @@
 #include "hittable_list.h"
 #include "background.h"
 #include "pdf.h"
+#include "render_stats.h"
+
+/**
+ * Russian roulette for a path, based on the largest channel of its throughput
+ * @param throughput: product of all attenuations of the path so far
+ * @param survival_probability: probability that the path continues
+ * @return true if the path continues
+ */
+inline bool russian_roulette(const Color &throughput, double &survival_probability)
+{
+    survival_probability = fmin(1.0, fmax(throughput.x(), fmax(throughput.y(), throughput.z())));
+    return random_double() < survival_probability;
+}
+
+/**
+ * Scales a contribution down so that its largest channel is at most the threshold,
+ * which keeps its hue.
+ * @param contribution: radiance reaching the camera
+ * @param threshold: largest allowed channel
+ * @return the clamped contribution
+ */
+inline Color clamp_contribution(const Color &contribution, double threshold)
+{
+    double largest = fmax(contribution.x(), fmax(contribution.y(), contribution.z()));
+    if (largest <= threshold)
+        return contribution;
+    return contribution * (threshold / largest);
+}
 
 /**
  * Ray tracing shader
+ * Follows the path of the ray bounce by bounce in a loop. The radiance
+ * collected so far and the path weight, the product of all attenuations
+ * since ray_in, are updated at every bounce. The wavelength of ray_in is kept
+ * at every bounce.
  * @param r: ray to trace
  * @param world: hittable objects
- * @param lights: lights
  * @param background: background for the scene
- * @param depth: max depth for recursion 
+ * @param depth: max number of bounces
+ * @param roulette_depth: paths are terminated by russian roulette once depth is at most
+ *                        this value, 0 disables russian roulette
+ * @param throughput: product of all attenuations of the path up to ray_in
+ * @param firefly_clamp: largest channel of the light reaching ray_in over one or more
+ *                       bounces, light hit directly by ray_in is not clamped
  */   
-Color ray_tracing_shader(const Ray &ray_in, HittableList &world, Background &background, int depth)
+Color ray_tracing_shader(const Ray &ray_in, HittableList &world, Background &background, int depth,
+                         int roulette_depth = 0, const Color &throughput = Color(1, 1, 1), double firefly_clamp = infinity)
 {
-    
-    if (depth <= 0) {
-        // max depth reached
-        return Color(0, 0, 0);
-    }
+    Color radiance(0, 0, 0);
+    Color path_weight(1, 1, 1);
+    Ray ray = ray_in;
+    bool direct = true; // ray is still ray_in, its light is not clamped
 
-    HitRecord hit_rec;
-    if (!world.hit(ray_in, 0.001f, infinity, hit_rec)) {
-        // background
-        return background.get_color(ray_in);
-    }
+    for (; depth > 0; depth--, direct = false) {
+        HitRecord hit_rec;
+        RenderCounters::count(render_counters().rays);
+        if (!world.hit(ray, ray_epsilon, infinity, hit_rec)) {
+            // background
+            Color light = path_weight * background.get_color(ray);
+            return radiance + (direct ? light : clamp_contribution(light, firefly_clamp));
+        }
+        hit_rec.get_material()->apply_normal_map(hit_rec);
 
-    ScatterRecord scatter_record;
-    Color emitted = hit_rec.get_material()->emitted(hit_rec.u, hit_rec.v, hit_rec.get_hit_point());
-    if (!hit_rec.get_material()->scatter(ray_in, hit_rec, scatter_record)) {
-        // no scatter
-        return emitted;
-    }
+        ScatterRecord scatter_record;
+        Color emitted = hit_rec.get_material()->emitted(ray, hit_rec.u, hit_rec.v, hit_rec.get_hit_point());
+        if (!hit_rec.get_material()->scatter(ray, hit_rec, scatter_record)) {
+            // no scatter
+            Color light = path_weight * emitted;
+            return radiance + (direct ? light : clamp_contribution(light, firefly_clamp));
+        }
 
-    if (scatter_record.is_specular) {
-        // specular reflection or refraction
-        return scatter_record.attenuation * ray_tracing_shader(scatter_record.specular_ray, world, background, depth - 1);
+        if (scatter_record.is_specular) {
+            // specular reflection or refraction
+            Color weight = scatter_record.attenuation;
+            if (depth <= roulette_depth) {
+                double survival_probability;
+                if (!russian_roulette(throughput * path_weight * weight, survival_probability)) {
+                    return radiance;
+                }
+                weight /= survival_probability;
+            }
+            path_weight *= weight;
+            ray = scatter_record.specular_ray;
+            ray.wavelength = ray_in.wavelength;
+            continue;
+        }
+
+        // diffuse
+        Vec3 direction;
+        double pdf_val;
+        scatter_record.pdf->generate(direction, pdf_val);
+        Ray scattered = Ray(hit_rec.get_hit_point(), direction, ray.get_time());
+        Color weight = scatter_record.attenuation * hit_rec.get_material()->scattering_pdf(ray, hit_rec, scattered) / pdf_val;
+        Color light = path_weight * emitted;
+        radiance += direct ? light : clamp_contribution(light, firefly_clamp);
+        if (depth <= roulette_depth) {
+            double survival_probability;
+            if (!russian_roulette(throughput * path_weight * weight, survival_probability)) {
+                return radiance;
+            }
+            weight /= survival_probability;
+        }
+        path_weight *= weight;
+        ray = scattered;
+        ray.wavelength = ray_in.wavelength;
     }
 
-    // diffuse
-    Vec3 direction;
-    double pdf_val;
-    scatter_record.pdf->generate(direction, pdf_val);
-    Ray scattered = Ray(hit_rec.get_hit_point(), direction, ray_in.get_time());
-    return emitted + scatter_record.attenuation * hit_rec.get_material()->scattering_pdf(ray_in, hit_rec, scattered) * ray_tracing_shader(scattered, world, background, depth - 1) / pdf_val;
+    // max depth reached
+    return radiance;
+}
+
+/**
+ * Power heuristic for multiple importance sampling with one sample of each strategy
+ * @param pdf: density of the strategy that generated the direction
+ * @param other_pdf: density of the other strategy for the same direction
+ * @return weight of the sample
+ */
+inline double power_heuristic(double pdf, double other_pdf)
+{
+    double sum = pdf * pdf + other_pdf * other_pdf;
+    return sum > 0 ? pdf * pdf / sum : 0;
+}
 
+/**
+ * Radiance arriving along a ray, the emission of the first hit or the background
+ * @param r: ray to trace
+ * @param world: hittable objects
+ * @param background: background for the scene
+ * @return the emitted color
+ */
+inline Color emitted_along(const Ray &r, HittableList &world, Background &background)
+{
+    HitRecord rec;
+    RenderCounters::count(render_counters().rays);
+    if (!world.hit(r, ray_epsilon, infinity, rec))
+        return background.get_color(r);
+    return rec.get_material()->emitted(r, rec.u, rec.v, rec.get_hit_point());
 }
 
+/**
+ * Ray tracing shader with next event estimation
+ * Like ray_tracing_shader, but at every diffuse bounce one direction is sampled
+ * from the lights and one from the material. The light sample is traced with a
+ * shadow ray, the material sample continues the path. Light found by either of
+ * them is weighted with the power heuristic, so it is not counted twice.
+ * @param r: ray to trace
+ * @param world: hittable objects
+ * @param background: background for the scene
+ * @param depth: max number of bounces
+ * @param roulette_depth: paths are terminated by russian roulette once depth is at most
+ *                        this value, 0 disables russian roulette
+ * @param throughput: product of all attenuations of the path up to ray_in
+ * @param firefly_clamp: largest channel of the light reaching ray_in over one or more
+ *                       bounces, light hit directly by ray_in is not clamped
+ */
+Color next_event_shader(const Ray &ray_in, HittableList &world, Background &background, int depth,
+                        int roulette_depth = 0, const Color &throughput = Color(1, 1, 1), double firefly_clamp = infinity)
+{
+    Color radiance(0, 0, 0);
+    Color path_weight(1, 1, 1);
+    Ray ray = ray_in;
+    bool direct = true; // ray is still ray_in, its light is not clamped
+
+    // the light pdf of the last diffuse bounce and the density of ray under the material pdf,
+    // light_pdf is null if ray was not sampled from a material with lights
+    std::shared_ptr<PDF> light_pdf;
+    double material_pdf_value = 0;
+
+    for (; depth > 0; depth--, direct = false) {
+        HitRecord hit_rec;
+        RenderCounters::count(render_counters().rays);
+        bool hit = world.hit(ray, ray_epsilon, infinity, hit_rec);
+
+        // light found by the material sample, also found by the light sample of the last bounce
+        Color emitted = hit ? hit_rec.get_material()->emitted(ray, hit_rec.u, hit_rec.v, hit_rec.get_hit_point())
+                            : background.get_color(ray);
+        double weight_emitted = light_pdf ? power_heuristic(material_pdf_value, light_pdf->value(ray.direction)) : 1.0;
+        Color light = path_weight * emitted * weight_emitted;
+        radiance += direct ? light : clamp_contribution(light, firefly_clamp);
+        if (!hit) {
+            return radiance;
+        }
+        hit_rec.get_material()->apply_normal_map(hit_rec);
+
+        ScatterRecord scatter_record;
+        if (!hit_rec.get_material()->scatter(ray, hit_rec, scatter_record)) {
+            // no scatter
+            return radiance;
+        }
+
+        if (scatter_record.is_specular) {
+            // specular reflection or refraction
+            Color weight = scatter_record.attenuation;
+            if (depth <= roulette_depth) {
+                double survival_probability;
+                if (!russian_roulette(throughput * path_weight * weight, survival_probability)) {
+                    return radiance;
+                }
+                weight /= survival_probability;
+            }
+            path_weight *= weight;
+            light_pdf = nullptr;
+            ray = scatter_record.specular_ray;
+            ray.wavelength = ray_in.wavelength;
+            continue;
+        }
+
+        // diffuse, light sample, unless the material sample is not traced either
+        light_pdf = scatter_record.light_pdf;
+        if (light_pdf && depth > 1) {
+            Vec3 direction;
+            double pdf_val;
+            light_pdf->generate(direction, pdf_val);
+            Ray shadow_ray(hit_rec.get_hit_point(), direction, ray.get_time());
+            double scattering_pdf = hit_rec.get_material()->scattering_pdf(ray, hit_rec, shadow_ray);
+            if (pdf_val > 0 && scattering_pdf > 0) {
+                double weight_light = power_heuristic(pdf_val, scatter_record.material_pdf->value(direction));
+                Color light = path_weight * scatter_record.attenuation * scattering_pdf / pdf_val * weight_light
+                              * emitted_along(shadow_ray, world, background);
+                radiance += clamp_contribution(light, firefly_clamp);
+            }
+        }
+
+        // diffuse, material sample
+        Vec3 direction;
+        scatter_record.material_pdf->generate(direction, material_pdf_value);
+        Ray scattered = Ray(hit_rec.get_hit_point(), direction, ray.get_time());
+        Color weight = scatter_record.attenuation * hit_rec.get_material()->scattering_pdf(ray, hit_rec, scattered) / material_pdf_value;
+        if (depth <= roulette_depth) {
+            double survival_probability;
+            if (!russian_roulette(throughput * path_weight * weight, survival_probability)) {
+                return radiance;
+            }
+            weight /= survival_probability;
+        }
+        path_weight *= weight;
+        ray = scattered;
+        ray.wavelength = ray_in.wavelength;
+    }
+
+    // max depth reached
+    return radiance;
+}
 
 // Color scattering_shader(const Ray &ray_in, HittableList &world, std::shared_ptr<HittableList>& lights, Background &background, int depth)
 // {
@@ -69,14 +271,101 @@ Color ray_tracing_shader(const Ray &ray_in, HittableList &world, Background &bac
 // }
 
 
-// Color normal_shader(const Ray &r, HittableList &world, std::shared_ptr<HittableList>& lights, Background &background, int depth)
-// {
-//     HitRecord rec;
-//     if (!world.hit(r, 0.001f, 100.0f, rec))
-//     {
-//         return background.get_color(r);
-//     }
-    
-//     Vec3 normal = rec.is_front_face(r) ? rec.get_normal() : -rec.get_normal();
-//     return normal*0.5 + Vec3(0.5, 0.5, 0.5);
-// }
+/**
+ * Normal shader, maps the outward shading normal of the first hit to a color
+ * @param r: ray to trace
+ * @param world: hittable objects
+ * @return 0.5 * (normal + 1), black if nothing is hit
+ */
+Color normal_shader(const Ray &r, HittableList &world)
+{
+    HitRecord rec;
+    RenderCounters::count(render_counters().rays);
+    if (!world.hit(r, ray_epsilon, infinity, rec))
+    {
+        return Color(0, 0, 0);
+    }
+    rec.get_material()->apply_normal_map(rec);
+    return 0.5 * (rec.get_shading_normal() + Vec3(1, 1, 1));
+}
+
+/**
+ * Albedo shader, the base color of the material at the first hit
+ * @param r: ray to trace
+ * @param world: hittable objects
+ * @param background: background for the scene
+ * @return attenuation of the material, the emitted color for lights,
+ *         the background color if nothing is hit
+ */
+Color albedo_shader(const Ray &r, HittableList &world, Background &background)
+{
+    HitRecord rec;
+    RenderCounters::count(render_counters().rays);
+    if (!world.hit(r, ray_epsilon, infinity, rec))
+    {
+        return background.get_color(r);
+    }
+    ScatterRecord scatter_record;
+    if (rec.get_material()->scatter(r, rec, scatter_record))
+    {
+        return scatter_record.attenuation;
+    }
+    return rec.get_material()->emitted(r, rec.u, rec.v, rec.get_hit_point());
+}
+
+/**
+ * Ambient occlusion shader, the fraction of the hemisphere above the first hit
+ * that is free of geometry, with cosine weighted rays
+ * @param r: ray to trace
+ * @param world: hittable objects
+ * @param samples: number of rays over the hemisphere
+ * @param distance: rays hitting geometry closer than this are occluded
+ * @return gray value of the unoccluded fraction, white if nothing is hit
+ */
+Color ambient_occlusion_shader(const Ray &r, HittableList &world, int samples, double distance)
+{
+    HitRecord rec;
+    RenderCounters::count(render_counters().rays);
+    if (!world.hit(r, ray_epsilon, infinity, rec) || samples <= 0)
+    {
+        return Color(1, 1, 1);
+    }
+    // hemisphere on the side the ray came from
+    Vec3 normal = rec.is_front_face(r) ? rec.get_normal() : -rec.get_normal();
+    ONB uvw;
+    uvw.build_from_w(normal);
+    int unoccluded = 0;
+    for (int s = 0; s < samples; s++)
+    {
+        Ray occlusion_ray(rec.get_hit_point(), uvw.local(random_cosine_direction()), r.get_time());
+        HitRecord occluder;
+        RenderCounters::count(render_counters().rays);
+        if (!world.hit(occlusion_ray, ray_epsilon, distance, occluder))
+            unoccluded++;
+    }
+    double value = static_cast<double>(unoccluded) / samples;
+    return Color(value, value, value);
+}
+
+/**
+ * Depth shader, maps the distance to the first hit to a gray value
+ * @param r: ray to trace
+ * @param world: hittable objects
+ * @param near: distance mapped to black
+ * @param far: distance mapped to white, also used if nothing is hit
+ * @return gray value of the normalized distance
+ */
+Color depth_shader(const Ray &r, HittableList &world, double near, double far)
+{
+    double value = 1.0;
+    HitRecord rec;
+    RenderCounters::count(render_counters().rays);
+    if (world.hit(r, ray_epsilon, infinity, rec))
+    {
+        double distance = rec.get_t() * r.direction.length();
+        value = clamp((distance - near) / (far - near), 0.0, 1.0);
+    }
+    return Color(value, value, value);
+}
+
+#endif // SHADERS_H
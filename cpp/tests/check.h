@@ -0,0 +1,53 @@
+/*
+check.h
+=======
+Minimal checks for the test programs in this directory.
+
+Every test program is built and run on its own from the cpp directory,
+with the include paths of main.cpp: the cpp directory and each of its
+subdirectories, e.g.
+```sh
+g++ -std=c++17 -I. -Ivector -Iray -Ihittables ... tests/test_triangle.cpp -o test_triangle && ./test_triangle
+```
+The tests that load scenes need yaml-cpp and are linked with -lyaml-cpp,
+like main.cpp. A program returns 1 if any check failed.
+*/
+
+#ifndef CHECK_H
+#define CHECK_H
+
+#include <cmath>
+#include <iostream>
+
+inline int failed_checks = 0;
+
+/**
+ * @brief      Prints a failed check and counts it.
+ * @param[in]  ok         Whether the check passed
+ * @param[in]  condition  The checked condition as text
+ * @param[in]  file       The file of the check
+ * @param[in]  line       The line of the check
+ */
+inline void report_check(bool ok, const char *condition, const char *file, int line)
+{
+    if (ok)
+        return;
+    std::cerr << file << ":" << line << ": check failed: " << condition << std::endl;
+    failed_checks++;
+}
+
+#define CHECK(condition) report_check((condition), #condition, __FILE__, __LINE__)
+#define CHECK_NEAR(a, b, tolerance) report_check(std::fabs((a) - (b)) <= (tolerance), #a " == " #b, __FILE__, __LINE__)
+
+/**
+ * @brief      Result of a test program.
+ * @return     0 if all checks passed, 1 otherwise.
+ */
+inline int check_result()
+{
+    if (failed_checks == 0)
+        std::cerr << "all checks passed" << std::endl;
+    return failed_checks == 0 ? 0 : 1;
+}
+
+#endif // CHECK_H
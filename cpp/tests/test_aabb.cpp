@@ -0,0 +1,49 @@
+/*
+test_aabb.cpp
+=============
+Checks of the axis-aligned bounding box.
+*/
+
+#include "check.h"
+#include "ray.h"
+#include "aabb.h"
+
+int main()
+{
+    AABB box(Vec3(-1, -1, -1), Vec3(1, 1, 1));
+
+    // slab test: a ray through the box, a ray passing by and a ray from inside
+    CHECK(box.hit(Ray(Vec3(-5, 0.5, 0.5), Vec3(1, 0, 0), 0), 0.001, 100));
+    CHECK(box.hit(Ray(Vec3(5, 5, 5), Vec3(-1, -1, -1), 0), 0.001, 100));
+    CHECK(!box.hit(Ray(Vec3(-5, 1.5, 0), Vec3(1, 0, 0), 0), 0.001, 100));
+    CHECK(!box.hit(Ray(Vec3(-5, 0, 0), Vec3(-1, 0, 0), 0), 0.001, 100));
+    CHECK(box.hit(Ray(Vec3(0, 0, 0), Vec3(0, 0, 1), 0), 0.001, 100));
+
+    // the range of the ray limits the hit
+    CHECK(!box.hit(Ray(Vec3(-5, 0, 0), Vec3(1, 0, 0), 0), 0.001, 3));
+
+    // as a surface, a ray from +x hits the +x face with the normal (1, 0, 0),
+    // a ray from inside hits the face where it leaves
+    HitRecord rec;
+    Ray from_x(Vec3(5, 0.5, 0.5), Vec3(-1, 0, 0), 0);
+    CHECK(box.hit(from_x, 0.001, 100, rec, nullptr));
+    CHECK_NEAR(rec.get_t(), 4.0, 1e-12);
+    CHECK_NEAR((rec.get_normal() - Vec3(1, 0, 0)).length(), 0, 1e-12);
+    CHECK(rec.is_front_face(from_x));
+    Ray from_inside(Vec3(0, 0, 0), Vec3(0, -1, 0), 0);
+    CHECK(box.hit(from_inside, 0.001, 100, rec, nullptr));
+    CHECK_NEAR(rec.get_t(), 1.0, 1e-12);
+    CHECK_NEAR((rec.get_normal() - Vec3(0, -1, 0)).length(), 0, 1e-12);
+    CHECK(!box.hit(Ray(Vec3(-5, 1.5, 0), Vec3(1, 0, 0), 0), 0.001, 100, rec, nullptr));
+
+    // the centroid is the middle of the box, padding widens only a flat axis
+    AABB offset(Vec3(1, -2, 3), Vec3(3, 2, 4));
+    CHECK_NEAR((offset.centroid() - Vec3(2, 0, 3.5)).length(), 0, 1e-12);
+    AABB flat(Vec3(0, 1, 0), Vec3(2, 1, 3));
+    AABB padded = flat.pad(0.01);
+    CHECK((padded.get_min() - Vec3(0, 0.99, 0)).length() < 1e-12);
+    CHECK((padded.get_max() - Vec3(2, 1.01, 3)).length() < 1e-12);
+    CHECK((box.pad(0.01).get_max() - box.get_max()).length() == 0);
+
+    return check_result();
+}
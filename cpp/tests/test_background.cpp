@@ -0,0 +1,56 @@
+/*
+test_background.cpp
+===================
+Checks of the background colors of rays that miss the scene and of the
+light sampling of the environment map.
+*/
+
+#include <vector>
+
+#include "check.h"
+#include "background.h"
+#include "env_map.h"
+#include "bvh.cpp"
+
+int main()
+{
+    // the gradient blends from the first color straight down to the second
+    // color straight up, independent of the length of the direction
+    GradientBackground gradient(Color(1, 1, 1), Color(0.5, 0.7, 1.0));
+    Vec3 origin(0, 0, 0);
+    CHECK_NEAR((gradient.get_color(Ray(origin, Vec3(0, -3, 0), 0)) - Color(1, 1, 1)).length(), 0, 1e-12);
+    CHECK_NEAR((gradient.get_color(Ray(origin, Vec3(0, 2, 0), 0)) - Color(0.5, 0.7, 1.0)).length(), 0, 1e-12);
+    CHECK_NEAR((gradient.get_color(Ray(origin, Vec3(1, 0, 0), 0)) - Color(0.75, 0.85, 1.0)).length(), 0, 1e-12);
+
+    // a solid background has the same color in every direction
+    SolidBackground solid(Color(0.1, 0.2, 0.3));
+    CHECK_NEAR((solid.get_color(Ray(origin, Vec3(0, -1, 0), 0)) - Color(0.1, 0.2, 0.3)).length(), 0, 1e-12);
+    CHECK_NEAR((solid.get_color(Ray(origin, Vec3(1, 1, 0), 0)) - Color(0.1, 0.2, 0.3)).length(), 0, 1e-12);
+
+    // an environment map that is dim except for one bright pixel: -z is seen
+    // in the middle of the map, and the sampled directions concentrate on the
+    // bright pixel with the density returned by pdf_value
+    {
+        seed_random(67);
+        std::vector<Color> pixels(8 * 4, Color(0.001, 0.001, 0.001));
+        pixels[1 * 8 + 5] = Color(1000, 1000, 1000);
+        EnvMap map(8, 4, pixels);
+        pixels[2 * 8 + 4] = Color(1, 2, 3);
+        EnvMap marked(8, 4, pixels);
+        CHECK_NEAR((marked.get_color(Ray(origin, Vec3(0, 0, -1), 0)) - Color(1, 2, 3)).length(), 0, 1e-12);
+
+        int bright = 0;
+        for (int n = 0; n < 1000; n++)
+        {
+            double pdf;
+            Vec3 direction = map.sample_direction(pdf);
+            CHECK_NEAR(direction.length(), 1, 1e-9);
+            CHECK_NEAR(pdf, map.pdf_value(direction), 1e-9 * pdf);
+            if (map.get_color(Ray(origin, direction, 0)).x() == 1000)
+                bright++;
+        }
+        CHECK(bright > 990);
+    }
+
+    return check_result();
+}
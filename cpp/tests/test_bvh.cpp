@@ -0,0 +1,167 @@
+/*
+test_bvh.cpp
+============
+Checks of the BVH split methods on a scene of three tight clusters of small
+spheres between larger scattered spheres.
+*/
+
+#include <vector>
+
+#include "check.h"
+#include "sphere.h"
+#include "lambertian.h"
+#include "render_stats.h"
+#include "hittable_list.h"
+#include "bvh.cpp"
+
+/**
+ * @brief      Shoots rays into the BVH.
+ * @param[in]  bvh       The BVH of the spheres
+ * @param[in]  rays      The rays
+ * @param[out] hits      The parameter of the closest hit of each ray, -1 for a miss
+ * @return     The counted node visits and primitive intersection tests.
+ */
+RenderStats trace(const BVHNode &bvh, const std::vector<Ray> &rays, std::vector<double> &hits)
+{
+    render_counters().reset();
+    RenderCounters::enabled = true;
+    hits.clear();
+    for (const Ray &ray : rays)
+    {
+        HitRecord rec;
+        hits.push_back(bvh.hit(ray, ray_epsilon, infinity, rec) ? rec.get_t() : -1);
+    }
+    RenderCounters::enabled = false;
+    return render_counters().get_stats();
+}
+
+/**
+ * @brief      Counts the objects below a node.
+ * @param[in]  node  The node
+ * @return     The number of objects.
+ */
+int count_objects(const BVHNode &node)
+{
+    if (node.left == node.right)
+        return 1;
+    if (node.leaf)
+        return 2;
+    return count_objects(static_cast<const BVHNode &>(*node.left)) + count_objects(static_cast<const BVHNode &>(*node.right));
+}
+
+/**
+ * @brief      Checks that two trees have the same boxes. Below five objects the
+ *             split axis is random, so only the boxes of those nodes are compared.
+ * @param[in]  a     The first tree
+ * @param[in]  b     The second tree
+ * @return     Whether the boxes are the same.
+ */
+bool same_boxes(const BVHNode &a, const BVHNode &b)
+{
+    for (int axis = 0; axis < 3; axis++)
+    {
+        if (a.box.get_min()[axis] != b.box.get_min()[axis] || a.box.get_max()[axis] != b.box.get_max()[axis])
+            return false;
+    }
+    if (count_objects(a) <= 4 || count_objects(b) <= 4)
+        return count_objects(a) == count_objects(b);
+    return same_boxes(static_cast<const BVHNode &>(*a.left), static_cast<const BVHNode &>(*b.left)) &&
+           same_boxes(static_cast<const BVHNode &>(*a.right), static_cast<const BVHNode &>(*b.right));
+}
+
+int main()
+{
+    seed_random(21);
+    Lambertian gray(Color(0.5, 0.5, 0.5));
+
+    // three clusters with 300 small spheres each and 50 larger spheres in between
+    std::vector<std::shared_ptr<Hittable>> spheres;
+    for (int cluster = 0; cluster < 3; cluster++)
+    {
+        Vec3 cluster_center = Vec3::random(-50, 50);
+        for (int i = 0; i < 300; i++)
+            spheres.push_back(std::make_shared<Sphere>(cluster_center + Vec3::random(-1, 1), 0.05, &gray));
+    }
+    for (int i = 0; i < 50; i++)
+        spheres.push_back(std::make_shared<Sphere>(Vec3::random(-50, 50), 0.5, &gray));
+    std::vector<std::shared_ptr<Hittable>> median_spheres = spheres;
+    std::vector<std::shared_ptr<Hittable>> sah_spheres = spheres;
+    auto median = build_bvh(median_spheres, 0, 1, SplitMethod::Median);
+    auto sah = build_bvh(sah_spheres, 0, 1, SplitMethod::SurfaceAreaHeuristic);
+
+    // rays from random points of the scene in random directions
+    std::vector<Ray> rays;
+    for (int i = 0; i < 20000; i++)
+        rays.push_back(Ray(Vec3::random(-60, 60), random_unit_vector(), 0));
+
+    // both trees return the same hits, the SAH tree visits about half as many nodes;
+    // the primitive tests are about equal, since the children's boxes are tested first
+    std::vector<double> median_hits, sah_hits;
+    RenderStats median_stats = trace(*median, rays, median_hits);
+    RenderStats sah_stats = trace(*sah, rays, sah_hits);
+    for (size_t i = 0; i < rays.size(); i++)
+        CHECK_NEAR(median_hits[i], sah_hits[i], 1e-9);
+    std::cerr << "node visits: median " << median_stats.bvh_node_visits << ", SAH " << sah_stats.bvh_node_visits << std::endl;
+    std::cerr << "primitive tests: median " << median_stats.primitive_tests << ", SAH " << sah_stats.primitive_tests << std::endl;
+    CHECK(sah_stats.bvh_node_visits < 0.7 * median_stats.bvh_node_visits);
+
+    // a ray along a row of 64 spheres enters every box, but the children are
+    // tested front to back, so the spheres behind the first hit are skipped
+    std::vector<std::shared_ptr<Hittable>> row;
+    for (int i = 0; i < 64; i++)
+        row.push_back(std::make_shared<Sphere>(Vec3(0, 0, -3.0 * (i + 1)), 1.0, &gray));
+    auto row_bvh = build_bvh(row, 0, 1);
+    std::vector<double> row_hits;
+    RenderStats row_stats = trace(*row_bvh, {Ray(Vec3(0, 0, 0), Vec3(0, 0, -1), 0)}, row_hits);
+    CHECK_NEAR(row_hits[0], 2.0, 1e-9);
+    CHECK(row_stats.primitive_tests <= 2);
+    CHECK(row_stats.bvh_node_visits <= 2 * 7);
+
+    // a list with a BVH returns the same hits as the linear scan over its objects,
+    // until an object is added
+    HittableList list;
+    for (int i = 0; i < 500; i++)
+        list.add(std::make_shared<Sphere>(Vec3::random(-20, 20), random_double(0.1, 2), &gray));
+    std::vector<Ray> list_rays;
+    std::vector<HitRecord> linear;
+    for (int i = 0; i < 5000; i++)
+    {
+        list_rays.push_back(Ray(Vec3::random(-25, 25), random_unit_vector(), 0));
+        HitRecord rec;
+        list.hit(list_rays.back(), ray_epsilon, infinity, rec);
+        linear.push_back(rec);
+    }
+    int linear_hits = 0;
+    for (const HitRecord &rec : linear)
+        linear_hits += rec.get_hit() ? 1 : 0;
+    CHECK(linear_hits > 500);
+    CHECK(!list.has_bvh());
+    CHECK(list.build_bvh());
+    CHECK(list.has_bvh());
+    for (size_t i = 0; i < list_rays.size(); i++)
+    {
+        HitRecord rec;
+        CHECK(list.hit(list_rays[i], ray_epsilon, infinity, rec) == linear[i].get_hit());
+        if (linear[i].get_hit())
+        {
+            CHECK(rec.get_t() == linear[i].get_t());
+            CHECK((rec.get_normal() - linear[i].get_normal()).length() == 0);
+        }
+    }
+    list.add(std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &gray));
+    CHECK(!list.has_bvh());
+
+    // above bvh_parallel_threshold objects the tree is built in parallel tasks,
+    // with the same boxes as the serial build
+    std::vector<std::shared_ptr<Hittable>> many;
+    for (size_t i = 0; i < bvh_parallel_threshold + 2000; i++)
+        many.push_back(std::make_shared<Sphere>(Vec3::random(-100, 100), 0.2, &gray));
+    std::vector<std::shared_ptr<Hittable>> serial_objects = many;
+    std::vector<std::shared_ptr<Hittable>> parallel_objects = many;
+    BVHNode serial(serial_objects, 0, serial_objects.size(), 0, 1, SplitMethod::SurfaceAreaHeuristic);
+    auto parallel = build_bvh(parallel_objects, 0, 1, SplitMethod::SurfaceAreaHeuristic);
+    CHECK(count_objects(*parallel) == static_cast<int>(many.size()));
+    CHECK(same_boxes(serial, *parallel));
+
+    return check_result();
+}
@@ -0,0 +1,158 @@
+/*
+test_camera.cpp
+===============
+Checks of the rays generated by the camera.
+*/
+
+#include <cmath>
+
+#include "check.h"
+#include "camera.h"
+
+/**
+ * @brief      Distance between two vectors.
+ * @param[in]  a     The first vector
+ * @param[in]  b     The second vector
+ * @return     The distance
+ */
+double distance(const Vec3 &a, const Vec3 &b)
+{
+    return (a - b).length();
+}
+
+/**
+ * @brief      Checks if a point of the xy-plane lies inside the triangle of a
+ *             three-blade aperture, with the corners (0, 1), (-sqrt(3)/2, -1/2)
+ *             and (sqrt(3)/2, -1/2).
+ * @param[in]  x     The x coordinate
+ * @param[in]  y     The y coordinate
+ * @return     True if the point is inside, up to rounding errors.
+ */
+bool inside_aperture_triangle(double x, double y)
+{
+    const double eps = 1e-9;
+    double s = std::sqrt(3.0);
+    // the edges are y = -1/2 and y = 1 -+ sqrt(3) x
+    return y >= -0.5 - eps && y <= 1 - s * x + eps && y <= 1 + s * x + eps;
+}
+
+int main()
+{
+    // defocus blur: without aperture every ray is the pinhole ray, with an aperture
+    // the origins spread over the lens and the rays still meet on the focal plane
+    {
+        Vec3 look_from(0, 0, 5);
+        Camera pinhole(40, 1.5, 0.0, 5.0, look_from, Vec3(0, 0, 0));
+        Vec3 target = pinhole.lower_left_corner + 0.3 * pinhole.horizontal + 0.6 * pinhole.vertical;
+        for (int i = 0; i < 10; i++)
+        {
+            Ray r = pinhole.get_ray(0.3, 0.6);
+            CHECK_NEAR(distance(r.origin, look_from), 0, 1e-12);
+            CHECK_NEAR(distance(r.direction, target - look_from), 0, 1e-12);
+        }
+
+        Camera lens(40, 1.5, 2.0, 5.0, look_from, Vec3(0, 0, 0));
+        Ray first = lens.get_ray(0.3, 0.6);
+        bool origin_varies = false;
+        for (int i = 0; i < 10; i++)
+        {
+            Ray r = lens.get_ray(0.3, 0.6);
+            origin_varies = origin_varies || distance(r.origin, first.origin) > 1e-6;
+            CHECK(distance(r.origin, look_from) <= 1.0);
+            CHECK_NEAR(distance(r.origin + r.direction, target), 0, 1e-9);
+        }
+        CHECK(origin_varies);
+    }
+
+    // orthographic rays are parallel and start on the image plane through look_from
+    {
+        Camera camera(40, 2.0, 0.0, 5.0, Vec3(0, 0, 5), Vec3(0, 0, 0));
+        camera.set_orthographic(4.0);
+        Ray a = camera.get_ray(0.1, 0.2);
+        Ray b = camera.get_ray(0.9, 0.7);
+        CHECK_NEAR(distance(a.direction, b.direction), 0, 1e-12);
+        CHECK_NEAR(distance(unit_vector(a.direction), Vec3(0, 0, -1)), 0, 1e-12);
+        CHECK_NEAR(distance(a.origin, Vec3(-1.6, -0.6, 5)), 0, 1e-12);
+        CHECK_NEAR(distance(b.origin, Vec3(1.6, 0.4, 5)), 0, 1e-12);
+    }
+
+    // with three blades the lens samples lie inside a triangle of the lens radius
+    {
+        Vec3 look_from(0, 0, 5);
+        Camera camera(40, 1.5, 2.0, 5.0, look_from, Vec3(0, 0, 0));
+        camera.set_bokeh_blades(3);
+        for (int i = 0; i < 10000; i++)
+        {
+            Vec3 offset = (camera.get_ray(0.5, 0.5).origin - look_from) / camera.lens_radius;
+            double x = dot(offset, camera.u);
+            double y = dot(offset, camera.v);
+            CHECK(inside_aperture_triangle(x, y));
+        }
+        // the first corner points up
+        CHECK_NEAR(distance(polygon_sample(3, 0.0, 1.0), Vec3(0, 1, 0)), 0, 1e-12);
+    }
+
+    // without chromatic aberration the three channels get the same ray, with it the
+    // channels are focused at different distances
+    {
+        Camera camera(40, 1.5, 2.0, 5.0, Vec3(0, 0, 5), Vec3(0, 0, 0));
+        Ray rays[3];
+        for (int channel = 0; channel < 3; channel++)
+        {
+            seed_random(68);
+            rays[channel] = camera.get_ray(0.3, 0.6, channel);
+        }
+        seed_random(68);
+        Ray all = camera.get_ray(0.3, 0.6);
+        for (int channel = 0; channel < 3; channel++)
+        {
+            CHECK(distance(rays[channel].origin, all.origin) == 0);
+            CHECK(distance(rays[channel].direction, all.direction) == 0);
+        }
+
+        camera.set_chromatic_aberration(0.05);
+        seed_random(68);
+        Ray red = camera.get_ray(0.3, 0.6, 0);
+        seed_random(68);
+        Ray blue = camera.get_ray(0.3, 0.6, 2);
+        CHECK(distance(red.origin, blue.origin) == 0);
+        CHECK(distance(unit_vector(red.direction), unit_vector(blue.direction)) > 1e-6);
+    }
+
+    // the center of a panorama looks forward, a quarter to the side looks right,
+    // the left and right edges wrap around behind the camera and the top and
+    // bottom edges look straight up and down
+    {
+        Vec3 look_from(1, 2, 3);
+        Camera camera(40, 2.0, 0.0, 5.0, look_from, Vec3(1, 2, 0));
+        camera.set_projection(Projection::Panoramic);
+        Ray center = camera.get_ray(0.5, 0.5);
+        CHECK_NEAR(distance(center.origin, look_from), 0, 1e-12);
+        CHECK_NEAR(distance(unit_vector(center.direction), Vec3(0, 0, -1)), 0, 1e-12);
+        CHECK_NEAR(distance(unit_vector(camera.get_ray(0.75, 0.5).direction), Vec3(1, 0, 0)), 0, 1e-12);
+        Vec3 left = camera.get_ray(0.0, 0.5).direction;
+        Vec3 right = camera.get_ray(1.0, 0.5).direction;
+        CHECK_NEAR(distance(unit_vector(left), Vec3(0, 0, 1)), 0, 1e-12);
+        CHECK_NEAR(distance(unit_vector(right), Vec3(0, 0, 1)), 0, 1e-12);
+        CHECK_NEAR(distance(unit_vector(camera.get_ray(0.3, 1.0).direction), Vec3(0, 1, 0)), 0, 1e-12);
+        CHECK_NEAR(distance(unit_vector(camera.get_ray(0.3, 0.0).direction), Vec3(0, -1, 0)), 0, 1e-12);
+    }
+
+    // looking straight down with the up direction (0, 0, 1) gives an orthonormal
+    // basis with the image upright along z
+    {
+        Camera camera(40, 1.5, 0.0, 5.0, Vec3(0, 5, 0), Vec3(0, 0, 0));
+        camera.set_vup(Vec3(0, 0, 1));
+        camera.set_up(40, 1.5, 0.0, 5.0, Vec3(0, 5, 0), Vec3(0, 0, 0));
+        CHECK_NEAR(distance(camera.w, Vec3(0, 1, 0)), 0, 1e-12);
+        CHECK_NEAR(distance(camera.v, Vec3(0, 0, 1)), 0, 1e-12);
+        CHECK_NEAR(camera.u.length(), 1, 1e-12);
+        CHECK_NEAR(dot(camera.u, camera.v), 0, 1e-12);
+        CHECK_NEAR(dot(camera.u, camera.w), 0, 1e-12);
+        CHECK_NEAR(distance(cross(camera.u, camera.v), camera.w), 0, 1e-12);
+        Ray center = camera.get_ray(0.5, 0.5);
+        CHECK_NEAR(distance(unit_vector(center.direction), Vec3(0, -1, 0)), 0, 1e-12);
+    }
+
+    return check_result();
+}
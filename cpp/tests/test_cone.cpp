@@ -0,0 +1,47 @@
+/*
+test_cone.cpp
+=============
+Checks of the hits on the side and the base of the cone.
+*/
+
+#include <cmath>
+
+#include "check.h"
+#include "cone.h"
+#include "bvh.cpp"
+
+int main()
+{
+    // cone with the apex at (0, 2, 0) opening downwards by 45 degrees, with the
+    // base of radius 2 at y = 0
+    Cone cone(Vec3(0, 2, 0), Vec3(0, -1, 0), 45, 2.0, nullptr);
+    HitRecord rec;
+
+    // a ray hits the slant where the radius is 1, with the normal perpendicular
+    // to the slant instead of radial
+    Ray side(Vec3(5, 1, 0), Vec3(-1, 0, 0), 0);
+    CHECK(cone.hit(side, 0.001, 100, rec));
+    CHECK_NEAR(rec.get_t(), 4.0, 1e-9);
+    CHECK_NEAR((rec.get_normal() - Vec3(1, 1, 0) / std::sqrt(2)).length(), 0, 1e-9);
+    CHECK(rec.is_front_face(side));
+
+    // rays above the apex miss, also where the mirrored half of the double
+    // cone would be
+    CHECK(!cone.hit(Ray(Vec3(5, 2.5, 0), Vec3(-1, 0, 0), 0), 0.001, 100, rec));
+    CHECK(!cone.hit(Ray(Vec3(5, 3, 0), Vec3(-1, 0, 0), 0), 0.001, 100, rec));
+
+    // a ray from below hits the base with the normal along the axis
+    Ray up(Vec3(0.5, -5, 0), Vec3(0, 1, 0), 0);
+    CHECK(cone.hit(up, 0.001, 100, rec));
+    CHECK_NEAR(rec.get_t(), 5.0, 1e-9);
+    CHECK_NEAR((rec.get_normal() - Vec3(0, -1, 0)).length(), 0, 1e-9);
+
+    // the box encloses the base circle and the apex
+    AABB box;
+    CHECK(cone.bounding_box(0, 1, box));
+    CHECK(box.get_min().x() <= -2 && box.get_min().z() <= -2 && box.get_min().y() <= 0);
+    CHECK(box.get_max().x() >= 2 && box.get_max().z() >= 2 && box.get_max().y() >= 2);
+    CHECK(box.get_max().y() < 2.01 && box.get_min().y() > -0.01);
+
+    return check_result();
+}
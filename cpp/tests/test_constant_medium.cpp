@@ -0,0 +1,41 @@
+/*
+test_constant_medium.cpp
+========================
+Checks of the scattering inside a volume of constant density.
+*/
+
+#include "check.h"
+#include "ray_tracer.h"
+#include "sphere.h"
+#include "constant_medium.h"
+#include "bvh.cpp"
+
+int main()
+{
+    seed_random(20);
+    Isotropic boundary_material(Color(1, 1, 1));
+    auto boundary = std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &boundary_material);
+    Ray ray(Vec3(0, 0, 5), Vec3(0, 0, -1), 0);
+    HitRecord rec;
+
+    // a thin fog lets the ray pass through the whole volume
+    ConstantMedium thin(boundary, 1e-9, Color(0.5, 0.5, 0.5));
+    for (int n = 0; n < 100; n++)
+        CHECK(!thin.hit(ray, ray_epsilon, infinity, rec));
+
+    // a dense smoke scatters the ray just behind the entry point, with the
+    // isotropic material owned by the medium
+    ConstantMedium dense(boundary, 1e9, Color(0.5, 0.5, 0.5));
+    CHECK(dense.owned_phase_function != nullptr);
+    for (int n = 0; n < 100; n++)
+    {
+        CHECK(dense.hit(ray, ray_epsilon, infinity, rec));
+        CHECK_NEAR(rec.get_t(), 4.0, 1e-6);
+        CHECK(rec.get_material() == dense.owned_phase_function.get());
+    }
+
+    // a ray that ends before the volume does not scatter
+    CHECK(!dense.hit(ray, ray_epsilon, 3.9, rec));
+
+    return check_result();
+}
@@ -0,0 +1,58 @@
+/*
+test_csg.cpp
+============
+Checks of the boolean combinations of hittables.
+*/
+
+#include <cmath>
+
+#include "check.h"
+#include "csg.h"
+#include "sphere.h"
+#include "lambertian.h"
+#include "bvh.cpp"
+
+int main()
+{
+    // a unit sphere with a bite of radius 0.6 taken out of its top at (0, 0, 1)
+    Lambertian apple(Color(0.8, 0.1, 0.1));
+    Lambertian bite_material(Color(0.9, 0.9, 0.7));
+    auto body = std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &apple);
+    auto bite = std::make_shared<Sphere>(Vec3(0, 0, 1), 0.6, &bite_material);
+    Csg bitten(body, bite, CsgOperation::Difference);
+    HitRecord rec;
+
+    // a ray into the bite passes the removed cap and hits the bottom of the bite,
+    // with the material of the bite and the normal facing out of the body
+    {
+        Ray down(Vec3(0, 0, 5), Vec3(0, 0, -1), 0);
+        CHECK(bitten.hit(down, 0.001, 100, rec));
+        CHECK_NEAR(rec.get_t(), 4.6, 1e-9);
+        CHECK_NEAR((rec.get_normal() - Vec3(0, 0, 1)).length(), 0, 1e-9);
+        CHECK(rec.get_material() == &bite_material);
+        CHECK(rec.is_front_face(down));
+    }
+
+    // beside the bite the body is hit as before
+    {
+        Ray down(Vec3(0.9, 0, 5), Vec3(0, 0, -1), 0);
+        CHECK(bitten.hit(down, 0.001, 100, rec));
+        CHECK_NEAR(rec.get_hit_point().z(), std::sqrt(1 - 0.81), 1e-9);
+        CHECK(rec.get_material() == &apple);
+    }
+
+    // the part of the bite outside of the body is not there
+    {
+        CHECK(!bitten.hit(Ray(Vec3(-5, 0, 1.3), Vec3(1, 0, 0), 0), 0.001, 100, rec));
+        CHECK(!bitten.hit(Ray(Vec3(0, 0, 5), Vec3(0, 0, -1), 0), 0.001, 4.5, rec));
+    }
+
+    // the bite does not enlarge the bounding box of the body
+    {
+        AABB box;
+        CHECK(bitten.bounding_box(0, 1, box));
+        CHECK_NEAR(box.get_max().z(), 1, 1e-3);
+    }
+
+    return check_result();
+}
@@ -0,0 +1,50 @@
+/*
+test_cylinder.cpp
+=================
+Checks of the hits on the side and the caps of the cylinder.
+*/
+
+#include "check.h"
+#include "cylinder.h"
+#include "bvh.cpp"
+
+int main()
+{
+    // cylinder of radius 1 from y = 0 to y = 2 around the y axis
+    Cylinder capped(Vec3(0, 0, 0), 2.0, 1.0, nullptr);
+    Cylinder open(Vec3(0, 0, 0), 2.0, 1.0, nullptr, false);
+    HitRecord rec;
+
+    // a ray from the side hits with a radial normal
+    Ray side(Vec3(5, 1, 0), Vec3(-1, 0, 0), 0);
+    CHECK(capped.hit(side, 0.001, 100, rec));
+    CHECK_NEAR(rec.get_t(), 4.0, 1e-12);
+    CHECK_NEAR((rec.get_normal() - Vec3(1, 0, 0)).length(), 0, 1e-12);
+    CHECK(rec.is_front_face(side));
+
+    // a ray from above hits the top cap with an axial normal, the open
+    // cylinder lets it pass to the inside of its side
+    Ray down(Vec3(0.5, 5, 0), Vec3(0, -1, 0), 0);
+    CHECK(capped.hit(down, 0.001, 100, rec));
+    CHECK_NEAR(rec.get_t(), 3.0, 1e-12);
+    CHECK_NEAR((rec.get_normal() - Vec3(0, 1, 0)).length(), 0, 1e-12);
+    CHECK(!open.hit(down, 0.001, 100, rec));
+    Ray slanted(Vec3(0, 3, 0), Vec3(1, -2, 0), 0);
+    CHECK(capped.hit(slanted, 0.001, 100, rec));
+    CHECK_NEAR((rec.get_hit_point() - Vec3(0.5, 2, 0)).length(), 0, 1e-12);
+    CHECK(open.hit(slanted, 0.001, 100, rec));
+    CHECK_NEAR((rec.get_hit_point() - Vec3(1, 1, 0)).length(), 0, 1e-12);
+    CHECK(!rec.is_front_face(slanted));
+
+    // a ray above the top and a ray beside the cylinder miss
+    CHECK(!capped.hit(Ray(Vec3(5, 3, 0), Vec3(-1, 0, 0), 0), 0.001, 100, rec));
+    CHECK(!capped.hit(Ray(Vec3(5, 1, 2), Vec3(-1, 0, 0), 0), 0.001, 100, rec));
+
+    // the box encloses the cylinder
+    AABB box;
+    CHECK(capped.bounding_box(0, 1, box));
+    CHECK_NEAR((box.get_min() - Vec3(-1, 0, -1)).length(), 0, 1e-3);
+    CHECK_NEAR((box.get_max() - Vec3(1, 2, 1)).length(), 0, 1e-3);
+
+    return check_result();
+}
@@ -0,0 +1,44 @@
+/*
+test_disk.cpp
+=============
+Checks of the disk hit and its bounding box.
+*/
+
+#include "check.h"
+#include "disk.h"
+#include "bvh.cpp"
+
+int main()
+{
+    // disk of radius 1 around (0, 0, 2), facing +z
+    Disk disk(Vec3(0, 0, 2), Vec3(0, 0, 3), 1.0, nullptr);
+    HitRecord rec;
+
+    // a ray inside the radius hits the plane with the unit normal
+    Ray down(Vec3(0.5, 0.5, 5), Vec3(0, 0, -1), 0);
+    CHECK(disk.hit(down, 0.001, 100, rec));
+    CHECK_NEAR(rec.get_t(), 3.0, 1e-12);
+    CHECK_NEAR((rec.get_hit_point() - Vec3(0.5, 0.5, 2)).length(), 0, 1e-12);
+    CHECK_NEAR((rec.get_normal() - Vec3(0, 0, 1)).length(), 0, 1e-12);
+    CHECK(rec.is_front_face(down));
+
+    // from below the ray hits the back face
+    Ray up(Vec3(0, 0, -5), Vec3(0, 0, 1), 0);
+    CHECK(disk.hit(up, 0.001, 100, rec));
+    CHECK(!rec.is_front_face(up));
+
+    // misses outside the radius, parallel to the plane and outside the range
+    CHECK(!disk.hit(Ray(Vec3(0.8, 0.8, 5), Vec3(0, 0, -1), 0), 0.001, 100, rec));
+    CHECK(!disk.hit(Ray(Vec3(-5, 0, 2), Vec3(1, 0, 0), 0), 0.001, 100, rec));
+    CHECK(!disk.hit(down, 0.001, 2.5, rec));
+
+    // the box encloses the disk and is padded along the normal
+    AABB box;
+    CHECK(disk.bounding_box(0, 1, box));
+    CHECK_NEAR(box.get_min().x(), -1, 1e-3);
+    CHECK_NEAR(box.get_max().y(), 1, 1e-3);
+    CHECK(box.get_min().z() < 2 && box.get_max().z() > 2);
+    CHECK(box.get_max().z() - box.get_min().z() < 0.01);
+
+    return check_result();
+}
@@ -0,0 +1,340 @@
+/*
+test_image_data.cpp
+===================
+Checks of the pixel storage and the output of ImageData.
+*/
+
+#include <cmath>
+#include <cstdio>
+#include <fstream>
+#include <sstream>
+#include <vector>
+
+#include "check.h"
+#include "image_data.h"
+
+/**
+ * @brief      Writes an image as ASCII ppm and reads the pixels back.
+ * @param      image  The image
+ * @return     The 8 bit rgb values of the pixels, indexed by row j and column i.
+ */
+std::vector<std::vector<Color>> read_back_ppm(ImageData &image)
+{
+    std::stringstream ppm;
+    image.write_ppm(ppm);
+    std::string magic;
+    int width, height, max_value;
+    ppm >> magic >> width >> height >> max_value;
+    std::vector<std::vector<Color>> pixels(height, std::vector<Color>(width));
+    // the rows are written from the top of the image, j = height - 1, down
+    for (int j = height - 1; j >= 0; --j)
+    {
+        for (int i = 0; i < width; ++i)
+        {
+            int r, g, b;
+            ppm >> r >> g >> b;
+            pixels[j][i] = Color(r, g, b);
+        }
+    }
+    return pixels;
+}
+
+/**
+ * @brief      Reads a whole file.
+ * @param[in]  filename  The name of the file
+ * @return     The content of the file.
+ */
+std::string read_file(const char *filename)
+{
+    std::ifstream file(filename, std::ios::binary);
+    std::stringstream content;
+    content << file.rdbuf();
+    return content.str();
+}
+
+/**
+ * @brief      Decodes a Radiance RGBE pixel.
+ * @param[in]  rgbe  The mantissas of the three channels and the shared exponent
+ * @return     The color.
+ */
+Color decode_rgbe(const unsigned char rgbe[4])
+{
+    if (rgbe[3] == 0)
+        return Color(0, 0, 0);
+    double scale = std::ldexp(1.0, rgbe[3] - 128 - 8);
+    return Color((rgbe[0] + 0.5) * scale, (rgbe[1] + 0.5) * scale, (rgbe[2] + 0.5) * scale);
+}
+
+/**
+ * @brief      Roughness of an image, the sum of the squared differences of the
+ *             red channel between horizontal and vertical neighbors.
+ * @param[in]  image  The image
+ * @return     The roughness
+ */
+double roughness(const ImageData &image)
+{
+    double sum = 0;
+    for (int j = 0; j < image.get_height(); j++)
+    {
+        for (int i = 0; i < image.get_width(); i++)
+        {
+            double r = image.get_radiance(i, j).r();
+            if (i + 1 < image.get_width())
+                sum += std::pow(image.get_radiance(i + 1, j).r() - r, 2);
+            if (j + 1 < image.get_height())
+                sum += std::pow(image.get_radiance(i, j + 1).r() - r, 2);
+        }
+    }
+    return sum;
+}
+
+int main()
+{
+    // a pixel of a 3 x 2 image is read back at the same place, its neighbors stay black
+    {
+        ImageData image(3, 2);
+        for (int j = 0; j < 2; j++)
+            for (int i = 0; i < 3; i++)
+                image.add_color(i, j, (i == 2 && j == 1) ? Color(1, 0.0, 0.0) : Color(0, 0, 0));
+        auto pixels = read_back_ppm(image);
+        CHECK(pixels.size() == 2 && pixels[0].size() == 3);
+        for (int j = 0; j < 2; j++)
+        {
+            for (int i = 0; i < 3; i++)
+            {
+                Color expected = (i == 2 && j == 1) ? Color(255, 0, 0) : Color(0, 0, 0);
+                CHECK_NEAR((pixels[j][i] - expected).length(), 0, 1e-12);
+            }
+        }
+    }
+
+    // the binary ppm has the same header and pixel order as the ASCII ppm
+    {
+        ImageData image(3, 2);
+        for (int j = 0; j < 2; j++)
+            for (int i = 0; i < 3; i++)
+                image.add_color(i, j, Color(0.1 * i, 0.2 * j, 0.5));
+        auto pixels = read_back_ppm(image);
+        std::stringstream p6;
+        image.write_ppm_binary(p6);
+        std::string bytes = p6.str();
+        std::string header = "P6\n3 2\n255\n";
+        CHECK(bytes.size() == header.size() + 3 * 3 * 2);
+        CHECK(bytes.compare(0, header.size(), header) == 0);
+        size_t k = header.size();
+        for (int j = 1; j >= 0; --j)
+        {
+            for (int i = 0; i < 3; i++)
+            {
+                CHECK((unsigned char)bytes[k++] == pixels[j][i].r());
+                CHECK((unsigned char)bytes[k++] == pixels[j][i].g());
+                CHECK((unsigned char)bytes[k++] == pixels[j][i].b());
+            }
+        }
+    }
+
+    // the png starts with the signature and a header chunk with the size of the image
+    {
+        ImageData image(3, 2);
+        for (int j = 0; j < 2; j++)
+            for (int i = 0; i < 3; i++)
+                image.add_color(i, j, Color(0.5, 0.5, 0.5));
+        std::stringstream png;
+        image.write_png(png);
+        std::string bytes = png.str();
+        CHECK(bytes.compare(0, 8, "\x89PNG\r\n\x1a\n") == 0);
+        CHECK(bytes.compare(12, 4, "IHDR") == 0);
+        CHECK((unsigned char)bytes[19] == 3 && (unsigned char)bytes[23] == 2);
+        CHECK(bytes.compare(bytes.size() - 8, 4, "IEND") == 0);
+    }
+
+    // a linear mid-gray of 0.25 is written as about 128 with gamma 2, unchanged with gamma 1
+    {
+        ImageData image(1, 1);
+        image.add_color(0, 0, Color(0.25, 0.25, 0.25));
+        image.add_color(0, 0, Color(0.25, 0.25, 0.25));
+        image.set_gamma(2.0);
+        CHECK_NEAR(read_back_ppm(image)[0][0].r(), 128, 1);
+        image.set_gamma(1.0);
+        CHECK_NEAR(read_back_ppm(image)[0][0].r(), 64, 1);
+    }
+
+    // a bright value of 10 saturates when clamped, Reinhard maps it to 10 / 11
+    {
+        ImageData image(1, 1);
+        image.add_color(0, 0, Color(10, 10, 10));
+        image.set_gamma(1.0);
+        CHECK(read_back_ppm(image)[0][0].r() == 255);
+        image.set_tone_map(ToneMap::Reinhard);
+        CHECK_NEAR(read_back_ppm(image)[0][0].r(), 255 * 10.0 / 11, 1);
+    }
+
+    // black and white are 0 and 255 in every color space, sRGB is darker than
+    // gamma 2.2 in the shadows and linear near black
+    {
+        for (ColorSpace space : {ColorSpace::Gamma, ColorSpace::Srgb, ColorSpace::Linear})
+        {
+            ImageData image(2, 1);
+            image.add_color(0, 0, Color(0, 0, 0));
+            image.add_color(1, 0, Color(1, 1, 1));
+            image.set_gamma(2.2);
+            image.set_color_space(space);
+            auto pixels = read_back_ppm(image);
+            CHECK(pixels[0][0].r() == 0);
+            CHECK(pixels[0][1].r() == 255);
+        }
+
+        ImageData image(2, 1);
+        image.add_color(0, 0, Color(0.01, 0.01, 0.01));
+        image.add_color(1, 0, Color(0.002, 0.002, 0.002));
+        image.set_gamma(2.2);
+        auto gamma = read_back_ppm(image);
+        image.set_color_space(ColorSpace::Srgb);
+        auto srgb = read_back_ppm(image);
+        CHECK_NEAR(gamma[0][0].r(), 255 * std::pow(0.01, 1 / 2.2), 1);
+        CHECK_NEAR(srgb[0][0].r(), 255 * (1.055 * std::pow(0.01, 1 / 2.4) - 0.055), 1);
+        CHECK(srgb[0][0].r() < gamma[0][0].r() - 3);
+        CHECK_NEAR(srgb[0][1].r(), 255 * 12.92 * 0.002, 1);
+    }
+
+    // the box filter jitters the samples uniformly within the pixel, as before the
+    // pixel filters, the tent filter spreads them over the neighboring pixels
+    {
+        ImageData image(4, 2);
+        for (int n = 0; n < 100; n++)
+        {
+            double u, v;
+            seed_random(n);
+            image.get_uv(1, 1, 0, 1, u, v);
+            seed_random(n);
+            double xi_u = random_double();
+            double xi_v = random_double();
+            CHECK_NEAR(u, (1 + 0.5) / 4 + (xi_u - 0.5) / 4, 1e-15);
+            CHECK_NEAR(v, (1 + 0.5) / 2 + (xi_v - 0.5) / 2, 1e-15);
+        }
+        image.set_pixel_filter(PixelFilter::Tent);
+        bool outside = false;
+        for (int n = 0; n < 1000; n++)
+        {
+            double u, v;
+            image.get_uv(1, 1, 0, 1, u, v);
+            CHECK(u >= 0.5 / 4 && u <= 2.5 / 4);
+            outside = outside || u < 1.0 / 4 || u > 2.0 / 4;
+        }
+        CHECK(outside);
+    }
+
+    // with 9 stratified samples every cell of a 3 x 3 grid in the pixel gets one sample
+    {
+        ImageData image(4, 2);
+        image.set_sample_strategy(SampleStrategy::Stratified);
+        for (int pass = 0; pass < 20; pass++)
+        {
+            std::vector<int> cells(9, 0);
+            for (int sample = 0; sample < 9; sample++)
+            {
+                double u, v;
+                image.get_uv(2, 1, sample, 9, u, v);
+                int cell_u = static_cast<int>((u * 4 - 2) * 3);
+                int cell_v = static_cast<int>((v * 2 - 1) * 3);
+                CHECK(cell_u >= 0 && cell_u < 3 && cell_v >= 0 && cell_v < 3);
+                if (cell_u >= 0 && cell_u < 3 && cell_v >= 0 && cell_v < 3)
+                    cells[cell_v * 3 + cell_u]++;
+            }
+            for (int cell : cells)
+                CHECK(cell == 1);
+        }
+    }
+
+    // writing leaves the image data alone: two writes give identical files, and
+    // samples can be added afterwards
+    {
+        ImageData image(3, 2);
+        for (int j = 0; j < 2; j++)
+            for (int i = 0; i < 3; i++)
+                image.add_color(i, j, Color(0.1 * i, 0.2 * j, 0.5));
+        const ImageData &snapshot = image;
+        snapshot.write_to_ppm("test_image_data_first.ppm");
+        snapshot.write_to_ppm("test_image_data_second.ppm");
+        std::string first = read_file("test_image_data_first.ppm");
+        CHECK(!first.empty());
+        CHECK(first == read_file("test_image_data_second.ppm"));
+        std::remove("test_image_data_first.ppm");
+        std::remove("test_image_data_second.ppm");
+
+        std::stringstream png_first, png_second;
+        snapshot.write_png(png_first);
+        snapshot.write_png(png_second);
+        CHECK(png_first.str() == png_second.str());
+
+        image.add_color(0, 0, Color(1, 1, 1));
+        CHECK(image.get_number_of_samples(0, 0) == 2);
+    }
+
+    // every pixel of a 2 x 2 image is visited once, row by row, with its color
+    // averaged over its samples
+    {
+        ImageData image(2, 2);
+        for (int j = 0; j < 2; j++)
+        {
+            for (int i = 0; i < 2; i++)
+            {
+                image.add_color(i, j, Color(i, j, 1));
+                image.add_color(i, j, Color(i, j, 0));
+            }
+        }
+        std::vector<int> order;
+        image.for_each_pixel([&](int i, int j, const Color &color) {
+            order.push_back(2 * j + i);
+            CHECK_NEAR((color - Color(i, j, 0.5)).length(), 0, 1e-12);
+        });
+        CHECK(order == std::vector<int>({0, 1, 2, 3}));
+    }
+
+    // the bilateral filter leaves a uniform image unchanged and smooths salt and
+    // pepper noise
+    {
+        ImageData uniform(8, 8);
+        ImageData noisy(8, 8);
+        seed_random(95);
+        for (int j = 0; j < 8; j++)
+        {
+            for (int i = 0; i < 8; i++)
+            {
+                uniform.add_color(i, j, Color(0.3, 0.5, 0.7));
+                double xi = random_double();
+                double value = xi < 0.15 ? 0.0 : (xi < 0.3 ? 1.0 : 0.5);
+                noisy.add_color(i, j, Color(value, value, value));
+            }
+        }
+        ImageData smooth_uniform = uniform.bilateral_denoise(1.5, 0.5);
+        for (int j = 0; j < 8; j++)
+            for (int i = 0; i < 8; i++)
+                CHECK_NEAR((smooth_uniform.get_radiance(i, j) - Color(0.3, 0.5, 0.7)).length(), 0, 1e-12);
+        ImageData denoised = noisy.bilateral_denoise(1.5, 0.5);
+        CHECK(roughness(noisy) > 0);
+        CHECK(roughness(denoised) < 0.5 * roughness(noisy));
+    }
+
+    // the hdr keeps values above 1: a pixel of 4 decodes to about 4, without
+    // the gamma and the clamp of the 8 bit formats
+    {
+        ImageData image(2, 1);
+        image.add_color(0, 0, Color(4.0, 1.0, 0.25));
+        image.add_color(1, 0, Color(0, 0, 0));
+        std::stringstream hdr;
+        image.write_hdr(hdr);
+        std::string bytes = hdr.str();
+        std::string header = "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 2\n";
+        CHECK(bytes.size() == header.size() + 2 * 4);
+        CHECK(bytes.compare(0, header.size(), header) == 0);
+        const unsigned char *pixels = reinterpret_cast<const unsigned char *>(bytes.data() + header.size());
+        Color bright = decode_rgbe(pixels);
+        CHECK_NEAR(bright.r(), 4.0, 4.0 / 128);
+        CHECK_NEAR(bright.g(), 1.0, 4.0 / 128);
+        CHECK_NEAR(bright.b(), 0.25, 4.0 / 128);
+        CHECK((decode_rgbe(pixels + 4) - Color(0, 0, 0)).length() == 0);
+    }
+
+    return check_result();
+}
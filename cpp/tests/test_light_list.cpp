@@ -0,0 +1,95 @@
+/*
+test_light_list.cpp
+===================
+Checks of the weighted pick of lights.
+*/
+
+#include <cmath>
+
+#include "check.h"
+#include "rectangle.h"
+#include "triangle.h"
+#include "light_list.h"
+#include "bvh.cpp"
+
+/**
+ * @brief      Counts how often sample_one picks each light.
+ * @param[in]  lights   The lights
+ * @param[in]  samples  The number of picks
+ * @return     The fraction of the picks of each light.
+ */
+std::vector<double> pick_fractions(const LightList &lights, int samples)
+{
+    std::vector<double> fractions(lights.size(), 0.0);
+    for (int n = 0; n < samples; n++)
+    {
+        double pdf;
+        auto light = lights.sample_one(pdf);
+        for (int i = 0; i < lights.size(); i++)
+        {
+            if (lights.get(i) == light)
+            {
+                CHECK_NEAR(pdf, lights.selection_probability(i), 1e-12);
+                fractions[i] += 1.0 / samples;
+            }
+        }
+    }
+    return fractions;
+}
+
+int main()
+{
+    auto small = std::make_shared<XZ_Rectangle>(0, 1, 0, 1, 5, nullptr);
+    auto large = std::make_shared<XZ_Rectangle>(0, 2, 0, 1, 5, nullptr);
+    auto triangle = std::make_shared<Triangle>(Vec3(0, 5, 0), Vec3(1, 5, 0), Vec3(0, 5, 1), nullptr);
+
+    // a light with twice the area is picked about twice as often
+    LightList lights;
+    lights.add(small);
+    lights.add(large);
+    CHECK_NEAR(lights.selection_probability(0), 1.0 / 3, 1e-12);
+    CHECK_NEAR(lights.selection_probability(1), 2.0 / 3, 1e-12);
+    std::vector<double> fractions = pick_fractions(lights, 100000);
+    CHECK_NEAR(fractions[1] / fractions[0], 2.0, 0.1);
+
+    // a light without a known area gets an equal share of the picks
+    lights.add(triangle);
+    CHECK_NEAR(lights.selection_probability(0), 2.0 / 9, 1e-12);
+    CHECK_NEAR(lights.selection_probability(1), 4.0 / 9, 1e-12);
+    CHECK_NEAR(lights.selection_probability(2), 1.0 / 3, 1e-12);
+    fractions = pick_fractions(lights, 100000);
+    CHECK_NEAR(fractions[2], 1.0 / 3, 0.01);
+    CHECK_NEAR(fractions[1] / fractions[0], 2.0, 0.1);
+
+    // without any known area all lights are picked equally often
+    LightList unknown;
+    unknown.add(triangle);
+    unknown.add(std::make_shared<Triangle>(Vec3(0, 6, 0), Vec3(9, 6, 0), Vec3(0, 6, 9), nullptr));
+    CHECK_NEAR(unknown.selection_probability(0), 0.5, 1e-12);
+    CHECK_NEAR(unknown.selection_probability(1), 0.5, 1e-12);
+
+    // with many lights of different areas every light is picked with its share
+    // of the total area, and lights with a weight of zero are never picked
+    seed_random(58);
+    LightList many;
+    for (int i = 0; i < 64; i++)
+        many.add(std::make_shared<XZ_Rectangle>(0, i + 1, 0, 1, 5, nullptr));
+    many.add(std::make_shared<XZ_Rectangle>(0, 1, 0, 1, 6, nullptr), 0.0);
+    double total_probability = 0;
+    for (int i = 0; i < many.size(); i++)
+    {
+        total_probability += many.selection_probability(i);
+        CHECK_NEAR(many.selection_probability(i), i < 64 ? (i + 1) / 2080.0 : 0.0, 1e-12);
+    }
+    CHECK_NEAR(total_probability, 1.0, 1e-12);
+    const int picks = 400000;
+    fractions = pick_fractions(many, picks);
+    for (int i = 0; i < many.size(); i++)
+    {
+        double p = many.selection_probability(i);
+        CHECK_NEAR(fractions[i], p, 5 * std::sqrt(p * (1 - p) / picks) + 1e-12);
+    }
+    CHECK(fractions[64] == 0);
+
+    return check_result();
+}
@@ -0,0 +1,59 @@
+/*
+test_load_obj.cpp
+=================
+Checks of loading triangle meshes from OBJ data.
+*/
+
+#include <sstream>
+
+#include "check.h"
+#include "load_obj.h"
+#include "bvh.cpp"
+
+/**
+ * @brief      Loads OBJ data from a string.
+ * @param[in]  obj        The OBJ data
+ * @param[out] triangles  The triangles of the data
+ * @return     true if the data was read without errors
+ */
+bool load_obj_string(const std::string &obj, HittableList &triangles)
+{
+    std::istringstream in(obj);
+    return load_obj(in, nullptr, triangles);
+}
+
+int main()
+{
+    // a unit square in the z = 0 plane as two triangles, the second face with
+    // relative indices and texture coordinates
+    {
+        HittableList triangles;
+        CHECK(load_obj_string("# square\n"
+                              "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n"
+                              "vt 0 0\n"
+                              "f 1 2 3\n"
+                              "f -4/1 -2/1 -1/1\n",
+                              triangles));
+        CHECK(triangles.size() == 2);
+        HitRecord rec;
+        CHECK(triangles.hit(Ray(Vec3(0.7, 0.2, 1), Vec3(0, 0, -1), 0), 0.001, 100, rec));
+        CHECK(triangles.hit(Ray(Vec3(0.2, 0.7, 1), Vec3(0, 0, -1), 0), 0.001, 100, rec));
+        CHECK_NEAR(rec.get_t(), 1.0, 1e-12);
+        CHECK(!triangles.hit(Ray(Vec3(1.2, 0.5, 1), Vec3(0, 0, -1), 0), 0.001, 100, rec));
+    }
+
+    // a face with four vertices is split into a fan of two triangles
+    {
+        HittableList triangles;
+        CHECK(load_obj_string("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n", triangles));
+        CHECK(triangles.size() == 2);
+    }
+
+    // an index past the vertices is an error
+    {
+        HittableList triangles;
+        CHECK(!load_obj_string("v 0 0 0\nv 1 0 0\nf 1 2 3\n", triangles));
+    }
+
+    return check_result();
+}
@@ -0,0 +1,168 @@
+/*
+test_materials.cpp
+==================
+Checks of the scattering of the materials.
+*/
+
+#include <cmath>
+
+#include "check.h"
+#include "ray_tracer.h"
+#include "sphere.h"
+#include "rectangle.h"
+#include "dielectric.h"
+#include "metal.h"
+#include "lambertian.h"
+#include "solid_color.h"
+#include "hittable_list.h"
+#include "bvh.cpp"
+
+/**
+ * @brief      Hits an object and scatters the ray at its material.
+ * @param[in]  object          The object
+ * @param[in]  ray             The incoming ray
+ * @param[out] rec             The hit record
+ * @param[out] scatter_record  The scatter record
+ * @return     True if the ray hits the object and scatters, False otherwise.
+ */
+bool hit_and_scatter(const Hittable &object, const Ray &ray, HitRecord &rec, ScatterRecord &scatter_record)
+{
+    if (!object.hit(ray, ray_epsilon, infinity, rec))
+        return false;
+    return rec.get_material()->scatter(ray, rec, scatter_record);
+}
+
+int main()
+{
+    HitRecord rec;
+    ScatterRecord scatter_record;
+
+    // glass with the refractive index of air never reflects a head-on ray,
+    // it passes straight through
+    {
+        Dielectric air(1.0);
+        Sphere sphere(Vec3(0, 0, 0), 1.0, &air);
+        Ray ray(Vec3(0, 0, 5), Vec3(0, 0, -1), 0);
+        for (int n = 0; n < 100; n++)
+        {
+            CHECK(hit_and_scatter(sphere, ray, rec, scatter_record));
+            CHECK(scatter_record.is_specular);
+            CHECK_NEAR((scatter_record.specular_ray.origin - Vec3(0, 0, 1)).length(), 0, 1e-12);
+            CHECK_NEAR((unit_vector(scatter_record.specular_ray.direction) - Vec3(0, 0, -1)).length(), 0, 1e-12);
+        }
+    }
+
+    // a mirror without fuzz reflects about the normal, the angle of incidence
+    // equals the angle of reflection
+    {
+        Metal mirror(Color(0.9, 0.9, 0.9), 0.0);
+        XZ_Rectangle floor(-5, 5, -5, 5, 0, &mirror);
+        Ray ray(Vec3(-1, 1, 0), Vec3(1, -1, 0), 0);
+        for (int n = 0; n < 10; n++)
+        {
+            CHECK(hit_and_scatter(floor, ray, rec, scatter_record));
+            CHECK_NEAR((scatter_record.specular_ray.origin - Vec3(0, 0, 0)).length(), 0, 1e-12);
+            CHECK_NEAR((unit_vector(scatter_record.specular_ray.direction) - unit_vector(Vec3(1, 1, 0))).length(), 0, 1e-12);
+            CHECK_NEAR((scatter_record.attenuation - Color(0.9, 0.9, 0.9)).length(), 0, 1e-12);
+        }
+    }
+
+    // a flat normal map pointing straight up leaves the shading normal at the
+    // geometric normal, a map pointing along x turns it to the tangent
+    {
+        Lambertian flat(Color(0.5, 0.5, 0.5));
+        flat.set_normal_map(std::make_shared<SolidColor>(Color(0.5, 0.5, 1)));
+        Sphere sphere(Vec3(0, 0, 0), 1.0, &flat);
+        Ray ray(Vec3(0.3, 0.4, 5), Vec3(0, 0, -1), 0);
+        CHECK(sphere.hit(ray, ray_epsilon, infinity, rec));
+        Vec3 normal = rec.get_normal();
+        flat.apply_normal_map(rec);
+        CHECK_NEAR((rec.get_shading_normal() - normal).length(), 0, 1e-12);
+        CHECK_NEAR((rec.get_normal() - normal).length(), 0, 1e-12);
+
+        Lambertian tilted(Color(0.5, 0.5, 0.5));
+        tilted.set_normal_map(std::make_shared<SolidColor>(Color(1, 0.5, 0.5)));
+        CHECK(sphere.hit(ray, ray_epsilon, infinity, rec));
+        tilted.apply_normal_map(rec);
+        CHECK_NEAR(dot(rec.get_shading_normal(), normal), 0, 1e-12);
+        CHECK_NEAR((rec.get_normal() - normal).length(), 0, 1e-12);
+    }
+
+    // colored glass attenuates a ray on its way through by Beer's law, a thicker
+    // piece more than a thin one, the entering ray is not attenuated
+    {
+        Dielectric glass(1.0, Color(0.1, 0.5, 1.0));
+        Color transmitted[2];
+        for (int k = 0; k < 2; k++)
+        {
+            double radius = k + 1;
+            Sphere sphere(Vec3(0, 0, 0), radius, &glass);
+            Ray ray(Vec3(0, 0, 5), Vec3(0, 0, -1), 0);
+            CHECK(hit_and_scatter(sphere, ray, rec, scatter_record));
+            CHECK_NEAR((scatter_record.attenuation - Color(1, 1, 1)).length(), 0, 1e-12);
+            CHECK(hit_and_scatter(sphere, scatter_record.specular_ray, rec, scatter_record));
+            transmitted[k] = scatter_record.attenuation;
+            Color expected(exp(-0.1 * 2 * radius), exp(-0.5 * 2 * radius), exp(-1.0 * 2 * radius));
+            CHECK_NEAR((transmitted[k] - expected).length(), 0, 1e-9);
+        }
+        CHECK(transmitted[1].r() < transmitted[0].r());
+        CHECK(transmitted[1].g() < transmitted[0].g());
+        CHECK(transmitted[1].b() < transmitted[0].b());
+    }
+
+    // glass with dispersion bends blue light more than red light, each following
+    // Snell's law with the refractive index of its wavelength
+    {
+        seed_random(97);
+        Dielectric prism(1.5, Color(0, 0, 0), 0.01);
+        CHECK(prism.refractive_index(450) > prism.refractive_index(650));
+        XZ_Rectangle surface(-5, 5, -5, 5, 0, &prism);
+        double sin_refracted[2];
+        double wavelengths[2] = {450, 650};
+        for (int k = 0; k < 2; k++)
+        {
+            Ray ray(Vec3(-1, 1, 0), Vec3(1, -1, 0), 0);
+            ray.wavelength = wavelengths[k];
+            // the Fresnel reflection is random, retry until the ray is refracted
+            do
+                CHECK(hit_and_scatter(surface, ray, rec, scatter_record));
+            while (scatter_record.specular_ray.direction.y() > 0);
+            sin_refracted[k] = unit_vector(scatter_record.specular_ray.direction).x();
+            CHECK_NEAR(sin_refracted[k] * prism.refractive_index(wavelengths[k]), std::sqrt(0.5), 1e-9);
+        }
+        CHECK(sin_refracted[0] < sin_refracted[1] - 1e-3);
+    }
+
+    // rays scattered on a sphere resting on a floor start ray_epsilon away from
+    // their surface, none of them hits the convex sphere or the flat floor again
+    {
+        seed_random(80);
+        Lambertian red(Color(0.8, 0.2, 0.2));
+        Lambertian gray(Color(0.5, 0.5, 0.5));
+        HittableList world;
+        world.add(std::make_shared<Sphere>(Vec3(0, 1, 0), 1.0, &red));
+        world.add(std::make_shared<XZ_Rectangle>(-10, 10, -10, 10, 0, &gray));
+        int secondary_hits = 0;
+        for (int n = 0; n < 10000; n++)
+        {
+            Vec3 target(random_double(-2, 2), random_double(0, 2), random_double(-2, 2));
+            Vec3 from(0, 3, 6);
+            Ray ray(from, target - from, 0);
+            if (!world.hit(ray, ray_epsilon, infinity, rec) || !rec.get_material()->scatter(ray, rec, scatter_record))
+                continue;
+            Material *surface = rec.get_material();
+            Vec3 direction;
+            double pdf_val;
+            scatter_record.pdf->generate(direction, pdf_val);
+            HitRecord next;
+            if (!world.hit(Ray(rec.get_hit_point(), direction, 0), ray_epsilon, infinity, next))
+                continue;
+            secondary_hits++;
+            CHECK(next.get_material() != surface);
+            CHECK(next.get_t() > ray_epsilon);
+        }
+        CHECK(secondary_hits > 0);
+    }
+
+    return check_result();
+}
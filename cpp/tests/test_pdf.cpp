@@ -0,0 +1,88 @@
+/*
+test_pdf.cpp
+============
+Checks of the probability density functions.
+*/
+
+#include "check.h"
+#include "materials.h"
+#include "lambertian.h"
+#include "sphere.h"
+#include "rectangle.h"
+#include "pdf.h"
+#include "bvh.cpp"
+
+int main()
+{
+    Lambertian gray(Color(0.5, 0.5, 0.5));
+    auto lights = std::make_shared<HittableList>();
+    lights->add(std::make_shared<Sphere>(Vec3(0, 3, 0), 0.5, &gray));
+    Vec3 origin(0, 0, 0);
+    auto light_pdf = std::make_shared<HittablePDF>(lights, origin, 0);
+    auto cosine_pdf = std::make_shared<CosinePDF>(Vec3(0, 1, 0));
+    MixturePDF mixture(light_pdf, cosine_pdf);
+
+    // cosine weighted directions lie in the upper hemisphere with an average
+    // cosine of 2/3, and the density is cos(theta) / pi
+    {
+        const int samples = 100000;
+        double average_cosine = 0;
+        for (int i = 0; i < samples; i++)
+        {
+            Vec3 direction = random_cosine_direction();
+            CHECK(direction.z() >= 0);
+            average_cosine += direction.z() / samples;
+        }
+        CHECK_NEAR(average_cosine, 2.0 / 3, 0.01);
+        Vec3 tilted = unit_vector(Vec3(1, 1, 0));
+        CHECK_NEAR(cosine_pdf->value(tilted), dot(tilted, Vec3(0, 1, 0)) / pi, 1e-12);
+        CHECK(cosine_pdf->value(Vec3(0, -1, 0)) == 0);
+    }
+
+    // the density of a mixture is the average of both densities, for any direction
+    for (Vec3 direction : {Vec3(0, 1, 0), Vec3(0.05, 1, 0), Vec3(1, 1, 0), Vec3(0, -1, 0)})
+    {
+        double average = 0.5 * light_pdf->value(direction) + 0.5 * cosine_pdf->value(direction);
+        CHECK_NEAR(mixture.value(direction), average, 1e-12);
+    }
+    for (int i = 0; i < 1000; i++)
+    {
+        Vec3 direction;
+        double value;
+        mixture.generate(direction, value);
+        CHECK_NEAR(value, 0.5 * light_pdf->value(direction) + 0.5 * cosine_pdf->value(direction), 1e-12);
+    }
+
+    // without lights the density is 0, not NAN
+    HittablePDF no_lights(std::make_shared<HittableList>(), origin, 0);
+    CHECK(no_lights.value(Vec3(0, 1, 0)) == 0);
+    Vec3 direction;
+    double value;
+    no_lights.generate(direction, value);
+    CHECK(value == 0);
+
+    // a box light samples directions towards itself, with a density integrating to 1
+    Box box(Vec3(-1, 2, -0.5), Vec3(1, 3, 0.5), nullptr);
+    for (Vec3 from : {Vec3(0, 0, 0), Vec3(3, 5, 2), Vec3(0, 2.5, 0)})
+    {
+        const int samples = 200000;
+        double integral = 0;
+        double solid_angle = 0;
+        double sampled_solid_angle = 0;
+        for (int i = 0; i < samples; i++)
+        {
+            Vec3 uniform = random_unit_vector();
+            double value = box.pdf_value(from, uniform, 0);
+            integral += value * 4 * pi / samples;
+            solid_angle += (value > 0 ? 4 * pi : 0) / samples;
+            // the sampled directions have the density of pdf_value
+            double sampled_value = box.pdf_value(from, box.random(from), 0);
+            CHECK(sampled_value > 0);
+            sampled_solid_angle += 1 / sampled_value / samples;
+        }
+        CHECK_NEAR(integral, 1.0, 0.05);
+        CHECK_NEAR(sampled_solid_angle / solid_angle, 1.0, 0.05);
+    }
+
+    return check_result();
+}
@@ -0,0 +1,52 @@
+/*
+test_quad.cpp
+=============
+Checks of the hit of a tilted quad and its bounding box.
+*/
+
+#include <cmath>
+
+#include "check.h"
+#include "quad.h"
+#include "camera.h"
+#include "bvh.cpp"
+
+int main()
+{
+    // quad of the points (2 alpha, beta, beta), tilted by 45 degrees about x
+    Quad quad(Vec3(0, 0, 0), Vec3(2, 0, 0), Vec3(0, 1, 1), nullptr);
+    HitRecord rec;
+
+    // a ray along -z hits at the planar coordinates of the point, with the
+    // unit normal along cross(u, v)
+    Ray down(Vec3(0.5, 0.5, 5), Vec3(0, 0, -1), 0);
+    CHECK(quad.hit(down, 0.001, 100, rec));
+    CHECK_NEAR(rec.get_t(), 4.5, 1e-12);
+    CHECK_NEAR(rec.get_u(), 0.25, 1e-12);
+    CHECK_NEAR(rec.get_v(), 0.5, 1e-12);
+    CHECK_NEAR((rec.get_normal() - Vec3(0, -1, 1) / std::sqrt(2)).length(), 0, 1e-12);
+    CHECK(rec.is_front_face(down));
+
+    // misses beyond the edges along u and v, and parallel to the plane
+    CHECK(!quad.hit(Ray(Vec3(2.5, 0.5, 5), Vec3(0, 0, -1), 0), 0.001, 100, rec));
+    CHECK(!quad.hit(Ray(Vec3(0.5, 1.5, 5), Vec3(0, 0, -1), 0), 0.001, 100, rec));
+    CHECK(!quad.hit(Ray(Vec3(-1, 0.5, 0.5), Vec3(1, 0, 0), 0), 0.001, 100, rec));
+
+    // the box encloses the four corners
+    AABB box;
+    CHECK(quad.bounding_box(0, 1, box));
+    CHECK_NEAR((box.get_min() - Vec3(0, 0, 0)).length(), 0, 1e-3);
+    CHECK_NEAR((box.get_max() - Vec3(2, 1, 1)).length(), 0, 1e-3);
+
+    // the texture footprint of a pixel on a quad facing the camera grows in
+    // proportion to its distance
+    Camera camera(40, 1.0, 0.0, 1.0, Vec3(0, 0, 0), Vec3(0, 0, -1));
+    Ray ray = camera.get_ray(0.5, 0.5, 0.01, 0.01);
+    HitRecord near_rec, far_rec;
+    CHECK(Quad(Vec3(-2, -2, -2), Vec3(4, 0, 0), Vec3(0, 4, 0), nullptr).hit(ray, 0.001, 100, near_rec));
+    CHECK(Quad(Vec3(-2, -2, -20), Vec3(4, 0, 0), Vec3(0, 4, 0), nullptr).hit(ray, 0.001, 100, far_rec));
+    CHECK(near_rec.get_footprint() > 0);
+    CHECK_NEAR(far_rec.get_footprint(), 10 * near_rec.get_footprint(), 1e-9);
+
+    return check_result();
+}
@@ -0,0 +1,27 @@
+/*
+test_ray.cpp
+============
+Checks of the points and the time of a ray.
+*/
+
+#include "check.h"
+#include "ray.h"
+
+int main()
+{
+    Vec3 origin(1, 2, 3);
+    Vec3 direction(-2, 0.5, 4);
+
+    // the ray starts at its origin and reaches origin + direction at t = 1
+    Ray ray(origin, direction);
+    CHECK((ray.point_at_parameter(0) - origin).length() == 0);
+    CHECK_NEAR((ray.point_at_parameter(1) - (origin + direction)).length(), 0, 1e-12);
+    CHECK_NEAR((ray.point_at_parameter(-0.5) - Vec3(2, 1.75, 1)).length(), 0, 1e-12);
+
+    // without a time the ray is at time 0
+    CHECK(ray.get_time() == 0);
+    CHECK(Ray().get_time() == 0);
+    CHECK(Ray(origin, direction, 0.25).get_time() == 0.25);
+
+    return check_result();
+}
@@ -0,0 +1,137 @@
+/*
+test_rectangle.cpp
+==================
+Checks of the axis-aligned rectangles and of the box made of them.
+*/
+
+#include <cmath>
+
+#include "check.h"
+#include "rectangle.h"
+#include "hittable_list.h"
+#include "bvh.cpp"
+
+/**
+ * @brief      Checks if a point lies inside a box.
+ * @param[in]  box   The box
+ * @param[in]  p     The point
+ * @return     True if the point is inside the box, False otherwise.
+ */
+bool inside(const AABB &box, const Vec3 &p)
+{
+    for (int axis = 0; axis < 3; axis++)
+    {
+        if (p[axis] < box.get_min()[axis] || p[axis] > box.get_max()[axis])
+            return false;
+    }
+    return true;
+}
+
+/**
+ * @brief      Checks that the points hit by rays towards a rectangle lie inside
+ *             its bounding box, and that the box is thin along the normal.
+ * @param[in]  rectangle    The rectangle
+ * @param[in]  from         The origin of the rays
+ * @param[in]  normal_axis  The axis of the normal of the rectangle
+ * @param[in]  corner       A corner of the rectangle
+ * @param[in]  opposite     The opposite corner of the rectangle
+ */
+void check_bounding_box(const Hittable &rectangle, const Vec3 &from, int normal_axis, const Vec3 &corner, const Vec3 &opposite)
+{
+    AABB box;
+    CHECK(rectangle.bounding_box(0, 1, box));
+    CHECK(box.get_max()[normal_axis] - box.get_min()[normal_axis] < 0.01);
+    CHECK(inside(box, corner));
+    CHECK(inside(box, opposite));
+    for (double s : {0.1, 0.5, 0.9})
+    {
+        Vec3 p = corner + s * (opposite - corner);
+        HitRecord rec;
+        CHECK(rectangle.hit(Ray(from, p - from, 0), 0.001, infinity, rec));
+        CHECK(inside(box, rec.get_hit_point()));
+    }
+}
+
+/**
+ * @brief      Checks the light sampling of a rectangle of area 4 with the given
+ *             center: the pdf towards the center is distance^2 / (area * cos),
+ *             from straight above and at an angle, and the random directions
+ *             hit the rectangle.
+ * @param[in]  rectangle    The rectangle
+ * @param[in]  center       The center of the rectangle
+ * @param[in]  normal_axis  The axis of the normal of the rectangle
+ */
+void check_light_sampling(const Hittable &rectangle, const Vec3 &center, int normal_axis)
+{
+    const double area = 4;
+    Vec3 normal(0, 0, 0);
+    normal[normal_axis] = 1;
+    Vec3 side(0, 0, 0);
+    side[(normal_axis + 1) % 3] = 1;
+
+    Vec3 above = center + 3 * normal;
+    CHECK_NEAR(rectangle.pdf_value(above, center - above, 0), 9 / area, 1e-9);
+
+    Vec3 aside = center + 3 * normal + 4 * side;
+    double cosine = 3.0 / 5.0;
+    CHECK_NEAR(rectangle.pdf_value(aside, center - aside, 0), 25 / (area * cosine), 1e-9);
+
+    for (int n = 0; n < 100; n++)
+    {
+        Vec3 direction = rectangle.random(aside);
+        CHECK(rectangle.pdf_value(aside, direction, 0) > 0);
+    }
+}
+
+int main()
+{
+    // a point of each rectangle lies inside its box, which spans the rectangle
+    // and is thin along the normal
+    XY_Rectangle xy(1, 3, -2, 0, 4, nullptr);
+    check_bounding_box(xy, Vec3(0, 0, 10), 2, Vec3(1, -2, 4), Vec3(3, 0, 4));
+    XZ_Rectangle xz(1, 3, -2, 0, 4, nullptr);
+    check_bounding_box(xz, Vec3(0, 10, 0), 1, Vec3(1, 4, -2), Vec3(3, 4, 0));
+    YZ_Rectangle yz(1, 3, -2, 0, 4, nullptr);
+    check_bounding_box(yz, Vec3(10, 0, 0), 0, Vec3(4, 1, -2), Vec3(4, 3, 0));
+
+    // the light sampling of each rectangle converts the density of its area to
+    // the density of the solid angle
+    check_light_sampling(xy, Vec3(2, -1, 4), 2);
+    check_light_sampling(xz, Vec3(2, 4, -1), 1);
+    check_light_sampling(yz, Vec3(4, 2, -1), 0);
+
+    // the slab test of the box finds the same hits as the six rectangles it was
+    // made of, with the normal of the face turned outwards
+    {
+        seed_random(69);
+        Vec3 minimum(-1, 0, 2);
+        Vec3 maximum(2, 1, 4);
+        Vec3 center = 0.5 * (minimum + maximum);
+        Box box(minimum, maximum, nullptr);
+        HittableList sides;
+        sides.add(std::make_shared<XY_Rectangle>(-1, 2, 0, 1, 4, nullptr));
+        sides.add(std::make_shared<XY_Rectangle>(-1, 2, 0, 1, 2, nullptr));
+        sides.add(std::make_shared<XZ_Rectangle>(-1, 2, 2, 4, 1, nullptr));
+        sides.add(std::make_shared<XZ_Rectangle>(-1, 2, 2, 4, 0, nullptr));
+        sides.add(std::make_shared<YZ_Rectangle>(0, 1, 2, 4, 2, nullptr));
+        sides.add(std::make_shared<YZ_Rectangle>(0, 1, 2, 4, -1, nullptr));
+        int hits = 0;
+        for (int n = 0; n < 1000; n++)
+        {
+            Vec3 from = (n % 4 == 0) ? center + 0.4 * random_in_unit_sphere() : center + 6 * random_unit_vector();
+            Ray ray(from, center + 2 * random_in_unit_sphere() - from, 0);
+            HitRecord slab, rectangles;
+            bool hit = sides.hit(ray, 0.001, infinity, rectangles);
+            CHECK(box.hit(ray, 0.001, infinity, slab) == hit);
+            if (!hit)
+                continue;
+            hits++;
+            CHECK_NEAR(slab.get_t(), rectangles.get_t(), 1e-9);
+            CHECK_NEAR(std::fabs(dot(slab.get_normal(), rectangles.get_normal())), 1, 1e-12);
+            CHECK(dot(slab.get_normal(), slab.get_hit_point() - center) > 0);
+        }
+        CHECK(hits > 500);
+    }
+
+    return check_result();
+}
@@ -0,0 +1,262 @@
+/*
+test_render.cpp
+===============
+Checks of the render functions in render.h.
+*/
+
+#include <vector>
+
+#ifdef _OPENMP
+#include <omp.h>
+#endif
+
+#include "check.h"
+#include "ray_tracer.h"
+#include "hittable_list.h"
+#include "camera.h"
+#include "sphere.h"
+#include "rectangle.h"
+#include "lambertian.h"
+#include "diffuse_light.h"
+#include "background.h"
+#include "bvh.cpp"
+#include "render.h"
+
+/**
+ * @brief      A small lit scene: a diffuse sphere on a diffuse floor under a light.
+ * @param      world  The hittable objects, filled by the function
+ */
+void lit_scene(HittableList &world)
+{
+    static Lambertian gray(Color(0.5, 0.5, 0.5));
+    static Lambertian red(Color(0.8, 0.2, 0.2));
+    static DiffuseLight light(Color(4, 4, 4));
+    world.add(std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &red));
+    world.add(std::make_shared<Sphere>(Vec3(0, -101, 0), 100.0, &gray));
+    world.add(std::make_shared<XZ_Rectangle>(-1, 1, -1, 1, 3, &light));
+}
+
+/**
+ * @brief      A sphere of radius 1 at the origin glowing in the color (1, 2, 3).
+ * @param      world  The hittable objects, filled by the function
+ */
+void glowing_scene(HittableList &world)
+{
+    static DiffuseLight glow(Color(1, 2, 3));
+    world.add(std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &glow));
+}
+
+/**
+ * @brief      Checks that two images have the same size and the same pixels.
+ * @param[in]  a     The first image
+ * @param[in]  b     The second image
+ * @return     Whether every pixel is equal.
+ */
+bool same_pixels(const ImageData &a, const ImageData &b)
+{
+    if (a.get_width() != b.get_width() || a.get_height() != b.get_height())
+        return false;
+    for (int j = 0; j < a.get_height(); j++)
+    {
+        for (int i = 0; i < a.get_width(); i++)
+        {
+            Color ca = a.get_radiance(i, j);
+            Color cb = b.get_radiance(i, j);
+            if (ca.x() != cb.x() || ca.y() != cb.y() || ca.z() != cb.z())
+                return false;
+        }
+    }
+    return true;
+}
+
+int main()
+{
+    HittableList world;
+    lit_scene(world);
+    SolidBackground background(Color(0.1, 0.1, 0.2));
+    Camera camera(40, 1.5, 0.0, 6.0, Vec3(0, 1, 6), Vec3(0, 0, 0));
+
+    // two renders with the same seed give identical image data, another seed does not
+    {
+        camera.set_image_data(24);
+        render_with_seed(camera, world, background, 4, 8, 38);
+        ImageData first = camera.image;
+        camera.set_image_data(24);
+        render_with_seed(camera, world, background, 4, 8, 38);
+        CHECK(same_pixels(first, camera.image));
+        camera.set_image_data(24);
+        render_with_seed(camera, world, background, 4, 8, 39);
+        CHECK(!same_pixels(first, camera.image));
+    }
+
+    // a seeded render on one thread gives the same pixels as on several threads
+    {
+#ifdef _OPENMP
+        int threads = omp_get_max_threads();
+        omp_set_num_threads(1);
+#endif
+        camera.set_image_data(24);
+        render_with_seed(camera, world, background, 4, 8, 1234);
+        ImageData serial = camera.image;
+#ifdef _OPENMP
+        omp_set_num_threads(std::max(threads, 4));
+#endif
+        camera.set_image_data(24);
+        render_with_seed(camera, world, background, 4, 8, 1234);
+        CHECK(same_pixels(serial, camera.image));
+#ifdef _OPENMP
+        omp_set_num_threads(threads);
+#endif
+    }
+
+    // render_pixel returns the sum of the samples and leaves the image data alone:
+    // the center pixel sees only the glowing sphere, a corner pixel only the background
+    {
+        HittableList glowing;
+        glowing_scene(glowing);
+        Camera front(40, 1.5, 0.0, 5.0, Vec3(0, 0, 5), Vec3(0, 0, 0));
+        front.set_image_data(9);
+        Color center = render_pixel(front, glowing, background, 4, 3, 16, 8);
+        CHECK_NEAR((center - 16 * Color(1, 2, 3)).length(), 0, 1e-9);
+        Color corner = render_pixel(front, glowing, background, 0, 0, 16, 8);
+        CHECK_NEAR((corner - 16 * Color(0.1, 0.1, 0.2)).length(), 0, 1e-9);
+        CHECK(front.image.get_number_of_samples(4, 3) == 0);
+    }
+
+    // progressive rendering adds one sample per pixel and pass, a callback
+    // returning false stops after its pass with a valid image
+    {
+        HittableList glowing;
+        glowing_scene(glowing);
+        Camera front(40, 1.5, 0.0, 5.0, Vec3(0, 0, 5), Vec3(0, 0, 0));
+        front.set_image_data(9);
+        std::vector<int> seen;
+        int passes = render_progressive(front, glowing, background, 10, 8, [&](const ImageData &image, int pass) {
+            seen.push_back(pass);
+            CHECK(image.get_number_of_samples(4, 3) == pass);
+            return pass < 3;
+        });
+        CHECK(passes == 3);
+        CHECK(seen == std::vector<int>({1, 2, 3}));
+        CHECK_NEAR((front.image.get_radiance(4, 3) - Color(1, 2, 3)).length(), 0, 1e-9);
+    }
+
+    // tiles at the right and top border are cut to the image, every pixel is
+    // rendered exactly once
+    {
+        HittableList glowing;
+        glowing_scene(glowing);
+        Camera front(40, 1.5, 0.0, 5.0, Vec3(0, 0, 5), Vec3(0, 0, 0));
+        front.set_image_data(10);
+        CHECK(front.image.get_width() % 4 != 0 && front.image.get_height() % 4 != 0);
+        render_tiled(front, glowing, background, 2, 8, 4);
+        for (int j = 0; j < front.image.get_height(); j++)
+            for (int i = 0; i < front.image.get_width(); i++)
+                CHECK(front.image.get_number_of_samples(i, j) == 2);
+    }
+
+    // the progress of a tiled render, also on several threads, grows with every
+    // finished tile and ends at exactly 1
+    {
+        HittableList glowing;
+        glowing_scene(glowing);
+        Camera front(40, 1.5, 0.0, 5.0, Vec3(0, 0, 5), Vec3(0, 0, 0));
+        front.set_image_data(30);
+#ifdef _OPENMP
+        int threads = omp_get_max_threads();
+        omp_set_num_threads(std::max(threads, 4));
+#endif
+        std::vector<double> progress;
+        render_tiled(front, glowing, background, 1, 8, 4, [&](double fraction) { progress.push_back(fraction); });
+#ifdef _OPENMP
+        omp_set_num_threads(threads);
+#endif
+        int tiles = ((front.image.get_width() + 3) / 4) * ((front.image.get_height() + 3) / 4);
+        CHECK(static_cast<int>(progress.size()) == tiles);
+        for (size_t k = 1; k < progress.size(); k++)
+            CHECK(progress[k] > progress[k - 1]);
+        CHECK(!progress.empty() && progress.back() == 1.0);
+    }
+
+    // the auxiliary images show the albedo and the normal of the first hit, and
+    // the background with a zero normal where the rays miss
+    {
+        HittableList sphere;
+        Lambertian red(Color(0.8, 0.2, 0.2));
+        sphere.add(std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &red));
+        Camera front(40, 1.0, 0.0, 5.0, Vec3(0, 0, 5), Vec3(0, 0, 0));
+        front.set_image_data(9);
+        ImageData albedo, normals;
+        render_with_aux(front, sphere, background, 16, 8, albedo, normals);
+        CHECK_NEAR((albedo.get_radiance(4, 4) - Color(0.8, 0.2, 0.2)).length(), 0, 1e-9);
+        CHECK_NEAR((normals.get_radiance(4, 4) - Color(0.5, 0.5, 1)).length(), 0, 0.1);
+        CHECK_NEAR((albedo.get_radiance(0, 0) - Color(0.1, 0.1, 0.2)).length(), 0, 1e-9);
+        CHECK((normals.get_radiance(0, 0) - Color(0, 0, 0)).length() == 0);
+    }
+
+    // in the depth image a nearer sphere is darker than a farther one, and
+    // pixels that miss lie on the far plane
+    {
+        HittableList spheres;
+        Lambertian gray(Color(0.5, 0.5, 0.5));
+        spheres.add(std::make_shared<Sphere>(Vec3(-1, 0, 2), 0.5, &gray));
+        spheres.add(std::make_shared<Sphere>(Vec3(1, 0, -2), 0.5, &gray));
+        Camera ortho(40, 2.0, 0.0, 5.0, Vec3(0, 0, 5), Vec3(0, 0, 0));
+        ortho.set_orthographic(4.0);
+        ortho.set_image_data(8);
+        ImageData depth = render_depth(ortho, spheres, 0.0, 10.0);
+        double near = depth.get_radiance(2, 2).x();
+        double far = depth.get_radiance(5, 2).x();
+        CHECK(near < far);
+        CHECK_NEAR(near, 0.25, 0.02);
+        CHECK_NEAR(far, 0.65, 0.02);
+        CHECK(depth.get_radiance(0, 0).x() == 1.0);
+    }
+
+    // adaptive sampling stops at the minimum where every sample is the same, the
+    // flat background and the inside of the glowing sphere, and takes the maximum
+    // on its silhouette
+    {
+        HittableList glowing;
+        glowing_scene(glowing);
+        Camera front(40, 1.0, 0.0, 5.0, Vec3(0, 0, 5), Vec3(0, 0, 0));
+        front.set_image_data(20);
+        render_adaptive(front, glowing, background, 4, 64, 0.01, 8);
+        CHECK(front.image.get_number_of_samples(0, 0) == 4);
+        CHECK(front.image.get_number_of_samples(10, 10) == 4);
+        int edge_pixels = 0;
+        for (int j = 0; j < front.image.get_height(); j++)
+        {
+            for (int i = 0; i < front.image.get_width(); i++)
+            {
+                int samples = front.image.get_number_of_samples(i, j);
+                CHECK(samples >= 4 && samples <= 64);
+                if (samples == 64)
+                    edge_pixels++;
+            }
+        }
+        CHECK(edge_pixels > 0);
+    }
+
+    // a one sample render of a glowing sphere casts exactly one primary ray per
+    // pixel and nothing else, the lit scene scatters further rays
+    {
+        HittableList glowing;
+        glowing_scene(glowing);
+        Camera front(40, 1.5, 0.0, 5.0, Vec3(0, 0, 5), Vec3(0, 0, 0));
+        front.set_image_data(12);
+        RenderStats stats = render_with_stats(front, glowing, background, 1, 8);
+        long long pixels = front.image.get_width() * front.image.get_height();
+        CHECK(stats.primary_rays == pixels);
+        CHECK(stats.secondary_rays == 0);
+        CHECK(stats.rays == pixels);
+
+        camera.set_image_data(12);
+        stats = render_with_stats(camera, world, background, 1, 8);
+        CHECK(stats.primary_rays == camera.image.get_width() * camera.image.get_height());
+        CHECK(stats.secondary_rays > 0);
+        CHECK(stats.rays == stats.primary_rays + stats.secondary_rays);
+    }
+
+    return check_result();
+}
@@ -0,0 +1,64 @@
+/*
+test_sampler.cpp
+================
+Checks of the radical inverse and the Halton sampler.
+*/
+
+#include "check.h"
+#include "sampler.h"
+
+int main()
+{
+    // the digits of the index are mirrored at the decimal point
+    CHECK_NEAR(radical_inverse(2, 1), 0.5, 1e-15);
+    CHECK_NEAR(radical_inverse(2, 2), 0.25, 1e-15);
+    CHECK_NEAR(radical_inverse(2, 6), 0.375, 1e-15);
+    CHECK_NEAR(radical_inverse(3, 1), 1.0 / 3, 1e-15);
+    CHECK_NEAR(radical_inverse(3, 2), 2.0 / 3, 1e-15);
+    CHECK_NEAR(radical_inverse(3, 5), 2.0 / 3 + 1.0 / 9, 1e-15);
+    CHECK(radical_inverse(2, 0) == 0);
+
+    // the sequence starts at index 1 with (1/2, 1/3), then (1/4, 2/3)
+    {
+        HaltonSampler sampler;
+        double x, y;
+        sampler.next_2d(x, y);
+        CHECK_NEAR(x, 0.5, 1e-15);
+        CHECK_NEAR(y, 1.0 / 3, 1e-15);
+        sampler.next_2d(x, y);
+        CHECK_NEAR(x, 0.25, 1e-15);
+        CHECK_NEAR(y, 2.0 / 3, 1e-15);
+    }
+
+    // skipping points starts later in the sequence, the shift wraps around 1
+    {
+        HaltonSampler sampler(1, 0.6, 0.5);
+        double x, y;
+        sampler.next_2d(x, y);
+        CHECK_NEAR(x, 0.85, 1e-15);
+        CHECK_NEAR(y, 2.0 / 3 + 0.5 - 1, 1e-15);
+    }
+
+    // the first 4 points put one x into every quarter of [0, 1), the first 6
+    // points two y into every third
+    {
+        HaltonSampler sampler;
+        int x_cells[4] = {0};
+        int y_cells[3] = {0};
+        for (int n = 0; n < 6; n++)
+        {
+            double x, y;
+            sampler.next_2d(x, y);
+            CHECK(x >= 0 && x < 1 && y >= 0 && y < 1);
+            if (n < 4)
+                x_cells[static_cast<int>(x * 4)]++;
+            y_cells[static_cast<int>(y * 3)]++;
+        }
+        for (int cell : x_cells)
+            CHECK(cell == 1);
+        for (int cell : y_cells)
+            CHECK(cell == 2);
+    }
+
+    return check_result();
+}
@@ -0,0 +1,53 @@
+/*
+test_scene_builder.cpp
+======================
+Checks of scenes built in code with the SceneBuilder.
+*/
+
+#include "check.h"
+#include "ray_tracer.h"
+#include "materials.h"
+#include "lambertian.h"
+#include "diffuse_light.h"
+#include "sphere.h"
+#include "bvh.cpp"
+#include "scene_builder.h"
+#include "cornell_box.h"
+#include "background.h"
+#include "shaders.h"
+
+int main()
+{
+    // the example of scene_builder.h, the red sphere is lit by the small sphere above it
+    SceneBuilder builder;
+    Material *red = builder.add_material<Lambertian>(Color(0.8, 0.1, 0.1));
+    Material *light = builder.add_material<DiffuseLight>(Color(4, 4, 4));
+    builder.add_object<Sphere>(red, Vec3(0, 0, 0), 1.0);
+    builder.add_object<Sphere>(light, Vec3(0, 3, 0), 0.5);
+    Scene scene = builder.build();
+
+    SolidBackground background(Color(0, 0, 0));
+    Color sum(0, 0, 0);
+    int samples = 1000;
+    for (int i = 0; i < samples; i++)
+    {
+        // the top of the red sphere, facing the light
+        Color color = ray_tracing_shader(Ray(Vec3(0, 1.5, 5), Vec3(0, -0.5, -5)), scene.world, background, 8);
+        CHECK(color.x() == color.x() && color.y() == color.y() && color.z() == color.z());
+        sum += color;
+    }
+    CHECK(sum.x() / samples > 0.05);
+    CHECK(sum.x() > sum.y());
+
+    // the Cornell box has five walls, the ceiling light and two boxes, with
+    // four materials of which one emits light
+    Scene cornell = cornell_box();
+    CHECK(cornell.world.size() == 8);
+    CHECK(cornell.materials.size() == 4);
+    int emissive = 0;
+    for (int i = 0; i < cornell.world.size(); i++)
+        emissive += cornell.world.get(i)->get_material()->is_emissive() ? 1 : 0;
+    CHECK(emissive == 1);
+
+    return check_result();
+}
@@ -0,0 +1,140 @@
+/*
+test_scenes.cpp
+===============
+Checks of the sample scenes in the scenes directory, loaded with load_scene.
+Run from the cpp directory, since the scene files are found relative to it.
+*/
+
+#include <cstdio>
+#include <fstream>
+#include <iterator>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "check.h"
+#include "ray_tracer.h"
+#include "hittable_list.h"
+#include "camera.h"
+#include "bvh.cpp"
+#include "load_scene.h"
+#include "shaders.h"
+
+/**
+ * @brief      Traces the ray through the center of the image.
+ * @param[in]  camera      The camera of the scene
+ * @param      world       The objects of the scene
+ * @param      background  The background of the scene
+ * @param[out] rec         The first hit of the ray
+ * @return     The color of one sample of the ray.
+ */
+Color trace_center(const Camera &camera, HittableList &world, Background &background, HitRecord &rec)
+{
+    Ray ray = camera.get_ray(0.5, 0.5);
+    world.hit(ray, ray_epsilon, infinity, rec);
+    return ray_tracing_shader(ray, world, background, 8);
+}
+
+/**
+ * @brief      Loads a scene in a child process, since load_scene exits on errors.
+ * @param[in]  filename  The scene file
+ * @return     The exit status of the child process.
+ */
+int load_scene_exit_status(const std::string &filename)
+{
+    pid_t pid = fork();
+    if (pid == 0)
+    {
+        Camera camera;
+        std::shared_ptr<Background> background;
+        load_scene(filename, camera, background);
+        _exit(0);
+    }
+    int status = 0;
+    waitpid(pid, &status, 0);
+    return WIFEXITED(status) ? WEXITSTATUS(status) : -1;
+}
+
+int main()
+{
+    // a single sphere at the origin, seen from (0, 0, 5)
+    {
+        Camera camera;
+        std::shared_ptr<Background> background;
+        HittableList world = load_scene("../scenes/single_sphere.json", camera, background);
+        CHECK(world.size() == 1);
+        HitRecord rec;
+        Color color = trace_center(camera, world, *background, rec);
+        CHECK(rec.get_hit());
+        CHECK_NEAR(rec.get_hit_point().z(), 1.0, 1e-6);
+        CHECK(color.x() == color.x() && color.y() == color.y() && color.z() == color.z());
+        CHECK(color.x() > 0 && color.y() > 0 && color.z() > 0);
+    }
+
+    // two spheres sharing the material "red" of the materials table
+    {
+        Camera camera;
+        std::shared_ptr<Background> background;
+        HittableList world = load_scene("../scenes/shared_material.json", camera, background);
+        CHECK(world.size() == 2);
+        CHECK(world.get(0)->get_material() != nullptr);
+        CHECK(world.get(0)->get_material() == world.get(1)->get_material());
+        HitRecord left, right;
+        world.hit(Ray(Vec3(-1.1, 0, 5), Vec3(0, 0, -1)), ray_epsilon, infinity, left);
+        world.hit(Ray(Vec3(1.1, 0, 5), Vec3(0, 0, -1)), ray_epsilon, infinity, right);
+        CHECK(left.get_hit() && right.get_hit());
+        CHECK(left.get_material() == right.get_material());
+        HitRecord rec;
+        Color color = trace_center(camera, world, *background, rec);
+        CHECK(color.x() == color.x() && color.y() == color.y() && color.z() == color.z());
+    }
+
+    // "projection": "orthographic" without "orthographic_width" still shoots parallel rays
+    {
+        std::string filename = "orthographic_test.json";
+        std::ifstream sample("../scenes/single_sphere.json");
+        std::string scene((std::istreambuf_iterator<char>(sample)), std::istreambuf_iterator<char>());
+        scene.replace(scene.find("\"camera\": {"), 11, "\"camera\": {\"projection\": \"orthographic\",");
+        std::ofstream(filename) << scene;
+        Camera camera;
+        std::shared_ptr<Background> background;
+        load_scene(filename, camera, background);
+        std::remove(filename.c_str());
+        Vec3 center = unit_vector(camera.get_ray(0.5, 0.5).direction);
+        Vec3 corner = unit_vector(camera.get_ray(0, 0).direction);
+        CHECK_NEAR((center - corner).length(), 0, 1e-9);
+    }
+
+    // every load_scene call reads its mesh files again, here after the file changed on disk
+    {
+        std::string filename = "mesh_test.json";
+        std::string mesh_filename = "mesh_test.obj";
+        std::ifstream sample("../scenes/single_sphere.json");
+        std::string scene((std::istreambuf_iterator<char>(sample)), std::istreambuf_iterator<char>());
+        scene.replace(scene.find("\"type\": \"sphere\""), 16, "\"type\": \"mesh\", \"file\": \"" + mesh_filename + "\"");
+        std::ofstream(filename) << scene;
+        for (double x : {0.0, 3.0})
+        {
+            std::ofstream(mesh_filename) << "v " << x - 1 << " -1 0\nv " << x + 1 << " -1 0\nv " << x << " 1 0\nf 1 2 3\n";
+            Camera camera;
+            std::shared_ptr<Background> background;
+            HittableList world = load_scene(filename, camera, background);
+            HitRecord rec;
+            CHECK(world.hit(Ray(Vec3(x, 0, 5), Vec3(0, 0, -1)), ray_epsilon, infinity, rec));
+        }
+        std::remove(filename.c_str());
+        std::remove(mesh_filename.c_str());
+    }
+
+    // a reference to a material missing from the table stops loading with exit status 1
+    {
+        std::string filename = "unknown_material_test.json";
+        std::ifstream sample("../scenes/shared_material.json");
+        std::string scene((std::istreambuf_iterator<char>(sample)), std::istreambuf_iterator<char>());
+        scene.replace(scene.rfind("\"red\""), 5, "\"blue\"");
+        std::ofstream(filename) << scene;
+        CHECK(load_scene_exit_status(filename) == 1);
+        std::remove(filename.c_str());
+    }
+
+    return check_result();
+}
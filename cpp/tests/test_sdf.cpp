@@ -0,0 +1,56 @@
+/*
+test_sdf.cpp
+============
+Checks of the sphere tracer of signed distance fields.
+*/
+
+#include "check.h"
+#include "sdf.h"
+#include "sphere.h"
+#include "lambertian.h"
+#include "bvh.cpp"
+
+int main()
+{
+    seed_random(62);
+    Lambertian gray(Color(0.5, 0.5, 0.5));
+    Vec3 center(0.5, -0.25, 0.0);
+    double radius = 1.5;
+    Sphere sphere(center, radius, &gray);
+    Sdf sdf([=](const Vec3 &p) { return (p - center).length() - radius; },
+            AABB(center - Vec3(radius, radius, radius), center + Vec3(radius, radius, radius)), &gray);
+
+    // rays from outside and from inside hit the distance field where they hit
+    // the algebraic sphere, with the same normal and face
+    {
+        int hits = 0;
+        for (int n = 0; n < 1000; n++)
+        {
+            Vec3 origin = (n % 2 == 0) ? center + 4 * random_unit_vector() : center + 0.5 * random_unit_vector();
+            Vec3 direction = 2 * random_unit_vector();
+            Ray r(origin, direction, 0);
+            HitRecord exact, traced;
+            bool hit_sphere = sphere.hit(r, 0.001, 100, exact);
+            bool hit_sdf = sdf.hit(r, 0.001, 100, traced);
+            CHECK(hit_sphere == hit_sdf);
+            if (!hit_sphere || !hit_sdf)
+                continue;
+            hits++;
+            CHECK_NEAR(traced.get_t(), exact.get_t(), 1e-5);
+            CHECK_NEAR((traced.get_hit_point() - exact.get_hit_point()).length(), 0, 1e-5);
+            CHECK_NEAR((traced.get_normal() - exact.get_normal()).length(), 0, 1e-4);
+            CHECK(traced.is_front_face(r) == exact.is_front_face(r));
+        }
+        CHECK(hits > 500);
+    }
+
+    // the bounding box is the one given
+    {
+        AABB box;
+        CHECK(sdf.bounding_box(0, 1, box));
+        CHECK_NEAR((box.get_min() - Vec3(-1, -1.75, -1.5)).length(), 0, 1e-12);
+        CHECK_NEAR((box.get_max() - Vec3(2, 1.25, 1.5)).length(), 0, 1e-12);
+    }
+
+    return check_result();
+}
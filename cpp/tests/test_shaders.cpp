@@ -0,0 +1,226 @@
+/*
+test_shaders.cpp
+================
+Checks of the shaders that trace single rays through a scene.
+*/
+
+#include <cmath>
+
+#include "check.h"
+#include "ray_tracer.h"
+#include "hittable_list.h"
+#include "sphere.h"
+#include "lambertian.h"
+#include "diffuse_light.h"
+#include "metal.h"
+#include "rectangle.h"
+#include "background.h"
+#include "bvh.cpp"
+#include "shaders.h"
+
+/**
+ * @brief      The path tracer as it was written before the loop, one recursion
+ *             per bounce, without russian roulette and clamping.
+ * @param[in]  ray         The ray to trace
+ * @param      world       The hittable objects
+ * @param      background  The background of the scene
+ * @param[in]  depth       The maximum number of bounces
+ * @return     The radiance along the ray.
+ */
+Color recursive_shader(const Ray &ray, HittableList &world, Background &background, int depth)
+{
+    if (depth <= 0)
+        return Color(0, 0, 0);
+    HitRecord hit_rec;
+    if (!world.hit(ray, ray_epsilon, infinity, hit_rec))
+        return background.get_color(ray);
+    hit_rec.get_material()->apply_normal_map(hit_rec);
+    ScatterRecord scatter_record;
+    Color emitted = hit_rec.get_material()->emitted(ray, hit_rec.u, hit_rec.v, hit_rec.get_hit_point());
+    if (!hit_rec.get_material()->scatter(ray, hit_rec, scatter_record))
+        return emitted;
+    if (scatter_record.is_specular)
+        return scatter_record.attenuation * recursive_shader(scatter_record.specular_ray, world, background, depth - 1);
+    Vec3 direction;
+    double pdf_val;
+    scatter_record.pdf->generate(direction, pdf_val);
+    Ray scattered(hit_rec.get_hit_point(), direction, ray.get_time());
+    Color weight = scatter_record.attenuation * hit_rec.get_material()->scattering_pdf(ray, hit_rec, scattered) / pdf_val;
+    return emitted + weight * recursive_shader(scattered, world, background, depth - 1);
+}
+
+int main()
+{
+    Lambertian gray(Color(0.5, 0.5, 0.5));
+    DiffuseLight glow(Color(1, 2, 3));
+    HittableList world;
+    world.add(std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &gray));
+    world.add(std::make_shared<Sphere>(Vec3(0, 5, 0), 1.0, &glow));
+    SolidBackground white(Color(1, 1, 1));
+    Ray to_gray(Vec3(0, 0, 5), Vec3(0, 0, -1), 0);
+    Ray to_glow(Vec3(0, 5, 5), Vec3(0, 0, -1), 0);
+    Ray to_background(Vec3(0, -5, 5), Vec3(0, 0, -1), 0);
+
+    // the point of a sphere facing the camera has the normal (0, 0, 1), shown
+    // as (0.5, 0.5, 1), and a miss is black
+    {
+        Color facing = normal_shader(to_gray, world);
+        CHECK_NEAR((facing - Color(0.5, 0.5, 1)).length(), 0, 1e-12);
+        Color side = normal_shader(Ray(Vec3(5, 0, 0), Vec3(-1, 0, 0), 0), world);
+        CHECK_NEAR((side - Color(1, 0.5, 0.5)).length(), 0, 1e-12);
+        Color miss = normal_shader(to_background, world);
+        CHECK((miss - Color(0, 0, 0)).length() == 0);
+    }
+
+    // russian roulette almost always ends a path without throughput, and the
+    // surviving paths weighted by 1 / survival probability keep the energy
+    {
+        seed_random(42);
+        const int paths = 100000;
+        int survivors = 0;
+        double energy = 0;
+        for (int n = 0; n < paths; n++)
+        {
+            double survival_probability;
+            if (russian_roulette(Color(1e-6, 1e-6, 1e-6), survival_probability))
+                survivors++;
+            if (russian_roulette(Color(0.1, 0.3, 0.2), survival_probability))
+                energy += 1.0 / survival_probability / paths;
+        }
+        CHECK(survivors <= 2);
+        CHECK_NEAR(energy, 1.0, 0.03);
+    }
+
+    // with a maximum depth of 1 only light seen directly arrives, the diffuse
+    // sphere lit by the background stays black until a second bounce is allowed
+    {
+        seed_random(52);
+        CHECK((ray_tracing_shader(to_gray, world, white, 1) - Color(0, 0, 0)).length() == 0);
+        CHECK((ray_tracing_shader(to_glow, world, white, 1) - Color(1, 2, 3)).length() == 0);
+        CHECK((ray_tracing_shader(to_background, world, white, 1) - Color(1, 1, 1)).length() == 0);
+        Color sum(0, 0, 0);
+        for (int n = 0; n < 1000; n++)
+            sum += ray_tracing_shader(to_gray, world, white, 2);
+        CHECK(sum.x() / 1000 > 0.2);
+    }
+
+    // a contribution above the threshold is scaled down with its hue kept, the
+    // shader clamps light arriving after a bounce but not light seen directly
+    {
+        CHECK_NEAR((clamp_contribution(Color(1, 2, 4), 2) - Color(0.5, 1, 2)).length(), 0, 1e-12);
+        CHECK((clamp_contribution(Color(1, 2, 1.5), 2) - Color(1, 2, 1.5)).length() == 0);
+
+        seed_random(88);
+        DiffuseLight bright(Color(100, 200, 300));
+        HittableList scene;
+        scene.add(std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &gray));
+        scene.add(std::make_shared<Sphere>(Vec3(0, 0, 3), 1.5, &bright));
+        SolidBackground black(Color(0, 0, 0));
+        Ray to_light(Vec3(0, 0, 10), Vec3(0, 0, -1), 0);
+        Ray to_sphere(Vec3(0, -5, 0), Vec3(0, 1, 0), 0);
+        CHECK((ray_tracing_shader(to_light, scene, black, 8, 0, Color(1, 1, 1), 2.0) - Color(100, 200, 300)).length() == 0);
+        bool lit = false;
+        bool unclamped_firefly = false;
+        for (int n = 0; n < 1000; n++)
+        {
+            Color clamped = ray_tracing_shader(to_sphere, scene, black, 8, 0, Color(1, 1, 1), 2.0);
+            CHECK(fmax(clamped.x(), fmax(clamped.y(), clamped.z())) <= 2.0 + 1e-9);
+            lit = lit || clamped.z() > 1.0;
+            Color unclamped = ray_tracing_shader(to_sphere, scene, black, 8);
+            unclamped_firefly = unclamped_firefly || unclamped.z() > 2.0;
+        }
+        CHECK(lit);
+        CHECK(unclamped_firefly);
+    }
+
+    // next event estimation converges to the same radiance as plain path tracing
+    // on a sphere and a floor lit by a spherical light, with a smaller variance
+    {
+        seed_random(92);
+        Lambertian plain_gray(Color(0.5, 0.5, 0.5));
+        Lambertian lit_gray(Color(0.5, 0.5, 0.5));
+        DiffuseLight lamp(Color(2, 2, 2));
+        auto light = std::make_shared<Sphere>(Vec3(0, 4, 0), 1.0, &lamp);
+        auto lights = std::make_shared<HittableList>();
+        lights->add(light);
+        lit_gray.set_lights(lights);
+        SolidBackground black(Color(0, 0, 0));
+
+        HittableList plain_scene, lit_scene;
+        for (auto pair : {std::make_pair(&plain_scene, &plain_gray), std::make_pair(&lit_scene, &lit_gray)})
+        {
+            pair.first->add(std::make_shared<Sphere>(Vec3(0, 1, 0), 1.0, pair.second));
+            pair.first->add(std::make_shared<XZ_Rectangle>(-10, 10, -10, 10, 0, pair.second));
+            pair.first->add(light);
+        }
+
+        const int paths = 100000;
+        Ray to_floor(Vec3(0, 2, 6), Vec3(1.5, 0, 0) - Vec3(0, 2, 6), 0);
+        double mean[2] = {0, 0};
+        double square[2] = {0, 0};
+        for (int n = 0; n < paths; n++)
+        {
+            double plain = ray_tracing_shader(to_floor, plain_scene, black, 4).x();
+            double nee = next_event_shader(to_floor, lit_scene, black, 4).x();
+            mean[0] += plain / paths;
+            square[0] += plain * plain / paths;
+            mean[1] += nee / paths;
+            square[1] += nee * nee / paths;
+        }
+        double variance[2] = {square[0] - mean[0] * mean[0], square[1] - mean[1] * mean[1]};
+        double standard_error = std::sqrt((variance[0] + variance[1]) / paths);
+        CHECK(mean[1] > 0.02);
+        CHECK(std::fabs(mean[0] - mean[1]) < 4 * standard_error);
+        CHECK(variance[1] < variance[0]);
+    }
+
+    // ambient occlusion is white on a floating sphere and darker on the same
+    // sphere resting in the corner of a floor and two walls
+    {
+        seed_random(94);
+        HittableList floating, corner;
+        floating.add(std::make_shared<Sphere>(Vec3(1, 1, 1), 1.0, &gray));
+        corner.add(std::make_shared<Sphere>(Vec3(1, 1, 1), 1.0, &gray));
+        corner.add(std::make_shared<XZ_Rectangle>(0, 10, 0, 10, 0, &gray));
+        corner.add(std::make_shared<YZ_Rectangle>(0, 10, 0, 10, 0, &gray));
+        corner.add(std::make_shared<XY_Rectangle>(0, 10, 0, 10, 0, &gray));
+        Ray low_side(Vec3(5, 0.2, 1), Vec3(-1, 0, 0), 0);
+        Color open = ambient_occlusion_shader(low_side, floating, 256, 2.0);
+        Color occluded = ambient_occlusion_shader(low_side, corner, 256, 2.0);
+        CHECK((open - Color(1, 1, 1)).length() == 0);
+        CHECK(occluded.x() < 0.7);
+        CHECK(occluded.x() == occluded.y() && occluded.y() == occluded.z());
+        CHECK((ambient_occlusion_shader(to_background, floating, 256, 2.0) - Color(1, 1, 1)).length() == 0);
+    }
+
+    // a miss returns the color of the background, a hit on a light its emission
+    // whatever the background is
+    {
+        SolidBackground red(Color(1, 0, 0));
+        CHECK((ray_tracing_shader(to_background, world, red, 8) - Color(1, 0, 0)).length() == 0);
+        CHECK((ray_tracing_shader(to_glow, world, red, 8) - Color(1, 2, 3)).length() == 0);
+        CHECK((ray_tracing_shader(to_glow, world, white, 8) - Color(1, 2, 3)).length() == 0);
+    }
+
+    // the path tracer gives the same radiance as the recursive one for the same
+    // random numbers, through diffuse and specular bounces
+    {
+        Metal mirror(Color(0.9, 0.8, 0.7), 0.1);
+        HittableList scene;
+        scene.add(std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &gray));
+        scene.add(std::make_shared<Sphere>(Vec3(2.5, 0, 0), 1.0, &mirror));
+        scene.add(std::make_shared<Sphere>(Vec3(0, 3, 0), 1.0, &glow));
+        for (int n = 0; n < 200; n++)
+        {
+            seed_random(78 + n);
+            Ray ray(Vec3(1, 1, 6), unit_vector(Vec3(0, 0, -6) + 2 * random_in_unit_sphere()), 0);
+            seed_random(1000 + n);
+            Color iterative = ray_tracing_shader(ray, scene, white, 6);
+            seed_random(1000 + n);
+            Color recursive = recursive_shader(ray, scene, white, 6);
+            CHECK_NEAR((iterative - recursive).length(), 0, 1e-9);
+        }
+    }
+
+    return check_result();
+}
@@ -0,0 +1,100 @@
+/*
+test_sphere.cpp
+===============
+Checks of the hits and the light sampling of the sphere. sphere.h is
+included first, so it has to compile on its own.
+*/
+
+#include "sphere.h"
+#include "check.h"
+#include "lambertian.h"
+#include "camera.h"
+#include "bvh.cpp"
+
+int main()
+{
+    Lambertian gray(Color(0.5, 0.5, 0.5));
+    Sphere sphere(Vec3(0, 3, 0), 1.0, &gray);
+    Vec3 origin(0, 0, 0);
+
+    // a ray from outside hits the front face, a ray from the center the back
+    // face, with the outward normal in both cases
+    {
+        HitRecord rec;
+        Ray outside(origin, Vec3(0, 1, 0), 0);
+        CHECK(sphere.hit(outside, ray_epsilon, infinity, rec));
+        CHECK(rec.is_front_face(outside));
+        CHECK_NEAR((rec.get_normal() - Vec3(0, -1, 0)).length(), 0, 1e-12);
+        Ray inside(Vec3(0, 3, 0), Vec3(0, 1, 0), 0);
+        CHECK(sphere.hit(inside, ray_epsilon, infinity, rec));
+        CHECK(!rec.is_front_face(inside));
+        CHECK_NEAR((rec.get_normal() - Vec3(0, 1, 0)).length(), 0, 1e-12);
+    }
+
+    // the range of the hit excludes its endpoints: a hit exactly at t_min or
+    // t_max is skipped, just inside the range it is found
+    {
+        Sphere unit(Vec3(0, 0, 0), 1.0, &gray);
+        HitRecord rec;
+        Ray down(Vec3(0, 0, 5), Vec3(0, 0, -1), 0);
+        CHECK(!unit.hit(down, 0.001, 4.0, rec));
+        CHECK(unit.hit(down, 0.001, 4.0 + 1e-9, rec));
+        CHECK_NEAR(rec.get_t(), 4.0, 1e-12);
+        CHECK(unit.hit(down, 4.0, 100, rec));
+        CHECK_NEAR(rec.get_t(), 6.0, 1e-12);
+        CHECK(!unit.hit(down, 4.0, 6.0, rec));
+    }
+
+    // the texture coordinates of the point (0, 0, -1) of a unit sphere are the
+    // center of the texture
+    {
+        Sphere unit(Vec3(0, 0, 0), 1.0, &gray);
+        HitRecord rec;
+        CHECK(unit.hit(Ray(Vec3(0, 0, -5), Vec3(0, 0, 1), 0), ray_epsilon, infinity, rec));
+        CHECK_NEAR((rec.get_hit_point() - Vec3(0, 0, -1)).length(), 0, 1e-12);
+        CHECK_NEAR(rec.get_u(), 0.5, 1e-9);
+        CHECK_NEAR(rec.get_v(), 0.5, 1e-9);
+    }
+
+    // the density over the unit sphere of directions integrates to 1
+    const int samples = 200000;
+    double integral = 0;
+    for (int i = 0; i < samples; i++)
+        integral += sphere.pdf_value(origin, random_unit_vector(), 0) * 4 * pi / samples;
+    CHECK_NEAR(integral, 1.0, 0.05);
+
+    // the sampled directions stay inside the cone of the sphere
+    for (int i = 0; i < 1000; i++)
+    {
+        Vec3 direction = sphere.random(origin);
+        HitRecord rec;
+        CHECK(sphere.hit(Ray(origin, direction, 0), ray_epsilon, infinity, rec));
+        CHECK(sphere.pdf_value(origin, direction, 0) > 0);
+    }
+
+    // the sampled directions weighted by their density cover the solid angle of
+    // the cone of the sphere, 2 pi (1 - cos theta_max) with sin theta_max = 1 / 3
+    {
+        double solid_angle = 2 * pi * (1 - std::sqrt(1 - 1.0 / 9));
+        double estimate = 0;
+        for (int i = 0; i < samples; i++)
+            estimate += 1 / sphere.pdf_value(origin, sphere.random(origin), 0) / samples;
+        CHECK_NEAR(estimate, solid_angle, 1e-3 * solid_angle);
+    }
+
+    // the texture footprint of a pixel grows with the distance to the sphere,
+    // rays without differentials leave it at 0
+    {
+        Camera camera(40, 1.0, 0.0, 1.0, Vec3(0, 0, 0), Vec3(0, 0, -1));
+        Ray ray = camera.get_ray(0.5, 0.5, 0.01, 0.01);
+        HitRecord near_rec, far_rec;
+        CHECK(Sphere(Vec3(0, 0, -3), 1.0, &gray).hit(ray, ray_epsilon, infinity, near_rec));
+        CHECK(Sphere(Vec3(0, 0, -10), 1.0, &gray).hit(ray, ray_epsilon, infinity, far_rec));
+        CHECK(near_rec.get_footprint() > 0);
+        CHECK(far_rec.get_footprint() > 3 * near_rec.get_footprint());
+        CHECK(Sphere(Vec3(0, 0, -3), 1.0, &gray).hit(Ray(Vec3(0, 0, 0), Vec3(0, 0, -1), 0), ray_epsilon, infinity, near_rec));
+        CHECK(near_rec.get_footprint() == 0);
+    }
+
+    return check_result();
+}
@@ -0,0 +1,67 @@
+/*
+test_textures.cpp
+=================
+Checks of the procedural and image textures.
+*/
+
+#include <cstdio>
+#include <fstream>
+
+#include "check.h"
+#include "ray_tracer.h"
+#include "perlin.h"
+#include "noise_texture.h"
+#include "checker_texture.h"
+#include "image_texture.h"
+
+int main()
+{
+    // Perlin noise stays in [-1, 1] and takes both signs, the noise texture
+    // maps it to gray values in [0, 1]
+    {
+        seed_random(14);
+        Perlin perlin;
+        NoiseTexture texture(4.0);
+        bool negative = false, positive = false;
+        for (int n = 0; n < 10000; n++)
+        {
+            Vec3 p(random_double(-10, 10), random_double(-10, 10), random_double(-10, 10));
+            double noise = perlin.noise(p);
+            CHECK(noise >= -1 && noise <= 1);
+            negative = negative || noise < 0;
+            positive = positive || noise > 0;
+            Color color = texture.value(0, 0, p);
+            CHECK(color.x() >= 0 && color.x() <= 1);
+            CHECK(color.x() == color.y() && color.y() == color.z());
+        }
+        CHECK(negative && positive);
+    }
+
+    // two points half a period apart along x lie in different checkers
+    {
+        Color white(1, 1, 1), black(0, 0, 0);
+        CheckerTexture checker(white, black, 10);
+        Vec3 p(0.05, 0.05, 0.05);
+        Vec3 q = p + Vec3(pi / 10, 0, 0);
+        CHECK((checker.value(0, 0, p) - white).length() == 0);
+        CHECK((checker.value(0, 0, q) - black).length() == 0);
+        CHECK((checker.value(0, 0, q + Vec3(0, pi / 10, 0)) - white).length() == 0);
+    }
+
+    // a 2 x 1 image: a texel center returns the texel, the midpoint between the
+    // texels their average, and coordinates outside the image the edge texel
+    {
+        const char *filename = "test_textures_image.ppm";
+        std::ofstream(filename) << "P3\n2 1\n255\n255 0 0 0 0 255\n";
+        ImageTexture texture(filename);
+        std::remove(filename);
+        CHECK(texture.get_width() == 2 && texture.get_height() == 1);
+        Vec3 p(0, 0, 0);
+        CHECK_NEAR((texture.value(0.25, 0.5, p) - Color(1, 0, 0)).length(), 0, 1e-12);
+        CHECK_NEAR((texture.value(0.75, 0.5, p) - Color(0, 0, 1)).length(), 0, 1e-12);
+        CHECK_NEAR((texture.value(0.5, 0.5, p) - Color(0.5, 0, 0.5)).length(), 0, 1e-12);
+        CHECK_NEAR((texture.value(0.0, 0.5, p) - Color(1, 0, 0)).length(), 0, 1e-12);
+    }
+
+    return check_result();
+}
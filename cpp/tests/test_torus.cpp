@@ -0,0 +1,46 @@
+/*
+test_torus.cpp
+==============
+Checks of the hits of the torus found by the quartic root finder.
+*/
+
+#include "check.h"
+#include "torus.h"
+#include "bvh.cpp"
+
+int main()
+{
+    // torus around the y axis with the major radius 2 and the minor radius 0.5
+    Torus torus(2.0, 0.5, nullptr);
+    HitRecord rec;
+
+    // a ray along the x axis hits the outside of the tube first
+    Ray along_x(Vec3(5, 0, 0), Vec3(-1, 0, 0), 0);
+    CHECK(torus.hit(along_x, 0.001, 100, rec));
+    CHECK_NEAR(rec.get_t(), 2.5, 1e-6);
+    CHECK_NEAR((rec.get_normal() - Vec3(1, 0, 0)).length(), 0, 1e-6);
+    CHECK(rec.is_front_face(along_x));
+
+    // from the center the ray hits the inside of the tube, behind the hole
+    CHECK(torus.hit(Ray(Vec3(0, 0, 0), Vec3(0, 0, 1), 0), 0.001, 100, rec));
+    CHECK_NEAR(rec.get_t(), 1.5, 1e-6);
+    CHECK_NEAR((rec.get_normal() - Vec3(0, 0, -1)).length(), 0, 1e-6);
+
+    // a ray down onto the circle of the tube hits its top
+    CHECK(torus.hit(Ray(Vec3(0, 5, -2), Vec3(0, -1, 0), 0), 0.001, 100, rec));
+    CHECK_NEAR(rec.get_t(), 4.5, 1e-6);
+    CHECK_NEAR((rec.get_normal() - Vec3(0, 1, 0)).length(), 0, 1e-6);
+
+    // rays along the major axis pass through the hole, rays above the tube miss
+    CHECK(!torus.hit(Ray(Vec3(0, 5, 0), Vec3(0, -1, 0), 0), 0.001, 100, rec));
+    CHECK(!torus.hit(Ray(Vec3(1, 5, 0.5), Vec3(0, -1, 0), 0), 0.001, 100, rec));
+    CHECK(!torus.hit(Ray(Vec3(5, 0.6, 0), Vec3(-1, 0, 0), 0), 0.001, 100, rec));
+
+    // the box encloses the tube
+    AABB box;
+    CHECK(torus.bounding_box(0, 1, box));
+    CHECK_NEAR((box.get_min() - Vec3(-2.5, -0.5, -2.5)).length(), 0, 1e-3);
+    CHECK_NEAR((box.get_max() - Vec3(2.5, 0.5, 2.5)).length(), 0, 1e-3);
+
+    return check_result();
+}
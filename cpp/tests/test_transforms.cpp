@@ -0,0 +1,190 @@
+/*
+test_transforms.cpp
+===================
+Checks of the transformations of objects and of their normals.
+*/
+
+#include "check.h"
+#include "triangle.h"
+#include "rectangle.h"
+#include "sphere.h"
+#include "lambertian.h"
+#include "rotation.h"
+#include "scale.h"
+#include "flip_normals.h"
+#include "translation.h"
+#include "instance.h"
+#include "hittable_list.h"
+#include "bvh.cpp"
+
+/**
+ * @brief      Hits an object with a ray from a point towards the origin.
+ * @param[in]  object  The object
+ * @param[in]  from    The origin of the ray
+ * @param[out] rec     The hit record
+ * @return     True if the ray hits the object, False otherwise.
+ */
+bool hit_towards_origin(const Hittable &object, const Vec3 &from, HitRecord &rec)
+{
+    return object.hit(Ray(from, -from, 0), 0.001, infinity, rec);
+}
+
+int main()
+{
+    // triangle in the xy plane facing +z, with vertex normals tilted towards +x
+    Vec3 tilted = unit_vector(Vec3(1, 0, 1));
+    auto triangle = std::make_shared<SmoothTriangle>(Vec3(-1, -1, 0), Vec3(1, -1, 0), Vec3(0, 1, 0),
+                                                     tilted, tilted, tilted, nullptr);
+    HitRecord rec;
+    CHECK(hit_towards_origin(*triangle, Vec3(0, 0, 5), rec));
+    CHECK_NEAR((rec.get_shading_normal() - tilted).length(), 0, 1e-9);
+
+    // rotated by 90 degrees around y, +z turns into +x and +x into -z
+    Vec3 rotated_normal(1, 0, 0);
+    Vec3 rotated_tilted = unit_vector(Vec3(1, 0, -1));
+    RotateY rotate_y(triangle, 90);
+    CHECK(hit_towards_origin(rotate_y, Vec3(5, 0, 0), rec));
+    CHECK_NEAR((rec.get_normal() - rotated_normal).length(), 0, 1e-9);
+    CHECK_NEAR((rec.get_shading_normal() - rotated_tilted).length(), 0, 1e-9);
+
+    Rotate rotate(triangle, Vec3(0, 1, 0), 90);
+    CHECK(hit_towards_origin(rotate, Vec3(5, 0, 0), rec));
+    CHECK_NEAR((rec.get_normal() - rotated_normal).length(), 0, 1e-9);
+    CHECK_NEAR((rec.get_shading_normal() - rotated_tilted).length(), 0, 1e-9);
+
+    // scaled by 2 along x, the normals are divided by 2 along x
+    Vec3 scaled_tilted = unit_vector(Vec3(0.5, 0, 1));
+    Scale scale(triangle, Vec3(2, 1, 1));
+    CHECK(hit_towards_origin(scale, Vec3(0, 0, 5), rec));
+    CHECK_NEAR((rec.get_normal() - Vec3(0, 0, 1)).length(), 0, 1e-9);
+    CHECK_NEAR((rec.get_shading_normal() - scaled_tilted).length(), 0, 1e-9);
+
+    // a box along +x rotated by 90 degrees around y lies along -z, its +x end
+    // faces -z and its +z side faces +x
+    {
+        auto box = std::make_shared<Box>(Vec3(0, -0.5, -0.5), Vec3(2, 0.5, 0.5), nullptr);
+        RotateY rotated(box, 90);
+        CHECK(hit_towards_origin(rotated, Vec3(0, 0, -5), rec));
+        CHECK_NEAR((rec.get_hit_point() - Vec3(0, 0, -2)).length(), 0, 1e-9);
+        CHECK_NEAR((rec.get_normal() - Vec3(0, 0, -1)).length(), 0, 1e-9);
+        CHECK(hit_towards_origin(rotated, Vec3(5, 0, 0), rec));
+        CHECK_NEAR((rec.get_hit_point() - Vec3(0.5, 0, 0)).length(), 0, 1e-9);
+        CHECK_NEAR((rec.get_normal() - Vec3(1, 0, 0)).length(), 0, 1e-9);
+
+        AABB bbox;
+        CHECK(rotated.bounding_box(0, 1, bbox));
+        CHECK_NEAR(bbox.get_min().z(), -2, 1e-9);
+        CHECK_NEAR(bbox.get_max().x(), 0.5, 1e-9);
+    }
+
+    // a box along +x rotated by 90 degrees around z lies along +y, with the
+    // +x end facing +y, and its bounding box is the one of the rotated corners
+    {
+        auto box = std::make_shared<Box>(Vec3(0, -0.5, -0.5), Vec3(2, 0.5, 0.5), nullptr);
+        Rotate rotated(box, Vec3(0, 0, 1), 90);
+        CHECK(hit_towards_origin(rotated, Vec3(0, 5, 0), rec));
+        CHECK_NEAR((rec.get_hit_point() - Vec3(0, 2, 0)).length(), 0, 1e-9);
+        CHECK_NEAR((rec.get_normal() - Vec3(0, 1, 0)).length(), 0, 1e-9);
+
+        AABB bbox;
+        CHECK(rotated.bounding_box(0, 1, bbox));
+        CHECK_NEAR((bbox.get_min() - Vec3(-0.5, 0, -0.5)).length(), 0, 1e-9);
+        CHECK_NEAR((bbox.get_max() - Vec3(0.5, 2, 0.5)).length(), 0, 1e-9);
+    }
+
+    // a translated sphere is hit at its new place with the normal unchanged, and
+    // its box moves along
+    {
+        Lambertian gray(Color(0.5, 0.5, 0.5));
+        Translate moved(std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &gray), Vec3(3, -1, 2));
+        CHECK(moved.hit(Ray(Vec3(3, -1, 10), Vec3(0, 0, -1), 0), 0.001, infinity, rec));
+        CHECK_NEAR(rec.get_t(), 7, 1e-12);
+        CHECK_NEAR((rec.get_hit_point() - Vec3(3, -1, 3)).length(), 0, 1e-12);
+        CHECK_NEAR((rec.get_normal() - Vec3(0, 0, 1)).length(), 0, 1e-12);
+        CHECK(!moved.hit(Ray(Vec3(0, 0, 10), Vec3(0, 0, -1), 0), 0.001, infinity, rec));
+        AABB bbox;
+        CHECK(moved.bounding_box(0, 1, bbox));
+        CHECK_NEAR((bbox.get_min() - Vec3(2, -2, 1)).length(), 0, 1e-3);
+        CHECK_NEAR((bbox.get_max() - Vec3(4, 0, 3)).length(), 0, 1e-3);
+    }
+
+    // a unit sphere scaled by 2 is hit like a sphere of radius 2, at the same
+    // distance along the ray and with the same normal
+    {
+        seed_random(73);
+        Lambertian gray(Color(0.5, 0.5, 0.5));
+        Scale scaled(std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &gray), Vec3(2, 2, 2));
+        Sphere large(Vec3(0, 0, 0), 2.0, &gray);
+        int hits = 0;
+        for (int n = 0; n < 100; n++)
+        {
+            Vec3 from = 5 * random_unit_vector();
+            Ray ray(from, 2.5 * random_in_unit_sphere() - from, 0);
+            HitRecord expected;
+            bool hit = large.hit(ray, 0.001, infinity, expected);
+            CHECK(scaled.hit(ray, 0.001, infinity, rec) == hit);
+            if (!hit)
+                continue;
+            hits++;
+            CHECK_NEAR(rec.get_t(), expected.get_t(), 1e-9);
+            CHECK_NEAR((rec.get_hit_point() - expected.get_hit_point()).length(), 0, 1e-9);
+            CHECK_NEAR((rec.get_normal() - expected.get_normal()).length(), 0, 1e-9);
+        }
+        CHECK(hits > 50);
+        AABB bbox;
+        CHECK(scaled.bounding_box(0, 1, bbox));
+        CHECK_NEAR((bbox.get_max() - Vec3(2, 2, 2)).length(), 0, 1e-3);
+    }
+
+    // flipped normals are the exact negation of the inner ones, the front face
+    // turns into the back face and the box stays the same
+    {
+        Lambertian gray(Color(0.5, 0.5, 0.5));
+        auto sphere = std::make_shared<Sphere>(Vec3(0, 0, 0), 1.0, &gray);
+        FlipNormals flipped(sphere);
+        for (Vec3 from : {Vec3(0, 0, 5), Vec3(3, 4, 0), Vec3(0, 0, 0.5)})
+        {
+            Ray ray(from, Vec3(0.1, 0.2, 0) - from, 0);
+            HitRecord inner;
+            CHECK(sphere->hit(ray, 0.001, infinity, inner));
+            CHECK(flipped.hit(ray, 0.001, infinity, rec));
+            CHECK(rec.get_t() == inner.get_t());
+            CHECK(rec.get_normal().x() == -inner.get_normal().x());
+            CHECK(rec.get_normal().y() == -inner.get_normal().y());
+            CHECK(rec.get_normal().z() == -inner.get_normal().z());
+            CHECK(rec.is_front_face(ray) != inner.is_front_face(ray));
+        }
+        AABB inner_box, flipped_box;
+        CHECK(sphere->bounding_box(0, 1, inner_box) && flipped.bounding_box(0, 1, flipped_box));
+        CHECK((flipped_box.get_min() - inner_box.get_min()).length() == 0);
+        CHECK((flipped_box.get_max() - inner_box.get_max()).length() == 0);
+    }
+
+    // two instances of one mesh of two triangles with its own BVH, placed side
+    // by side, are each hit at their place, with the material of the instance
+    {
+        Lambertian gray(Color(0.5, 0.5, 0.5));
+        Lambertian red(Color(0.8, 0.2, 0.2));
+        HittableList square;
+        square.add(std::make_shared<Triangle>(Vec3(-0.5, -0.5, 0), Vec3(0.5, -0.5, 0), Vec3(0.5, 0.5, 0), &gray));
+        square.add(std::make_shared<Triangle>(Vec3(-0.5, -0.5, 0), Vec3(0.5, 0.5, 0), Vec3(-0.5, 0.5, 0), &gray));
+        square.build_bvh();
+        auto mesh = std::make_shared<HittableList>(square);
+        HittableList world;
+        world.add(std::make_shared<Translate>(std::make_shared<Instance>(mesh), Vec3(-2, 0, 0)));
+        world.add(std::make_shared<Translate>(std::make_shared<Instance>(mesh, &red), Vec3(2, 0, 0)));
+        world.build_bvh();
+        CHECK(mesh.use_count() == 3);
+
+        for (double x : {-2.4, -1.6, 1.6, 2.4})
+        {
+            CHECK(world.hit(Ray(Vec3(x, 0.3, 5), Vec3(0, 0, -1), 0), 0.001, infinity, rec));
+            CHECK_NEAR((rec.get_hit_point() - Vec3(x, 0.3, 0)).length(), 0, 1e-9);
+            CHECK(rec.get_material() == (x < 0 ? &gray : &red));
+        }
+        CHECK(!world.hit(Ray(Vec3(0, 0, 5), Vec3(0, 0, -1), 0), 0.001, infinity, rec));
+        CHECK(!world.hit(Ray(Vec3(-2, 0.6, 5), Vec3(0, 0, -1), 0), 0.001, infinity, rec));
+    }
+
+    return check_result();
+}
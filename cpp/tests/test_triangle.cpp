@@ -0,0 +1,57 @@
+/*
+test_triangle.cpp
+=================
+Checks of the triangle hit with the Möller–Trumbore algorithm.
+*/
+
+#include "check.h"
+#include "triangle.h"
+#include "bvh.cpp"
+
+int main()
+{
+    // triangle in the z = 0 plane, counter-clockwise seen from +z
+    Triangle triangle(Vec3(-1, -1, 0), Vec3(1, -1, 0), Vec3(0, 1, 0), nullptr);
+    HitRecord rec;
+
+    // a ray through the inside hits at the plane with the normal of the edge cross product
+    CHECK(triangle.hit(Ray(Vec3(0, 0, 5), Vec3(0, 0, -1), 0), 0.001, 100, rec));
+    CHECK_NEAR(rec.get_t(), 5.0, 1e-12);
+    CHECK_NEAR((rec.get_hit_point() - Vec3(0, 0, 0)).length(), 0, 1e-12);
+    CHECK_NEAR((rec.get_normal() - Vec3(0, 0, 1)).length(), 0, 1e-12);
+    CHECK(rec.is_front_face(Ray(Vec3(0, 0, 5), Vec3(0, 0, -1), 0)));
+
+    // from behind the same normal is hit on its back face
+    CHECK(triangle.hit(Ray(Vec3(0, 0, -5), Vec3(0, 0, 1), 0), 0.001, 100, rec));
+    CHECK(!rec.is_front_face(Ray(Vec3(0, 0, -5), Vec3(0, 0, 1), 0)));
+
+    // misses outside the edges, parallel to the plane and outside the range
+    CHECK(!triangle.hit(Ray(Vec3(0.9, 0.9, 5), Vec3(0, 0, -1), 0), 0.001, 100, rec));
+    CHECK(!triangle.hit(Ray(Vec3(0, -1.1, 5), Vec3(0, 0, -1), 0), 0.001, 100, rec));
+    CHECK(!triangle.hit(Ray(Vec3(-5, 0, 0), Vec3(1, 0, 0), 0), 0.001, 100, rec));
+    CHECK(!triangle.hit(Ray(Vec3(0, 0, 5), Vec3(0, 0, -1), 0), 0.001, 4, rec));
+
+    // the box of an axis-aligned triangle is padded along the flat axis
+    AABB box;
+    CHECK(triangle.bounding_box(0, 1, box));
+    CHECK(box.get_max().z() - box.get_min().z() > 0);
+    CHECK_NEAR(box.get_min().x(), -1, 1e-3);
+    CHECK_NEAR(box.get_max().y(), 1, 1e-3);
+
+    // a smooth triangle with three equal vertex normals along the face normal
+    // is shaded like the flat triangle everywhere
+    SmoothTriangle flat(Vec3(-1, -1, 0), Vec3(1, -1, 0), Vec3(0, 1, 0),
+                        Vec3(0, 0, 2), Vec3(0, 0, 2), Vec3(0, 0, 2), nullptr);
+    for (Vec3 from : {Vec3(0, 0, 5), Vec3(-0.6, -0.8, 5), Vec3(0.5, -0.5, 5), Vec3(0, 0.8, 5)})
+    {
+        Ray ray(from, Vec3(0, 0, -1), 0);
+        HitRecord flat_rec;
+        CHECK(triangle.hit(ray, 0.001, 100, flat_rec));
+        CHECK(flat.hit(ray, 0.001, 100, rec));
+        CHECK_NEAR(rec.get_t(), flat_rec.get_t(), 1e-12);
+        CHECK_NEAR((rec.get_normal() - flat_rec.get_normal()).length(), 0, 1e-12);
+        CHECK_NEAR((rec.get_shading_normal() - flat_rec.get_shading_normal()).length(), 0, 1e-12);
+    }
+
+    return check_result();
+}
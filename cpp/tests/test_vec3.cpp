@@ -0,0 +1,87 @@
+/*
+test_vec3.cpp
+=============
+Checks of the vector math of Vec3.
+*/
+
+#include "check.h"
+#include "vec3.h"
+
+/**
+ * @brief      Distance between two vectors.
+ * @param[in]  a     The first vector
+ * @param[in]  b     The second vector
+ * @return     The distance
+ */
+double distance(const Vec3 &a, const Vec3 &b)
+{
+    return (a - b).length();
+}
+
+int main()
+{
+    // reflection at a surface facing up flips the y component
+    CHECK_NEAR(distance(reflect(Vec3(1, -1, 0), Vec3(0, 1, 0)), Vec3(1, 1, 0)), 0, 1e-12);
+
+    // refraction with the ratio 1 keeps the direction, a denser medium bends it
+    // towards the normal
+    Vec3 in = unit_vector(Vec3(1, -1, 0));
+    CHECK_NEAR(distance(refract(in, Vec3(0, 1, 0), 1.0), in), 0, 1e-12);
+    Vec3 bent = refract(in, Vec3(0, 1, 0), 1.0 / 1.5);
+    CHECK_NEAR(bent.length(), 1, 1e-12);
+    CHECK_NEAR(bent.x(), in.x() / 1.5, 1e-12);
+
+    // length, squared length and vectors near zero
+    CHECK_NEAR(Vec3(3, 4, 12).length(), 13, 1e-12);
+    CHECK_NEAR(Vec3(3, 4, 12).length_squared(), 169, 1e-12);
+    CHECK(Vec3(1e-9, 0, 0).is_near_zero());
+    CHECK(!Vec3(1e-6, 0, 0).is_near_zero());
+    CHECK(!Vec3(0, 0, -1).is_near_zero());
+
+    // component-wise minimum, maximum and clamp
+    Vec3 a(1, -2, 3);
+    Vec3 b(-1, 5, 3);
+    CHECK_NEAR(distance(Vec3::min(a, b), Vec3(-1, -2, 3)), 0, 1e-12);
+    CHECK_NEAR(distance(Vec3::max(a, b), Vec3(1, 5, 3)), 0, 1e-12);
+    CHECK_NEAR(distance(Vec3(-5, 0.5, 7).clamp(Vec3(0, 0, 0), Vec3(1, 1, 1)), Vec3(0, 0.5, 1)), 0, 1e-12);
+
+    // the product of two vectors is taken component by component, next to the
+    // product with a scalar
+    CHECK_NEAR(distance(Vec3(1, 2, 3) * Vec3(4, 5, 6), Vec3(4, 10, 18)), 0, 1e-12);
+    CHECK_NEAR(distance(Vec3(1, 2, 3) * 2, Vec3(2, 4, 6)), 0, 1e-12);
+    CHECK_NEAR(distance(2 * Vec3(1, 2, 3), Vec3(2, 4, 6)), 0, 1e-12);
+
+    // rotation by 90 degrees around z turns x into y, the axis stays and the
+    // length is kept for any axis
+    CHECK_NEAR(distance(Vec3(1, 0, 0).rotate_around(Vec3(0, 0, 1), pi / 2), Vec3(0, 1, 0)), 0, 1e-12);
+    CHECK_NEAR(distance(Vec3(0, 0, 2).rotate_around(Vec3(0, 0, 1), pi / 2), Vec3(0, 0, 2)), 0, 1e-12);
+    CHECK_NEAR(Vec3(1, 2, 3).rotate_around(unit_vector(Vec3(1, 1, 0)), 1.0).length(), Vec3(1, 2, 3).length(), 1e-12);
+
+    // the indices 0, 1 and 2 read and write x, y and z, the checked get refuses
+    // other indices and leaves the value alone
+    Vec3 indexed(1, 2, 3);
+    indexed[1] = 5;
+    CHECK(indexed[0] == 1 && indexed[1] == 5 && indexed[2] == 3);
+    double component = -1;
+    CHECK(indexed.get(2, component) && component == 3);
+    CHECK(!indexed.get(3, component) && component == 3);
+    CHECK(!indexed.get(-1, component) && component == 3);
+
+    // random vectors: the components lie in their range, unit vectors have the
+    // length 1 and points in the unit sphere a length below 1
+    seed_random(84);
+    for (int n = 0; n < 10000; n++)
+    {
+        Vec3 unit = Vec3::random();
+        Vec3 ranged = Vec3::random(-2, 3);
+        for (int axis = 0; axis < 3; axis++)
+        {
+            CHECK(unit[axis] >= 0 && unit[axis] < 1);
+            CHECK(ranged[axis] >= -2 && ranged[axis] < 3);
+        }
+        CHECK_NEAR(random_unit_vector().length(), 1, 1e-12);
+        CHECK(random_in_unit_sphere().length() < 1);
+    }
+
+    return check_result();
+}
@@ -1,13 +1,28 @@
+#ifndef CHECKER_TEXTURE_H
+#define CHECKER_TEXTURE_H
 
+#include <cmath>
+#include <memory>
+
+#include "texture.h"
+#include "solid_color.h"
+
+/**
+ * @brief      Class for checker texture.
+ * @details    Alternates between two textures in 3D space, based on the sign of
+ *             sin(scale * x) * sin(scale * y) * sin(scale * z).
+ */
 class CheckerTexture : public Texture
 {
 public:
     CheckerTexture() {}
-    CheckerTexture(Texture *t0, Texture *t1) : even(t0), odd(t1) {}
+    CheckerTexture(Texture *t0, Texture *t1, double scale = 10) : even(t0), odd(t1), scale(scale) {}
+    CheckerTexture(const Color &c0, const Color &c1, double scale = 10)
+        : even(std::make_shared<SolidColor>(c0)), odd(std::make_shared<SolidColor>(c1)), scale(scale) {}
 
     virtual Color value(double u, double v, const Vec3 &p) const override
     {
-        auto sines = sin(10 * p.x()) * sin(10 * p.y()) * sin(10 * p.z());
+        auto sines = sin(scale * p.x()) * sin(scale * p.y()) * sin(scale * p.z());
         if (sines < 0)
         {
             return odd->value(u, v, p);
@@ -19,6 +34,9 @@ public:
     }
 
 private:
-    Texture *even;
-    Texture *odd;
-};
\ No newline at end of file
+    std::shared_ptr<Texture> even;
+    std::shared_ptr<Texture> odd;
+    double scale = 10;
+};
+
+#endif // CHECKER_TEXTURE_H
@@ -0,0 +1,226 @@
+#ifndef IMAGE_TEXTURE_H
+#define IMAGE_TEXTURE_H
+
+#include <fstream>
+#include <iostream>
+#include <string>
+#include <vector>
+#include <algorithm>
+#include <cmath>
+
+#include "texture.h"
+#include "ray_tracer.h"
+
+/**
+ * @brief      Class for image texture.
+ * @details    Maps a ppm image (P3 or P6) onto a surface using the texture coordinates
+ *             of the hit. The color is interpolated bilinearly between the four
+ *             texels around the texture coordinates, texels outside the image are
+ *             clamped to the edge. If the image cannot be loaded, the texture is
+ *             solid cyan, so that the problem is visible in the render.
+ *             For lookups with a footprint, e.g. from ray differentials, the color
+ *             is interpolated between the two mip levels whose texels are closest
+ *             to the footprint. Each mip level averages 2x2 texels of the level below.
+ */
+class ImageTexture : public Texture
+{
+public:
+    ImageTexture() {}
+    ImageTexture(const std::string &filename)
+    {
+        if (!load_ppm(filename))
+        {
+            std::cerr << "Could not load image texture: " << filename << std::endl;
+            width = height = 0;
+            data.clear();
+        }
+        build_mip_levels();
+    }
+
+    virtual Color value(double u, double v, const Vec3 &p) const override
+    {
+        return sample(0, u, v);
+    }
+
+    /**
+     * @brief      The color averaged over a footprint.
+     * @param[in]  u          The u texture coordinate
+     * @param[in]  v          The v texture coordinate
+     * @param[in]  p          The hit point
+     * @param[in]  footprint  The width of the pixel in texture coordinates
+     * @return     The color.
+     */
+    virtual Color value(double u, double v, const Vec3 &p, double footprint) const override
+    {
+        double texels = footprint * std::max(width, height);
+        if (texels <= 1 || levels.size() < 2)
+            return sample(0, u, v);
+        double level = fmin(log2(texels), static_cast<double>(levels.size() - 1));
+        int lower = static_cast<int>(floor(level));
+        int upper = std::min(lower + 1, static_cast<int>(levels.size()) - 1);
+        double f = level - lower;
+        return (1 - f) * sample(lower, u, v) + f * sample(upper, u, v);
+    }
+
+    int get_width() const { return width; }
+    int get_height() const { return height; }
+    int get_number_of_levels() const { return levels.size(); }
+
+private:
+    /**
+     * @brief      A reduced copy of the image, level 0 is the image itself.
+     */
+    struct MipLevel
+    {
+        int width;
+        int height;
+        std::vector<double> data; // rgb values in [0, 1], row by row from the top
+    };
+
+    int width = 0;
+    int height = 0;
+    std::vector<double> data; // pixels while loading, moved to the first mip level
+    std::vector<MipLevel> levels;
+
+    /**
+     * @brief      Interpolates bilinearly in one mip level.
+     * @param[in]  level  The mip level
+     * @param[in]  u      The u texture coordinate
+     * @param[in]  v      The v texture coordinate
+     * @return     The color.
+     */
+    Color sample(int level, double u, double v) const
+    {
+        if (levels.empty())
+            return Color(0, 1, 1);
+        const MipLevel &mip = levels[level];
+
+        // clamp texture coordinates and flip v to image coordinates
+        u = clamp(u, 0.0, 1.0);
+        v = 1.0 - clamp(v, 0.0, 1.0);
+
+        // position relative to the texel centers
+        double x = u * mip.width - 0.5;
+        double y = v * mip.height - 0.5;
+        int i = static_cast<int>(floor(x));
+        int j = static_cast<int>(floor(y));
+        double fx = x - i;
+        double fy = y - j;
+
+        return (1 - fx) * (1 - fy) * texel(mip, i, j) + fx * (1 - fy) * texel(mip, i + 1, j) +
+               (1 - fx) * fy * texel(mip, i, j + 1) + fx * fy * texel(mip, i + 1, j + 1);
+    }
+
+    /**
+     * @brief      Get the color of a texel, clamping the indices to the image.
+     * @param[in]  mip   The mip level
+     * @param[in]  i     The column
+     * @param[in]  j     The row, counted from the top
+     * @return     The color of the texel.
+     */
+    static Color texel(const MipLevel &mip, int i, int j)
+    {
+        i = i < 0 ? 0 : (i >= mip.width ? mip.width - 1 : i);
+        j = j < 0 ? 0 : (j >= mip.height ? mip.height - 1 : j);
+        const double *pixel = &mip.data[3 * (j * mip.width + i)];
+        return Color(pixel[0], pixel[1], pixel[2]);
+    }
+
+    /**
+     * @brief      Builds the mip levels down to a single texel.
+     *             Odd sizes are rounded up, the last row or column is repeated.
+     */
+    void build_mip_levels()
+    {
+        levels.clear();
+        if (data.empty())
+            return;
+        levels.push_back({width, height, std::move(data)});
+        data.clear();
+        while (levels.back().width > 1 || levels.back().height > 1)
+        {
+            const MipLevel &below = levels.back();
+            MipLevel mip{(below.width + 1) / 2, (below.height + 1) / 2, {}};
+            mip.data.resize(3 * mip.width * mip.height);
+            for (int j = 0; j < mip.height; j++)
+            {
+                for (int i = 0; i < mip.width; i++)
+                {
+                    Color average = 0.25 * (texel(below, 2 * i, 2 * j) + texel(below, 2 * i + 1, 2 * j) +
+                                            texel(below, 2 * i, 2 * j + 1) + texel(below, 2 * i + 1, 2 * j + 1));
+                    for (int c = 0; c < 3; c++)
+                        mip.data[3 * (j * mip.width + i) + c] = average[c];
+                }
+            }
+            levels.push_back(mip);
+        }
+    }
+
+    /**
+     * @brief      Read the next header value of a ppm file, skipping comments.
+     * @param      in     The input stream
+     * @param[out] value  The value
+     * @return     True if a value was read.
+     */
+    static bool read_header_value(std::istream &in, int &value)
+    {
+        in >> std::ws;
+        while (in.peek() == '#')
+        {
+            std::string comment;
+            std::getline(in, comment);
+            in >> std::ws;
+        }
+        return static_cast<bool>(in >> value);
+    }
+
+    /**
+     * @brief      Load a ppm image (P3 or P6).
+     * @param[in]  filename  The filename
+     * @return     True if the image was loaded.
+     */
+    bool load_ppm(const std::string &filename)
+    {
+        std::ifstream in(filename, std::ios::binary);
+        if (!in.good())
+            return false;
+
+        std::string magic;
+        in >> magic;
+        if (magic != "P3" && magic != "P6")
+            return false;
+
+        int max_value;
+        if (!read_header_value(in, width) || !read_header_value(in, height) || !read_header_value(in, max_value))
+            return false;
+        if (width <= 0 || height <= 0 || max_value <= 0 || max_value > 255)
+            return false;
+
+        data.resize(3 * width * height);
+        if (magic == "P3")
+        {
+            for (auto &channel : data)
+            {
+                int c;
+                if (!(in >> c))
+                    return false;
+                channel = static_cast<double>(c) / max_value;
+            }
+        }
+        else
+        {
+            // single whitespace character between header and pixel data
+            in.get();
+            for (auto &channel : data)
+            {
+                int c = in.get();
+                if (c == EOF)
+                    return false;
+                channel = static_cast<double>(c) / max_value;
+            }
+        }
+        return true;
+    }
+};
+
+#endif // IMAGE_TEXTURE_H
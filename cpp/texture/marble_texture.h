@@ -1,8 +1,14 @@
+#ifndef MARBLE_TEXTURE_H
+#define MARBLE_TEXTURE_H
 
 #include <cmath>
 
 #include "texture.h"
 
+/**
+ * @brief      Class for marble texture.
+ * @details    Sine stripes along z, distorted by Perlin turbulence.
+ */
 class MarbleTexture : public Texture
 {
 public:
@@ -18,3 +24,5 @@ private:
     Perlin noise;
     double scale;
 };
+
+#endif // MARBLE_TEXTURE_H
@@ -1,6 +1,13 @@
+#ifndef NOISE_TEXTURE_H
+#define NOISE_TEXTURE_H
 
 #include "texture.h"
 
+/**
+ * @brief      Class for noise texture.
+ * @details    Gray values from Perlin noise, sampled at the hit point scaled by scale.
+ *             The noise in [-1, 1] is mapped to gray values in [0, 1].
+ */
 class NoiseTexture : public Texture
 {
 public:
@@ -9,10 +16,12 @@ public:
 
     virtual Color value(double u, double v, const Vec3 &p) const override
     {
-        return Color(1, 1, 1) * 0.5 * (1 + sin(scale * p.z() + 10 * noise.turb(scale * p)));
+        return Color(1, 1, 1) * 0.5 * (1 + noise.noise(scale * p));
     }
 
 private:
     Perlin noise;
     double scale;
 };
+
+#endif // NOISE_TEXTURE_H
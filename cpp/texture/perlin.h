@@ -27,11 +27,11 @@ class Perlin
 public:
     Perlin()
     {
-        // Generate random vectors
+        // Generate random values in [-1, 1], so the noise is signed
         ranvec = new double[point_count];
         for (int i = 0; i < point_count; ++i)
         {
-            ranvec[i] = random_double();
+            ranvec[i] = random_double(-1, 1);
         }
         perm_x = perlin_generate_perm();
         perm_y = perlin_generate_perm();
@@ -9,6 +9,14 @@ class Texture
 {
 public:
     virtual Color value(double u, double v, const Vec3 &p) const = 0;
+    /**
+     * @brief      The color for a pixel that covers the width footprint in texture
+     *             coordinates. Textures that do not filter ignore the footprint.
+     */
+    virtual Color value(double u, double v, const Vec3 &p, double footprint) const
+    {
+        return value(u, v, p);
+    }
 };
 
 
@@ -3,6 +3,8 @@
 
 #include <cmath>
 #include <iostream>
+#include <algorithm>
+#include <cassert>
 
 #include "ray_tracer.h"
 
@@ -15,8 +17,18 @@ public:
     Vec3(double e0, double e1, double e2) : e{e0, e1, e2} {}
     inline const Vec3 &operator+() const { return *this; }
     inline Vec3 operator-() const { return Vec3(-e[0], -e[1], -e[2]); }
-    inline double operator[](int i) const { return e[i]; }
-    inline double &operator[](int i) { return e[i]; }
+    // indices 0, 1 and 2 are x, y and z, other indices fail an assertion in debug builds
+    inline double operator[](int i) const
+    {
+        assert(i >= 0 && i < 3 && "Vec3 index out of range, expected 0, 1 or 2");
+        return e[i];
+    }
+    inline double &operator[](int i)
+    {
+        assert(i >= 0 && i < 3 && "Vec3 index out of range, expected 0, 1 or 2");
+        return e[i];
+    }
+    inline bool get(int i, double &value) const;
 
     inline Vec3 &operator+=(const Vec3 &v2);
     inline Vec3 &operator-=(const Vec3 &v2);
@@ -25,7 +37,12 @@ public:
     inline Vec3 &operator*=(const double t);
     inline Vec3 &operator/=(const double t);
 
+    inline static Vec3 random();
     inline static Vec3 random(double min, double max);
+    inline static Vec3 min(const Vec3 &a, const Vec3 &b);
+    inline static Vec3 max(const Vec3 &a, const Vec3 &b);
+    inline Vec3 clamp(const Vec3 &lo, const Vec3 &hi) const;
+    inline Vec3 rotate_around(const Vec3 &axis, double angle) const;
 
     inline double sum() const
     {
@@ -132,6 +149,22 @@ inline Vec3 unit_vector(Vec3 v)
     return v / v.length();
 }
 
+// reflection of v at a surface with unit normal n
+inline Vec3 reflect(const Vec3 &v, const Vec3 &n)
+{
+    return v - 2 * dot(v, n) * n;
+}
+
+// refraction of the unit vector uv at a surface with unit normal n,
+// split into the components perpendicular and parallel to the normal
+inline Vec3 refract(const Vec3 &uv, const Vec3 &n, double etai_over_etat)
+{
+    double cos_theta = fmin(dot(-uv, n), 1.0);
+    Vec3 r_out_perp = etai_over_etat * (uv + cos_theta * n);
+    Vec3 r_out_parallel = -sqrt(fabs(1.0 - r_out_perp.length_squared())) * n;
+    return r_out_perp + r_out_parallel;
+}
+
 // Vec3 member functions
 inline Vec3 &Vec3::operator+=(const Vec3 &v)
 {
@@ -181,11 +214,70 @@ inline Vec3 &Vec3::operator/=(const double t)
     return *this;
 }
 
+inline Vec3 Vec3::random()
+{
+    // every component in [0,1)
+    return Vec3(random_double(), random_double(), random_double());
+}
+
 inline Vec3 Vec3::random(double min, double max)
 {
     return Vec3(random_double(min, max), random_double(min, max), random_double(min, max));
 }
 
+/**
+ * @brief      Component-wise minimum of two vectors.
+ */
+inline Vec3 Vec3::min(const Vec3 &a, const Vec3 &b)
+{
+    return Vec3(fmin(a.e[0], b.e[0]), fmin(a.e[1], b.e[1]), fmin(a.e[2], b.e[2]));
+}
+
+/**
+ * @brief      Component-wise maximum of two vectors.
+ */
+inline Vec3 Vec3::max(const Vec3 &a, const Vec3 &b)
+{
+    return Vec3(fmax(a.e[0], b.e[0]), fmax(a.e[1], b.e[1]), fmax(a.e[2], b.e[2]));
+}
+
+/**
+ * @brief      Gets a component with a checked index, unlike operator[].
+ * @param[in]  i      The index, 0, 1 or 2 for x, y and z
+ * @param[out] value  The component, unchanged if the index is out of range
+ * @return     True if the index is valid, false otherwise.
+ */
+inline bool Vec3::get(int i, double &value) const
+{
+    if (i < 0 || i >= 3)
+        return false;
+    value = e[i];
+    return true;
+}
+
+/**
+ * @brief      Clamps every component between the components of lo and hi.
+ */
+inline Vec3 Vec3::clamp(const Vec3 &lo, const Vec3 &hi) const
+{
+    return Vec3::min(Vec3::max(*this, lo), hi);
+}
+
+/**
+ * @brief      Rotates the vector around an axis through the origin (Rodrigues' rotation formula).
+ *             A positive angle turns counterclockwise when looking against the axis.
+ * @param[in]  axis   The axis, does not need to be normalized
+ * @param[in]  angle  The angle in radians
+ * @return     The rotated vector.
+ */
+inline Vec3 Vec3::rotate_around(const Vec3 &axis, double angle) const
+{
+    Vec3 k = unit_vector(axis);
+    double cos_angle = std::cos(angle);
+    double sin_angle = std::sin(angle);
+    return *this * cos_angle + cross(k, *this) * sin_angle + k * dot(k, *this) * (1 - cos_angle);
+}
+
 inline Vec3 random_in_unit_sphere()
 {
     while (true)
@@ -226,4 +318,32 @@ inline Vec3 random_in_unit_disk()
     }
 }
 
+/**
+ * @brief      Maps two uniform numbers in [0, 1) to a uniform point in a regular
+ *             polygon inscribed in the unit circle, with one corner pointing up.
+ *             The first number selects the triangle between the center and one
+ *             edge, its remainder and the second number select the point inside.
+ * @param[in]  sides  The number of corners, at least 3
+ * @param[in]  r1     The first uniform number
+ * @param[in]  r2     The second uniform number
+ * @return     The point in the xy-plane.
+ */
+inline Vec3 polygon_sample(int sides, double r1, double r2)
+{
+    double scaled = r1 * sides;
+    int edge = std::min(static_cast<int>(scaled), sides - 1);
+    double along_edge = scaled - edge;
+    double angle0 = pi / 2 + 2 * pi * edge / sides;
+    double angle1 = pi / 2 + 2 * pi * (edge + 1) / sides;
+    Vec3 corner0(cos(angle0), sin(angle0), 0);
+    Vec3 corner1(cos(angle1), sin(angle1), 0);
+    // the square root spreads the points evenly between center and edge
+    return sqrt(r2) * ((1 - along_edge) * corner0 + along_edge * corner1);
+}
+
+inline Vec3 random_in_unit_polygon(int sides)
+{
+    return polygon_sample(sides, random_double(), random_double());
+}
+
 #endif
\ No newline at end of file